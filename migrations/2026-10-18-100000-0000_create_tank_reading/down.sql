@@ -0,0 +1,2 @@
+DROP TABLE tank_reading;
+ALTER TABLE tank DROP COLUMN atg_number;
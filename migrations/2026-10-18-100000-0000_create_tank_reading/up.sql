@@ -0,0 +1,17 @@
+-- Номер резервуару в рівнемірі (ATG)
+ALTER TABLE tank ADD COLUMN atg_number INTEGER;
+UPDATE tank SET atg_number = id + 1;
+
+CREATE TABLE tank_reading (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    tank_id INTEGER NOT NULL,
+    recorded_at BIGINT NOT NULL, -- Unix time, секунди
+    height DOUBLE NOT NULL, -- Висота продукту, мм
+    volume DOUBLE NOT NULL, -- Обсяг, л
+    water_height DOUBLE NOT NULL, -- Висота підтоварної води, мм
+    temperature DOUBLE NOT NULL, -- °C
+    source VARCHAR NOT NULL DEFAULT 'atg',
+    FOREIGN KEY (tank_id) REFERENCES tank(id)
+);
+
+CREATE INDEX tank_reading_tank_time ON tank_reading (tank_id, recorded_at);
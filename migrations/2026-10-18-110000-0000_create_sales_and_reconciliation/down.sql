@@ -0,0 +1,4 @@
+DROP TABLE variance_alert;
+ALTER TABLE tank DROP COLUMN variance_threshold;
+DROP TABLE stock_movement;
+DROP TABLE sale;
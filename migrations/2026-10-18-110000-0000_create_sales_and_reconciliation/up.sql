@@ -0,0 +1,39 @@
+CREATE TABLE sale (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    customer_id INTEGER NOT NULL,
+    fuel_id INTEGER NOT NULL,
+    amount INTEGER NOT NULL,
+    total BIGINT NOT NULL, -- У копійках
+    created_at BIGINT NOT NULL,
+    FOREIGN KEY (customer_id) REFERENCES customer(id),
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
+
+-- Рух пального по резервуарах: додатні значення — надходження, від'ємні — продаж
+CREATE TABLE stock_movement (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    tank_id INTEGER NOT NULL,
+    kind VARCHAR NOT NULL, -- 'sale' | 'delivery'
+    quantity INTEGER NOT NULL,
+    sale_id INTEGER,
+    created_at BIGINT NOT NULL,
+    FOREIGN KEY (tank_id) REFERENCES tank(id),
+    FOREIGN KEY (sale_id) REFERENCES sale(id)
+);
+
+CREATE INDEX stock_movement_tank_time ON stock_movement (tank_id, created_at);
+
+-- Допустима розбіжність при звірці, л
+ALTER TABLE tank ADD COLUMN variance_threshold INTEGER NOT NULL DEFAULT 50;
+
+CREATE TABLE variance_alert (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    tank_id INTEGER NOT NULL,
+    day VARCHAR NOT NULL, -- YYYY-MM-DD
+    variance DOUBLE NOT NULL,
+    threshold INTEGER NOT NULL,
+    created_at BIGINT NOT NULL,
+    acknowledged BOOLEAN NOT NULL DEFAULT 0,
+    FOREIGN KEY (tank_id) REFERENCES tank(id),
+    UNIQUE (tank_id, day)
+);
@@ -0,0 +1,6 @@
+ALTER TABLE tank_reading DROP COLUMN net_volume;
+ALTER TABLE stock_movement DROP COLUMN temperature;
+ALTER TABLE stock_movement DROP COLUMN net_quantity;
+ALTER TABLE sale DROP COLUMN net_amount;
+ALTER TABLE fuel DROP COLUMN expansion_coeff;
+ALTER TABLE fuel DROP COLUMN density;
@@ -0,0 +1,14 @@
+-- Густина при 15 °C (кг/м³) та коефіцієнт об'ємного розширення (1/°C).
+-- Якщо коефіцієнт не задано, він розраховується за ASTM D1250 (таблиця 54B).
+ALTER TABLE fuel ADD COLUMN density DOUBLE;
+ALTER TABLE fuel ADD COLUMN expansion_coeff DOUBLE;
+
+UPDATE fuel SET density = 745.0 WHERE fuel_type = 'petrol' OR fuel_type IS NULL;
+UPDATE fuel SET density = 835.0 WHERE fuel_type = 'diesel';
+UPDATE fuel SET density = 540.0, expansion_coeff = 0.0025 WHERE fuel_type = 'gas';
+
+-- Об'єми, приведені до 15 °C (net), поряд із фактичними (gross)
+ALTER TABLE sale ADD COLUMN net_amount DOUBLE;
+ALTER TABLE stock_movement ADD COLUMN net_quantity DOUBLE;
+ALTER TABLE stock_movement ADD COLUMN temperature DOUBLE;
+ALTER TABLE tank_reading ADD COLUMN net_volume DOUBLE;
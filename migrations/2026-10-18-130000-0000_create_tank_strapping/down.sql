@@ -0,0 +1 @@
+DROP TABLE tank_strapping;
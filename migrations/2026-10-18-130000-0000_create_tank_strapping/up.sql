@@ -0,0 +1,9 @@
+-- Градуювальна таблиця резервуару: висота продукту (мм) -> об'єм (л)
+CREATE TABLE tank_strapping (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    tank_id INTEGER NOT NULL,
+    height_mm INTEGER NOT NULL,
+    volume DOUBLE NOT NULL,
+    FOREIGN KEY (tank_id) REFERENCES tank(id),
+    UNIQUE (tank_id, height_mm)
+);
@@ -0,0 +1,4 @@
+ALTER TABLE stock_movement DROP COLUMN delivery_id;
+DROP TABLE delivery;
+DROP TABLE purchase_order;
+DROP TABLE supplier;
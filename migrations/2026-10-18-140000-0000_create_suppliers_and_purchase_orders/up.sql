@@ -0,0 +1,37 @@
+CREATE TABLE supplier (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR NOT NULL UNIQUE,
+    contact VARCHAR,
+    created_at BIGINT NOT NULL
+);
+
+-- status: 'open' | 'partial' | 'delivered' | 'closed_short' | 'cancelled'
+CREATE TABLE purchase_order (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    supplier_id INTEGER NOT NULL,
+    fuel_id INTEGER NOT NULL,
+    ordered_qty INTEGER NOT NULL,
+    delivered_qty INTEGER NOT NULL DEFAULT 0,
+    unit_cost BIGINT NOT NULL, -- Закупівельна ціна за літр у копійках
+    status VARCHAR NOT NULL DEFAULT 'open',
+    created_at BIGINT NOT NULL,
+    closed_at BIGINT,
+    FOREIGN KEY (supplier_id) REFERENCES supplier(id),
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
+
+CREATE TABLE delivery (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    order_id INTEGER NOT NULL,
+    fuel_id INTEGER NOT NULL,
+    quantity INTEGER NOT NULL,
+    unit_cost BIGINT NOT NULL,
+    total_cost BIGINT NOT NULL,
+    temperature DOUBLE,
+    net_quantity DOUBLE,
+    created_at BIGINT NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES purchase_order(id),
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
+
+ALTER TABLE stock_movement ADD COLUMN delivery_id INTEGER REFERENCES delivery(id);
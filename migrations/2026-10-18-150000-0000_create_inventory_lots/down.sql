@@ -0,0 +1,3 @@
+ALTER TABLE sale DROP COLUMN cogs;
+DROP INDEX inventory_lot_open;
+DROP TABLE inventory_lot;
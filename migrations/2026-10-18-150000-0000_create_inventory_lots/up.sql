@@ -0,0 +1,23 @@
+-- Партії пального в резервуарі із закупівельною ціною (списуються FIFO)
+CREATE TABLE inventory_lot (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    tank_id INTEGER NOT NULL,
+    delivery_id INTEGER,
+    unit_cost BIGINT NOT NULL, -- копійки за літр
+    quantity INTEGER NOT NULL,
+    remaining INTEGER NOT NULL,
+    created_at BIGINT NOT NULL,
+    FOREIGN KEY (tank_id) REFERENCES tank(id),
+    FOREIGN KEY (delivery_id) REFERENCES delivery(id)
+);
+
+CREATE INDEX inventory_lot_open ON inventory_lot(tank_id, remaining);
+
+-- Собівартість проданого пального, копійки
+ALTER TABLE sale ADD COLUMN cogs BIGINT;
+
+-- Початкові партії для наявних залишків за старим правилом (половина роздрібної ціни)
+INSERT INTO inventory_lot (tank_id, delivery_id, unit_cost, quantity, remaining, created_at)
+SELECT tank.id, NULL, fuel.price / 2, tank.stored, tank.stored, CAST(strftime('%s', 'now') AS INTEGER)
+FROM tank JOIN fuel ON fuel.id = tank.fuelid
+WHERE tank.stored > 0 AND fuel.fuel_type != 'electricity';
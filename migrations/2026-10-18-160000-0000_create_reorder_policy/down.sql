@@ -0,0 +1 @@
+DROP TABLE reorder_policy;
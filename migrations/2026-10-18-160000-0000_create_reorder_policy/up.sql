@@ -0,0 +1,17 @@
+-- Точка перезамовлення та цільовий рівень пального (літри на всі резервуари),
+-- а також останній прогноз споживання, розрахований фоновою задачею
+CREATE TABLE reorder_policy (
+    fuel_id INTEGER PRIMARY KEY NOT NULL,
+    reorder_point INTEGER NOT NULL,
+    target_level INTEGER NOT NULL,
+    avg_daily_sales DOUBLE,
+    days_until_empty DOUBLE,
+    forecast_at BIGINT,
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
+
+INSERT INTO reorder_policy (fuel_id, reorder_point, target_level)
+SELECT fuel.id, SUM(tank.capacity) / 4, SUM(tank.capacity) * 9 / 10
+FROM fuel JOIN tank ON tank.fuelid = fuel.id
+WHERE fuel.fuel_type != 'electricity'
+GROUP BY fuel.id;
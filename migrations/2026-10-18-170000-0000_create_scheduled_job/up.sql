@@ -0,0 +1,12 @@
+-- Стан фонових задач планувальника (рядок створюється при першій реєстрації задачі)
+CREATE TABLE scheduled_job (
+    name VARCHAR PRIMARY KEY NOT NULL,
+    schedule VARCHAR NOT NULL,
+    last_started_at BIGINT,
+    last_finished_at BIGINT,
+    last_status VARCHAR, -- 'ok' | 'error' | 'skipped'
+    last_message VARCHAR,
+    last_error VARCHAR,
+    last_error_at BIGINT,
+    run_count INTEGER NOT NULL DEFAULT 0
+);
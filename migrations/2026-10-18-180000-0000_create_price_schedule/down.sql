@@ -0,0 +1,5 @@
+DROP TABLE price_rule;
+DROP TABLE price_schedule;
+DROP INDEX price_history_fuel;
+DROP TABLE price_history;
+ALTER TABLE fuel DROP COLUMN base_price;
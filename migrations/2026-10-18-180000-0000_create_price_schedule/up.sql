@@ -0,0 +1,46 @@
+-- Базова ціна (встановлена вручну або за розкладом); fuel.price — діюча ціна
+-- з урахуванням правил за часом доби
+ALTER TABLE fuel ADD COLUMN base_price BIGINT NOT NULL DEFAULT 0;
+UPDATE fuel SET base_price = price;
+
+-- Історія діючих цін
+-- source: 'initial' | 'manual' | 'schedule' | 'time_of_day'
+CREATE TABLE price_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    fuel_id INTEGER NOT NULL,
+    price BIGINT NOT NULL,
+    changed_at BIGINT NOT NULL,
+    source VARCHAR NOT NULL,
+    changed_by VARCHAR,
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
+
+CREATE INDEX price_history_fuel ON price_history(fuel_id, changed_at);
+
+INSERT INTO price_history (fuel_id, price, changed_at, source)
+SELECT id, price, CAST(strftime('%s', 'now') AS INTEGER), 'initial' FROM fuel;
+
+-- Заплановані зміни базової ціни
+CREATE TABLE price_schedule (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    fuel_id INTEGER NOT NULL,
+    price BIGINT NOT NULL,
+    effective_at BIGINT NOT NULL,
+    created_by VARCHAR NOT NULL,
+    created_at BIGINT NOT NULL,
+    applied_at BIGINT,
+    cancelled BOOLEAN NOT NULL DEFAULT 0,
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
+
+-- Ціна за часом доби: діє з start_minute до end_minute (хвилини від півночі,
+-- інтервал може переходити через північ)
+CREATE TABLE price_rule (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    fuel_id INTEGER NOT NULL,
+    start_minute INTEGER NOT NULL,
+    end_minute INTEGER NOT NULL,
+    price BIGINT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
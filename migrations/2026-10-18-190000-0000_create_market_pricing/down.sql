@@ -0,0 +1,2 @@
+DROP TABLE price_proposal;
+DROP TABLE market_pricing_rule;
@@ -0,0 +1,26 @@
+-- Правило ринкового ціноутворення: середня ринкова ціна + зсув (копійки, може бути
+-- від'ємним), але не нижче собівартості + мінімальної націнки
+CREATE TABLE market_pricing_rule (
+    fuel_id INTEGER PRIMARY KEY NOT NULL,
+    market_offset BIGINT NOT NULL,
+    margin_floor BIGINT NOT NULL,
+    auto_apply BOOLEAN NOT NULL DEFAULT 0,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
+
+-- status: 'pending' | 'applied' | 'rejected' | 'superseded'
+CREATE TABLE price_proposal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    fuel_id INTEGER NOT NULL,
+    current_price BIGINT NOT NULL,
+    proposed_price BIGINT NOT NULL,
+    market_average BIGINT NOT NULL,
+    cost_basis BIGINT,
+    floor_applied BOOLEAN NOT NULL,
+    status VARCHAR NOT NULL DEFAULT 'pending',
+    created_at BIGINT NOT NULL,
+    decided_by VARCHAR,
+    decided_at BIGINT,
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
@@ -0,0 +1,4 @@
+DROP INDEX market_quote_snapshot;
+DROP TABLE market_quote;
+DROP INDEX market_snapshot_source;
+DROP TABLE market_snapshot;
@@ -0,0 +1,19 @@
+-- Знімки ринкових цін (кожне завантаження джерела) — також кеш для fetch_fuel_prices
+CREATE TABLE market_snapshot (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    source VARCHAR NOT NULL,
+    fetched_at BIGINT NOT NULL
+);
+
+CREATE INDEX market_snapshot_source ON market_snapshot(source, fetched_at);
+
+-- Ціна однієї АЗС на пальне у знімку, грн/л
+CREATE TABLE market_quote (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    snapshot_id INTEGER NOT NULL,
+    fuel VARCHAR NOT NULL,
+    price DOUBLE NOT NULL,
+    FOREIGN KEY (snapshot_id) REFERENCES market_snapshot(id)
+);
+
+CREATE INDEX market_quote_snapshot ON market_quote(snapshot_id);
@@ -0,0 +1 @@
+ALTER TABLE market_quote DROP COLUMN brand;
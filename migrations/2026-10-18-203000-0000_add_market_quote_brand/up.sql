@@ -0,0 +1,2 @@
+-- Мережа АЗС (brand) для цін у знімках; старі знімки лишаються без мережі
+ALTER TABLE market_quote ADD COLUMN brand VARCHAR NOT NULL DEFAULT '';
@@ -0,0 +1,5 @@
+DROP INDEX notification_customer;
+DROP TABLE notification;
+DROP INDEX price_alert_active;
+DROP TABLE price_alert;
+ALTER TABLE customer DROP COLUMN email;
@@ -0,0 +1,34 @@
+-- Адреса для сповіщень поштою (необов'язкова)
+ALTER TABLE customer ADD COLUMN email VARCHAR;
+
+-- Підписка клієнта на подію по пальному.
+-- kind: 'price_below' (threshold — ціна в копійках) | 'available' (пальне/зарядка знову доступні).
+-- Після спрацювання підписка вимикається, клієнт може поновити її вручну.
+CREATE TABLE price_alert (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    customer_id INTEGER NOT NULL,
+    fuel_id INTEGER NOT NULL,
+    kind VARCHAR NOT NULL,
+    threshold BIGINT,
+    active BOOLEAN NOT NULL DEFAULT 1,
+    created_at BIGINT NOT NULL,
+    triggered_at BIGINT,
+    FOREIGN KEY (customer_id) REFERENCES customer(id),
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
+
+CREATE INDEX price_alert_active ON price_alert(fuel_id, active);
+
+-- Центр сповіщень клієнта
+CREATE TABLE notification (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    customer_id INTEGER NOT NULL,
+    alert_id INTEGER,
+    message VARCHAR NOT NULL,
+    created_at BIGINT NOT NULL,
+    read_at BIGINT,
+    FOREIGN KEY (customer_id) REFERENCES customer(id),
+    FOREIGN KEY (alert_id) REFERENCES price_alert(id)
+);
+
+CREATE INDEX notification_customer ON notification(customer_id, created_at);
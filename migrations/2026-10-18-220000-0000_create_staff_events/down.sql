@@ -0,0 +1,5 @@
+DROP TABLE staff_channel;
+DROP INDEX staff_event_created;
+DROP INDEX staff_event_open_key;
+DROP TABLE staff_event;
+ALTER TABLE tank DROP COLUMN low_stock_threshold;
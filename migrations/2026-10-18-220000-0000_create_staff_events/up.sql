@@ -0,0 +1,37 @@
+-- Поріг низького залишку резервуара в літрах (NULL — 20% місткості)
+ALTER TABLE tank ADD COLUMN low_stock_threshold INTEGER;
+
+-- Події для персоналу станції.
+-- kind: 'low_stock' | 'charger_offline' | 'refill_failed' | 'variance'; severity: 'warning' | 'critical'.
+-- Повторна подія з тим самим dedup_key, поки попередня не вирішена, лише збільшує occurrences.
+-- dispatched_at — коли подію передано в канали (NULL — ще в черзі).
+CREATE TABLE staff_event (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    kind VARCHAR NOT NULL,
+    severity VARCHAR NOT NULL,
+    dedup_key VARCHAR NOT NULL,
+    message VARCHAR NOT NULL,
+    tank_id INTEGER,
+    fuel_id INTEGER,
+    occurrences INTEGER NOT NULL DEFAULT 1,
+    created_at BIGINT NOT NULL,
+    last_seen_at BIGINT NOT NULL,
+    dispatched_at BIGINT,
+    resolved_at BIGINT,
+    acknowledged_by VARCHAR,
+    acknowledged_at BIGINT,
+    FOREIGN KEY (tank_id) REFERENCES tank(id),
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
+
+CREATE UNIQUE INDEX staff_event_open_key ON staff_event(dedup_key) WHERE resolved_at IS NULL;
+CREATE INDEX staff_event_created ON staff_event(created_at);
+
+-- Канали доставки подій: kind 'email' (target — адреса) | 'webhook' (target — URL)
+CREATE TABLE staff_channel (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    kind VARCHAR NOT NULL,
+    target VARCHAR NOT NULL,
+    min_severity VARCHAR NOT NULL DEFAULT 'warning',
+    enabled BOOLEAN NOT NULL DEFAULT 1
+);
@@ -0,0 +1,3 @@
+DROP INDEX webhook_delivery_due;
+DROP TABLE webhook_delivery;
+DROP TABLE webhook_subscription;
@@ -0,0 +1,29 @@
+-- Підписки зовнішніх систем (ERP, BI) на події станції.
+-- events — список подій через кому або '*' (усі); secret — ключ підпису HMAC-SHA256.
+CREATE TABLE webhook_subscription (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    url VARCHAR NOT NULL,
+    secret VARCHAR NOT NULL,
+    events VARCHAR NOT NULL DEFAULT '*',
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    created_at BIGINT NOT NULL
+);
+
+-- Черга та журнал доставок. status: 'pending' | 'delivered' | 'failed'.
+-- Запис створюється в тій самій транзакції, що й подія, тож подія не губиться при збої.
+CREATE TABLE webhook_delivery (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    subscription_id INTEGER NOT NULL,
+    event VARCHAR NOT NULL,
+    payload VARCHAR NOT NULL,
+    status VARCHAR NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at BIGINT NOT NULL,
+    last_status_code INTEGER,
+    last_error VARCHAR,
+    created_at BIGINT NOT NULL,
+    delivered_at BIGINT,
+    FOREIGN KEY (subscription_id) REFERENCES webhook_subscription(id)
+);
+
+CREATE INDEX webhook_delivery_due ON webhook_delivery(status, next_attempt_at);
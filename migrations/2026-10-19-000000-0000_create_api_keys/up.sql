@@ -0,0 +1,22 @@
+-- API-ключі для машинних клієнтів (скрипти, паливні картки автопарків).
+-- Ключ показується один раз: "gs_<prefix>_<secret>"; зберігається лише SHA-256 від secret.
+-- scopes — дозволи через кому: prices:read, stock:read, purchases:create.
+-- customer_id — рахунок автопарку, з якого списуються покупки (обов'язковий для purchases:create).
+-- Після ротації попередній секрет діє до previous_valid_until, щоб інтеграцію встигли переналаштувати.
+CREATE TABLE api_key (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR NOT NULL,
+    prefix VARCHAR NOT NULL UNIQUE,
+    key_hash VARCHAR NOT NULL,
+    previous_hash VARCHAR,
+    previous_valid_until BIGINT,
+    scopes VARCHAR NOT NULL,
+    customer_id INTEGER,
+    rate_limit INTEGER NOT NULL DEFAULT 60,
+    created_by VARCHAR NOT NULL,
+    created_at BIGINT NOT NULL,
+    rotated_at BIGINT,
+    last_used_at BIGINT,
+    revoked_at BIGINT,
+    FOREIGN KEY (customer_id) REFERENCES customer(id)
+);
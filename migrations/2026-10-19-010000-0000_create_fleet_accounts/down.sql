@@ -0,0 +1,5 @@
+DROP INDEX fleet_ledger_driver;
+DROP INDEX fleet_ledger_company;
+DROP TABLE fleet_ledger;
+DROP TABLE fleet_driver;
+DROP TABLE fleet_company;
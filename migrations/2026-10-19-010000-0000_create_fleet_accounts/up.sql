@@ -0,0 +1,43 @@
+-- Корпоративні рахунки автопарків. Баланс (копійки) належить компанії, водії купують
+-- пальне зі своїх логінів клієнта, а списання йде з рахунку компанії.
+CREATE TABLE fleet_company (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR NOT NULL UNIQUE,
+    balance BIGINT NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL
+);
+
+-- Водій компанії: логін клієнта та (за наявності) номер паливної картки.
+-- Ліміти в копійках, NULL — без ліміту; allowed_fuel_types — типи пального через кому, NULL — усі.
+CREATE TABLE fleet_driver (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    company_id INTEGER NOT NULL,
+    customer_id INTEGER NOT NULL UNIQUE,
+    card_number VARCHAR UNIQUE,
+    daily_limit BIGINT,
+    monthly_limit BIGINT,
+    allowed_fuel_types VARCHAR,
+    active BOOLEAN NOT NULL DEFAULT 1,
+    created_at BIGINT NOT NULL,
+    FOREIGN KEY (company_id) REFERENCES fleet_company(id),
+    FOREIGN KEY (customer_id) REFERENCES customer(id)
+);
+
+-- Рух коштів компанії: kind 'topup' (amount > 0) або 'purchase' (amount < 0, з продажем і водієм).
+-- Основа для лімітів водіїв та місячних виписок.
+CREATE TABLE fleet_ledger (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    company_id INTEGER NOT NULL,
+    driver_id INTEGER,
+    sale_id INTEGER,
+    kind VARCHAR NOT NULL,
+    amount BIGINT NOT NULL,
+    note VARCHAR,
+    created_at BIGINT NOT NULL,
+    FOREIGN KEY (company_id) REFERENCES fleet_company(id),
+    FOREIGN KEY (driver_id) REFERENCES fleet_driver(id),
+    FOREIGN KEY (sale_id) REFERENCES sale(id)
+);
+
+CREATE INDEX fleet_ledger_company ON fleet_ledger(company_id, created_at);
+CREATE INDEX fleet_ledger_driver ON fleet_ledger(driver_id, created_at);
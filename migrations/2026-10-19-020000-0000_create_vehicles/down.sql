@@ -0,0 +1,3 @@
+DROP INDEX sale_vehicle;
+ALTER TABLE sale DROP COLUMN vehicle_id;
+DROP TABLE vehicle;
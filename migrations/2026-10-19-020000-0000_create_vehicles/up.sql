@@ -0,0 +1,17 @@
+-- Авто клієнта: тип пального (petrol, diesel, gas, electricity) та об'єм бака
+-- в літрах або ємність батареї в кВт·год. Номер зберігається без пробілів, великими літерами.
+CREATE TABLE vehicle (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    customer_id INTEGER NOT NULL,
+    plate VARCHAR NOT NULL,
+    fuel_type VARCHAR NOT NULL,
+    tank_capacity INTEGER NOT NULL,
+    created_at BIGINT NOT NULL,
+    FOREIGN KEY (customer_id) REFERENCES customer(id),
+    UNIQUE (customer_id, plate)
+);
+
+-- Авто, для якого куплено пальне (NULL — без вибору авто)
+ALTER TABLE sale ADD COLUMN vehicle_id INTEGER REFERENCES vehicle(id);
+
+CREATE INDEX sale_vehicle ON sale(vehicle_id, created_at);
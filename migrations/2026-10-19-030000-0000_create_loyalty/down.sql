@@ -0,0 +1,3 @@
+DROP INDEX loyalty_entry_customer;
+DROP TABLE loyalty_entry;
+DROP TABLE loyalty_tier;
@@ -0,0 +1,32 @@
+-- Рівні програми лояльності. Рівень клієнта — найвищий, чий min_spent (копійки) не більший
+-- за суму його покупок за останні 365 днів. Нарахування: points_per_litre за кожен літр
+-- (кВт·год) і points_per_100_uah за кожні сплачені 100 грн. 1 бал = 1 грн при оплаті.
+CREATE TABLE loyalty_tier (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR NOT NULL UNIQUE,
+    min_spent BIGINT NOT NULL UNIQUE,
+    points_per_litre INTEGER NOT NULL DEFAULT 0,
+    points_per_100_uah INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT INTO loyalty_tier (name, min_spent, points_per_litre, points_per_100_uah) VALUES
+    ('Базовий', 0, 0, 1),
+    ('Срібний', 1000000, 0, 2),
+    ('Золотий', 3000000, 0, 3);
+
+-- Рух балів клієнта: kind 'earn' (points > 0; remaining — ще не списані й не згорілі,
+-- expires_at — коли згорають), 'redeem' і 'expire' (points < 0).
+CREATE TABLE loyalty_entry (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    customer_id INTEGER NOT NULL,
+    kind VARCHAR NOT NULL,
+    points BIGINT NOT NULL,
+    remaining BIGINT NOT NULL DEFAULT 0,
+    expires_at BIGINT,
+    sale_id INTEGER,
+    created_at BIGINT NOT NULL,
+    FOREIGN KEY (customer_id) REFERENCES customer(id),
+    FOREIGN KEY (sale_id) REFERENCES sale(id)
+);
+
+CREATE INDEX loyalty_entry_customer ON loyalty_entry(customer_id, created_at);
@@ -0,0 +1,5 @@
+ALTER TABLE sale DROP COLUMN promotion_id;
+ALTER TABLE sale DROP COLUMN discount;
+DROP INDEX promotion_use_customer;
+DROP TABLE promotion_use;
+DROP TABLE promotion;
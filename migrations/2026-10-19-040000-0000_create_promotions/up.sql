@@ -0,0 +1,41 @@
+-- Акції та купони. code NULL — акція діє автоматично, інакше — лише з купоном.
+-- kind 'percent' (value — відсоток) або 'per_litre' (value — копійок знижки за літр).
+-- fuel_id NULL — на все пальне; min_quantity — мінімум літрів акційного пального в покупці;
+-- happy_hour_start/end — хвилини від початку доби (місцевий час), вікно може переходити
+-- через північ; per_customer_limit — скільки покупок клієнт може зробити за акцією.
+CREATE TABLE promotion (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name VARCHAR NOT NULL,
+    code VARCHAR UNIQUE,
+    kind VARCHAR NOT NULL,
+    value INTEGER NOT NULL,
+    fuel_id INTEGER,
+    min_quantity INTEGER,
+    happy_hour_start INTEGER,
+    happy_hour_end INTEGER,
+    valid_from BIGINT,
+    valid_until BIGINT,
+    per_customer_limit INTEGER,
+    active BOOLEAN NOT NULL DEFAULT 1,
+    created_at BIGINT NOT NULL,
+    FOREIGN KEY (fuel_id) REFERENCES fuel(id)
+);
+
+-- Використання акції: одна покупка клієнта (перший продаж покупки) і сума знижки
+CREATE TABLE promotion_use (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    promotion_id INTEGER NOT NULL,
+    customer_id INTEGER NOT NULL,
+    sale_id INTEGER,
+    discount BIGINT NOT NULL,
+    created_at BIGINT NOT NULL,
+    FOREIGN KEY (promotion_id) REFERENCES promotion(id),
+    FOREIGN KEY (customer_id) REFERENCES customer(id),
+    FOREIGN KEY (sale_id) REFERENCES sale(id)
+);
+
+CREATE INDEX promotion_use_customer ON promotion_use(promotion_id, customer_id);
+
+-- Знижка на рядку продажу: total лишається ціною без знижки, сплачено total - discount
+ALTER TABLE sale ADD COLUMN discount BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE sale ADD COLUMN promotion_id INTEGER REFERENCES promotion(id);
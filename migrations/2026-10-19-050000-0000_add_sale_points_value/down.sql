@@ -0,0 +1 @@
+ALTER TABLE sale DROP COLUMN points_value;
@@ -0,0 +1,9 @@
+-- Частина суми продажу, оплачена балами лояльності (копійки): грошей за неї не надходить
+ALTER TABLE sale ADD COLUMN points_value BIGINT NOT NULL DEFAULT 0;
+
+-- Досі бали прив'язувались до першого продажу покупки (1 бал = 100 копійок)
+UPDATE sale SET points_value = (
+    SELECT -SUM(points) * 100 FROM loyalty_entry
+    WHERE loyalty_entry.kind = 'redeem' AND loyalty_entry.sale_id = sale.id
+)
+WHERE id IN (SELECT sale_id FROM loyalty_entry WHERE kind = 'redeem' AND sale_id IS NOT NULL);
@@ -0,0 +1,76 @@
+// Підписки клієнтів на ціну та наявність пального. Перевіряються після змін ціни та
+// залишків; спрацьована підписка створює запис у центрі сповіщень, вимикається і,
+// якщо в клієнта вказана пошта, повертає лист для надсилання.
+
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+
+use crate::models::PriceAlert;
+use crate::notify::Message;
+use crate::schema::{customer, fuel, notification, price_alert, tank};
+use crate::utils::alert_triggered;
+
+pub const KIND_PRICE_BELOW: &str = "price_below";
+pub const KIND_AVAILABLE: &str = "available";
+
+fn alert_message(alert: &PriceAlert, fuel_name: &str, price: i64) -> String {
+    let fmt = |cents: i64| format!("{:.2} грн", cents as f64 / 100.0);
+    match alert.kind.as_str() {
+        KIND_PRICE_BELOW => format!(
+            "{} подешевшало до {} (ваш поріг {})",
+            fuel_name,
+            fmt(price),
+            fmt(alert.threshold.unwrap_or_default())
+        ),
+        _ => format!("{} знову доступне, ціна {}", fuel_name, fmt(price)),
+    }
+}
+
+/// Перевіряє всі активні підписки. Повертає листи для клієнтів із вказаною поштою.
+pub fn evaluate(conn: &mut SqliteConnection) -> QueryResult<Vec<Message>> {
+    let alerts: Vec<(PriceAlert, String, i64, Option<String>)> = price_alert::table
+        .inner_join(fuel::table)
+        .inner_join(customer::table)
+        .filter(price_alert::active.eq(true))
+        .order(price_alert::id)
+        .select((PriceAlert::as_select(), fuel::name, fuel::price, customer::email))
+        .load(conn)?;
+
+    let now = chrono::Utc::now().timestamp();
+    let mut messages = Vec::new();
+    for (alert, fuel_name, price, email) in alerts {
+        let stored: i32 = tank::table
+            .filter(tank::fuelid.eq(alert.fuel_id))
+            .select(tank::stored)
+            .load::<i32>(conn)?
+            .iter()
+            .sum();
+        if !alert_triggered(&alert.kind, alert.threshold, price, stored > 0) {
+            continue;
+        }
+
+        let text = alert_message(&alert, &fuel_name, price);
+        conn.transaction(|conn| {
+            diesel::update(price_alert::table.find(alert.id))
+                .set((price_alert::active.eq(false), price_alert::triggered_at.eq(Some(now))))
+                .execute(conn)?;
+            diesel::insert_into(notification::table)
+                .values((
+                    notification::customer_id.eq(alert.customer_id),
+                    notification::alert_id.eq(Some(alert.id)),
+                    notification::message.eq(&text),
+                    notification::created_at.eq(now),
+                ))
+                .execute(conn)
+        })?;
+
+        if let Some(to) = email.filter(|e| !e.is_empty()) {
+            messages.push(Message {
+                to,
+                subject: format!("GasStation: {}", fuel_name),
+                body: text,
+            });
+        }
+    }
+    Ok(messages)
+}
@@ -0,0 +1,168 @@
+// API-ключі для машинних клієнтів: скрипти, інтеграції автопарків.
+//
+// Ключ має вигляд "gs_<prefix>_<secret>". Префікс зберігається відкрито — за ним ключ
+// шукається і впізнається в адмінці; від секрету зберігається лише SHA-256. Повний ключ
+// показується один раз: при створенні та при ротації.
+//
+// Дозволи (scopes): prices:read, stock:read, purchases:create. У кожного ключа свій ліміт
+// запитів на хвилину. Після ротації попередній секрет діє ще ROTATION_GRACE_SECS, щоб
+// інтеграцію встигли переналаштувати; відкликаний ключ перестає діяти одразу.
+
+use std::time::Duration;
+
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+use sha2::{Digest, Sha256};
+
+use crate::models::ApiKey;
+use crate::rate_limit::check_rate_limit;
+use crate::schema::api_key;
+use crate::utils::{has_scope, split_api_key};
+
+pub const SCOPE_PRICES_READ: &str = "prices:read";
+pub const SCOPE_STOCK_READ: &str = "stock:read";
+pub const SCOPE_PURCHASES_CREATE: &str = "purchases:create";
+
+/// Усі дозволи, які можна видати ключу
+pub const SCOPES: [&str; 3] = [SCOPE_PRICES_READ, SCOPE_STOCK_READ, SCOPE_PURCHASES_CREATE];
+
+pub const ROTATION_GRACE_SECS: i64 = 24 * 3600;
+
+#[derive(Debug, PartialEq)]
+pub enum KeyError {
+    Invalid,
+    Revoked,
+    MissingScope(String),
+    RateLimited,
+    Database(String),
+}
+
+impl std::fmt::Display for KeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyError::Invalid => write!(f, "Недійсний API-ключ"),
+            KeyError::Revoked => write!(f, "API-ключ відкликано"),
+            KeyError::MissingScope(scope) => write!(f, "Ключ не має дозволу {}", scope),
+            KeyError::RateLimited => write!(f, "Занадто багато запитів для цього ключа. Спробуйте пізніше."),
+            KeyError::Database(e) => write!(f, "Помилка бази даних: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+fn hash_secret(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn random_hex(len: usize) -> String {
+    let mut buf = vec![0u8; len];
+    getrandom::fill(&mut buf).unwrap_or(());
+    buf.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Створює ключ. Повертає запис і повний ключ (показати один раз).
+pub fn issue(
+    conn: &mut SqliteConnection,
+    name: &str,
+    scopes: &[String],
+    customer_id: Option<i32>,
+    rate_limit: i32,
+    created_by: &str,
+) -> QueryResult<(ApiKey, String)> {
+    let prefix = random_hex(4);
+    let secret = random_hex(20);
+    let key = diesel::insert_into(api_key::table)
+        .values((
+            api_key::name.eq(name),
+            api_key::prefix.eq(&prefix),
+            api_key::key_hash.eq(hash_secret(&secret)),
+            api_key::scopes.eq(scopes.join(",")),
+            api_key::customer_id.eq(customer_id),
+            api_key::rate_limit.eq(rate_limit),
+            api_key::created_by.eq(created_by),
+            api_key::created_at.eq(chrono::Utc::now().timestamp()),
+        ))
+        .returning(ApiKey::as_returning())
+        .get_result(conn)?;
+    Ok((key, format!("gs_{}_{}", prefix, secret)))
+}
+
+/// Новий секрет для ключа; попередній діє ще ROTATION_GRACE_SECS.
+/// None — ключ не знайдено або відкликано.
+pub fn rotate(conn: &mut SqliteConnection, key_id: i32, now: i64) -> QueryResult<Option<String>> {
+    conn.transaction(|conn| {
+        let current: Option<(String, String)> = api_key::table
+            .find(key_id)
+            .filter(api_key::revoked_at.is_null())
+            .select((api_key::prefix, api_key::key_hash))
+            .first(conn)
+            .optional()?;
+        let Some((prefix, old_hash)) = current else {
+            return Ok(None);
+        };
+
+        let secret = random_hex(20);
+        diesel::update(api_key::table.find(key_id))
+            .set((
+                api_key::key_hash.eq(hash_secret(&secret)),
+                api_key::previous_hash.eq(Some(old_hash)),
+                api_key::previous_valid_until.eq(Some(now + ROTATION_GRACE_SECS)),
+                api_key::rotated_at.eq(Some(now)),
+            ))
+            .execute(conn)?;
+        Ok(Some(format!("gs_{}_{}", prefix, secret)))
+    })
+}
+
+/// Відкликає ключ разом із попереднім секретом
+pub fn revoke(conn: &mut SqliteConnection, key_id: i32, now: i64) -> QueryResult<usize> {
+    diesel::update(api_key::table.find(key_id).filter(api_key::revoked_at.is_null()))
+        .set((
+            api_key::revoked_at.eq(Some(now)),
+            api_key::previous_valid_until.eq(None::<i64>),
+        ))
+        .execute(conn)
+}
+
+/// Перевіряє ключ, дозвіл і ліміт запитів, відмічає час використання
+pub fn authenticate(conn: &mut SqliteConnection, raw: &str, scope: &str, now: i64) -> Result<ApiKey, KeyError> {
+    let (prefix, secret) = split_api_key(raw).ok_or(KeyError::Invalid)?;
+    let db = |e: diesel::result::Error| KeyError::Database(e.to_string());
+
+    let (key, key_hash, previous_hash): (ApiKey, String, Option<String>) = api_key::table
+        .filter(api_key::prefix.eq(prefix))
+        .select((ApiKey::as_select(), api_key::key_hash, api_key::previous_hash))
+        .first(conn)
+        .optional()
+        .map_err(db)?
+        .ok_or(KeyError::Invalid)?;
+
+    let hash = hash_secret(secret);
+    let previous_valid = key.previous_valid_until.is_some_and(|until| now < until);
+    if hash != key_hash && !(previous_valid && previous_hash.as_deref() == Some(hash.as_str())) {
+        return Err(KeyError::Invalid);
+    }
+    if key.revoked_at.is_some() {
+        return Err(KeyError::Revoked);
+    }
+    if !has_scope(&key.scopes, scope) {
+        return Err(KeyError::MissingScope(scope.to_string()));
+    }
+    let limit = key.rate_limit.max(1) as u32;
+    if !check_rate_limit(&format!("api_key_{}", key.prefix), limit, Duration::from_secs(60)) {
+        return Err(KeyError::RateLimited);
+    }
+
+    // Час використання з точністю до хвилини — без запису в БД на кожен запит
+    if key.last_used_at.is_none_or(|t| now - t >= 60) {
+        diesel::update(api_key::table.find(key.id))
+            .set(api_key::last_used_at.eq(Some(now)))
+            .execute(conn)
+            .map_err(db)?;
+    }
+    Ok(key)
+}
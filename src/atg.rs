@@ -0,0 +1,281 @@
+// Прийом телеметрії автоматичного рівнеміра (ATG) у форматі звіту Veeder-Root TLS
+// "In-Tank Inventory" (команда i201, комп'ютерний формат):
+//
+//   <SOH>i201TTYYMMDDHHmm{ TT p ssss NN FFFFFFFF×NN }...&&CCCC<ETX>
+//
+//   TT       — номер резервуару (00 у заголовку = всі резервуари)
+//   p        — код продукту
+//   ssss     — біти статусу резервуару
+//   NN       — кількість полів даних (hex)
+//   FFFFFFFF — IEEE 754 float у hex: обсяг, TC-обсяг, вільний об'єм, висота продукту,
+//              висота води, температура, обсяг води
+//   CCCC     — контрольна сума: сума всіх символів від SOH до && включно + CCCC = 0 (mod 2^16)
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, TimeZone};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const SOH: u8 = 0x01;
+const ETX: u8 = 0x03;
+const MAX_FRAME: usize = 8192;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TankInventory {
+    pub tank_number: u8,
+    pub product_code: char,
+    pub status: u16,
+    pub volume: f64,
+    pub tc_volume: f64,
+    pub ullage: f64,
+    pub height: f64,
+    pub water_height: f64,
+    pub temperature: f64,
+    pub water_volume: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventoryReport {
+    pub timestamp: NaiveDateTime,
+    pub tanks: Vec<TankInventory>,
+}
+
+fn checksum(bytes: &[u8]) -> u16 {
+    let sum = bytes.iter().fold(0u16, |acc, b| acc.wrapping_add(*b as u16));
+    (!sum).wrapping_add(1)
+}
+
+fn encode_float(value: f64) -> String {
+    format!("{:08X}", (value as f32).to_bits())
+}
+
+fn decode_float(hex: &str) -> Option<f64> {
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .map(|bits| f32::from_bits(bits) as f64)
+}
+
+pub fn encode_inventory_report(report: &InventoryReport) -> Vec<u8> {
+    let mut body = format!("i20100{}", report.timestamp.format("%y%m%d%H%M"));
+    for t in &report.tanks {
+        body.push_str(&format!(
+            "{:02}{}{:04X}07",
+            t.tank_number, t.product_code, t.status
+        ));
+        for value in [
+            t.volume,
+            t.tc_volume,
+            t.ullage,
+            t.height,
+            t.water_height,
+            t.temperature,
+            t.water_volume,
+        ] {
+            body.push_str(&encode_float(value));
+        }
+    }
+    body.push_str("&&");
+
+    let mut frame = vec![SOH];
+    frame.extend_from_slice(body.as_bytes());
+    let sum = checksum(&frame);
+    frame.extend_from_slice(format!("{:04X}", sum).as_bytes());
+    frame.push(ETX);
+    frame
+}
+
+/// Розбирає кадр звіту (з SOH та ETX) з перевіркою контрольної суми
+pub fn parse_inventory_report(frame: &[u8]) -> Result<InventoryReport, String> {
+    if frame.len() < 24 || frame[0] != SOH || frame[frame.len() - 1] != ETX {
+        return Err("Некоректний кадр ATG".to_string());
+    }
+    // Протокол ASCII: після цієї перевірки кожен байтовий індекс — межа символу
+    let inner = &frame[1..frame.len() - 1];
+    if !inner.is_ascii() {
+        return Err("Кадр ATG містить не ASCII дані".to_string());
+    }
+    let text = std::str::from_utf8(inner).map_err(|_| "Кадр ATG містить не ASCII дані".to_string())?;
+
+    let marker = text
+        .rfind("&&")
+        .ok_or_else(|| "Відсутня контрольна сума".to_string())?;
+    let expected = u16::from_str_radix(&text[marker + 2..], 16)
+        .map_err(|_| "Некоректна контрольна сума".to_string())?;
+    // SOH + все до && включно
+    if checksum(&frame[..marker + 3]) != expected {
+        return Err("Невірна контрольна сума".to_string());
+    }
+
+    let body = &text[..marker];
+    if !body.starts_with("i201") {
+        return Err(format!("Непідтримуваний звіт: {}", body.get(..6).unwrap_or(body)));
+    }
+    let timestamp = NaiveDateTime::parse_from_str(
+        body.get(6..16).ok_or("Відсутня дата звіту")?,
+        "%y%m%d%H%M",
+    )
+    .map_err(|e| format!("Некоректна дата звіту: {}", e))?;
+
+    let mut tanks = Vec::new();
+    let mut rest = body.get(16..).unwrap_or_default();
+    while !rest.is_empty() {
+        let field = |range: std::ops::Range<usize>| rest.get(range).ok_or("Обрізаний запис резервуару");
+        let tank_number = field(0..2)?
+            .parse::<u8>()
+            .map_err(|_| "Некоректний номер резервуару")?;
+        let product_code = field(2..3)?.chars().next().unwrap_or('0');
+        let status = u16::from_str_radix(field(3..7)?, 16).map_err(|_| "Некоректний статус")?;
+        let fields = usize::from_str_radix(field(7..9)?, 16).map_err(|_| "Некоректна кількість полів")?;
+
+        let data = rest
+            .get(9..9 + fields * 8)
+            .ok_or("Обрізані дані резервуару")?;
+        let values: Vec<f64> = (0..fields)
+            .map(|i| data.get(i * 8..i * 8 + 8).and_then(decode_float))
+            .collect::<Option<_>>()
+            .ok_or("Некоректне значення float")?;
+        if values.len() < 6 {
+            return Err("Недостатньо полів у записі резервуару".to_string());
+        }
+
+        tanks.push(TankInventory {
+            tank_number,
+            product_code,
+            status,
+            volume: values[0],
+            tc_volume: values[1],
+            ullage: values[2],
+            height: values[3],
+            water_height: values[4],
+            temperature: values[5],
+            water_volume: values.get(6).copied().unwrap_or(0.0),
+        });
+        rest = rest.get(9 + fields * 8..).unwrap_or_default();
+    }
+
+    Ok(InventoryReport { timestamp, tanks })
+}
+
+async fn read_report_frame<S: AsyncRead + Unpin>(stream: &mut S) -> std::io::Result<Vec<u8>> {
+    let mut byte = [0u8; 1];
+    loop {
+        stream.read_exact(&mut byte).await?;
+        if byte[0] == SOH {
+            break;
+        }
+    }
+
+    let mut frame = vec![SOH];
+    loop {
+        stream.read_exact(&mut byte).await?;
+        frame.push(byte[0]);
+        if byte[0] == ETX {
+            return Ok(frame);
+        }
+        if frame.len() > MAX_FRAME {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "ATG frame too long",
+            ));
+        }
+    }
+}
+
+/// TCP-слухач звітів ATG. Кожен коректний звіт передається в `on_report`.
+pub async fn listen<F>(addr: &str, on_report: F) -> std::io::Result<SocketAddr>
+where
+    F: Fn(InventoryReport) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+    let on_report = Arc::new(on_report);
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, peer)) = listener.accept().await {
+            let on_report = on_report.clone();
+            tokio::spawn(async move {
+                while let Ok(frame) = read_report_frame(&mut stream).await {
+                    match parse_inventory_report(&frame) {
+                        Ok(report) => on_report(report),
+                        Err(e) => println!("LOG: ATG {}: {}", peer, e),
+                    }
+                }
+            });
+        }
+    });
+
+    Ok(local_addr)
+}
+
+/// Зберігає звіт у таблицю tank_reading (резервуар визначається за tank.atg_number).
+/// Час заміру — з самого звіту (місцевий час ATG); час отримання — лише якщо дата некоректна.
+pub fn store_report(report: &InventoryReport) -> Result<usize, diesel::result::Error> {
+    use crate::db;
+    use crate::models::NewTankReading;
+    use crate::schema::{tank, tank_reading};
+    use diesel::prelude::*;
+
+    let mut conn = db::connection();
+    let recorded_at = chrono::Local
+        .from_local_datetime(&report.timestamp)
+        .earliest()
+        .map_or_else(|| chrono::Utc::now().timestamp(), |dt| dt.timestamp());
+    let mut stored = 0;
+
+    for t in &report.tanks {
+        let tank_id: Option<i32> = tank::table
+            .filter(tank::atg_number.eq(t.tank_number as i32))
+            .select(tank::id)
+            .first(&mut conn)
+            .optional()?;
+
+        let Some(tank_id) = tank_id else {
+            println!("LOG: ATG tank {} is not mapped to any tank", t.tank_number);
+            continue;
+        };
+
+        diesel::insert_into(tank_reading::table)
+            .values(&NewTankReading {
+                tank_id,
+                recorded_at,
+                height: t.height,
+                volume: t.volume,
+                water_height: t.water_height,
+                temperature: t.temperature,
+                source: "atg",
+                net_volume: Some(t.tc_volume),
+            })
+            .execute(&mut conn)?;
+        stored += 1;
+    }
+
+    Ok(stored)
+}
+
+// ---------- Симулятор ----------
+
+/// Симулятор рівнеміра: підключається до слухача і періодично надсилає звіти.
+/// `snapshot` повертає поточний стан резервуарів для кожного звіту.
+pub async fn run_simulator<F>(addr: &str, interval: Duration, reports: usize, snapshot: F) -> std::io::Result<()>
+where
+    F: Fn() -> Vec<TankInventory>,
+{
+    let mut stream = TcpStream::connect(addr).await?;
+    let mut ticker = tokio::time::interval(interval);
+    let mut sent = 0;
+
+    while reports == 0 || sent < reports {
+        ticker.tick().await;
+        let report = InventoryReport {
+            timestamp: chrono::Local::now().naive_local(),
+            tanks: snapshot(),
+        };
+        stream.write_all(&encode_inventory_report(&report)).await?;
+        sent += 1;
+    }
+
+    Ok(())
+}
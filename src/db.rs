@@ -10,10 +10,25 @@ pub mod server_db {
 
     pub type Pool = r2d2::Pool<ConnectionManager<SqliteConnection>>;
 
+    // Фонові задачі й запити пишуть одночасно: замість миттєвої помилку "database is locked"
+    // з'єднання чекає на звільнення БД (налаштування діє на кожне з'єднання окремо)
+    #[derive(Debug)]
+    struct BusyTimeout;
+
+    impl r2d2::CustomizeConnection<SqliteConnection, r2d2::Error> for BusyTimeout {
+        fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), r2d2::Error> {
+            diesel::sql_query("PRAGMA busy_timeout = 5000;")
+                .execute(conn)
+                .map(|_| ())
+                .map_err(r2d2::Error::QueryError)
+        }
+    }
+
     pub static DB_POOL: Lazy<Pool> = Lazy::new(|| {
         let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
         let manager = ConnectionManager::<SqliteConnection>::new(database_url);
         let pool = r2d2::Pool::builder()
+            .connection_customizer(Box::new(BusyTimeout))
             .build(manager)
             .expect("Failed to create pool.");
 
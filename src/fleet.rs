@@ -0,0 +1,243 @@
+// Корпоративні рахунки автопарків.
+//
+// Водій купує пальне зі свого логіна клієнта (або API-ключем, прив'язаним до нього), а
+// списання йде з балансу компанії. Перед покупкою перевіряються активність водія, дозволені
+// типи пального, добовий і місячний ліміти та баланс компанії. Кожна покупка й поповнення
+// пишуться в fleet_ledger — з нього рахуються витрати водіїв і місячні виписки.
+
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate};
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+
+use crate::models::{FleetCompany, FleetDriver, FleetStatement, FleetStatementLine};
+use crate::reconciliation::day_start;
+use crate::schema::{customer, fleet_company, fleet_driver, fleet_ledger, fuel, sale};
+use crate::utils::{fuel_type_allowed, limit_exceeded};
+
+pub const KIND_TOPUP: &str = "topup";
+pub const KIND_PURCHASE: &str = "purchase";
+
+/// Водій разом із компанією, якщо клієнт належить автопарку
+pub fn driver_for(
+    conn: &mut SqliteConnection,
+    customer_id: i32,
+) -> QueryResult<Option<(FleetDriver, FleetCompany)>> {
+    fleet_driver::table
+        .inner_join(fleet_company::table)
+        .filter(fleet_driver::customer_id.eq(customer_id))
+        .select((FleetDriver::as_select(), FleetCompany::as_select()))
+        .first(conn)
+        .optional()
+}
+
+/// Початок доби та місяця для `today` (місцевий час)
+pub fn period_starts(today: NaiveDate) -> (i64, i64) {
+    let month_start = today.with_day(1).unwrap_or(today);
+    (day_start(today), day_start(month_start))
+}
+
+/// Сума покупок водія з моменту `from`, копійки
+pub fn spent_since(conn: &mut SqliteConnection, driver_id: i32, from: i64) -> QueryResult<i64> {
+    let amounts: Vec<i64> = fleet_ledger::table
+        .filter(fleet_ledger::driver_id.eq(driver_id))
+        .filter(fleet_ledger::kind.eq(KIND_PURCHASE))
+        .filter(fleet_ledger::created_at.ge(from))
+        .select(fleet_ledger::amount)
+        .load(conn)?;
+    Ok(-amounts.iter().sum::<i64>())
+}
+
+/// Чи може водій купити пальне (назва, тип) на суму `total`. Err — причина відмови.
+pub fn check_purchase(
+    conn: &mut SqliteConnection,
+    driver: &FleetDriver,
+    company: &FleetCompany,
+    fuels: &[(String, String)],
+    total: i64,
+    today: NaiveDate,
+) -> Result<(), String> {
+    if !driver.active {
+        return Err("Картку водія заблоковано. Зверніться до адміністратора автопарку.".to_string());
+    }
+    if let Some((name, _)) = fuels
+        .iter()
+        .find(|(_, fuel_type)| !fuel_type_allowed(driver.allowed_fuel_types.as_deref(), fuel_type))
+    {
+        return Err(format!("Пальне {} не дозволене для водія", name));
+    }
+
+    let (day_from, month_from) = period_starts(today);
+    let spent_today = spent_since(conn, driver.id, day_from).map_err(|e| e.to_string())?;
+    if limit_exceeded(driver.daily_limit, spent_today, total) {
+        return Err(format!(
+            "Перевищено добовий ліміт водія: доступно {:.2} грн",
+            (driver.daily_limit.unwrap_or(0) - spent_today).max(0) as f64 / 100.0
+        ));
+    }
+    let spent_month = spent_since(conn, driver.id, month_from).map_err(|e| e.to_string())?;
+    if limit_exceeded(driver.monthly_limit, spent_month, total) {
+        return Err(format!(
+            "Перевищено місячний ліміт водія: доступно {:.2} грн",
+            (driver.monthly_limit.unwrap_or(0) - spent_month).max(0) as f64 / 100.0
+        ));
+    }
+
+    if company.balance < total {
+        return Err(format!("Недостатньо коштів на рахунку компанії {}", company.name));
+    }
+    Ok(())
+}
+
+/// Списує покупку водія з рахунку компанії (викликати в транзакції продажу).
+/// Списання умовне: якщо коштів уже не вистачає, повертає Err і продаж відкочується.
+pub fn record_purchase(
+    conn: &mut SqliteConnection,
+    driver: &FleetDriver,
+    sale_id: i32,
+    total: i64,
+    now: i64,
+) -> Result<(), String> {
+    let db = |e: diesel::result::Error| e.to_string();
+    let debited = diesel::update(
+        fleet_company::table
+            .find(driver.company_id)
+            .filter(fleet_company::balance.ge(total)),
+    )
+    .set(fleet_company::balance.eq(fleet_company::balance - total))
+    .execute(conn)
+    .map_err(db)?;
+    if debited != 1 {
+        return Err("Недостатньо коштів на рахунку компанії".to_string());
+    }
+    diesel::insert_into(fleet_ledger::table)
+        .values((
+            fleet_ledger::company_id.eq(driver.company_id),
+            fleet_ledger::driver_id.eq(Some(driver.id)),
+            fleet_ledger::sale_id.eq(Some(sale_id)),
+            fleet_ledger::kind.eq(KIND_PURCHASE),
+            fleet_ledger::amount.eq(-total),
+            fleet_ledger::created_at.eq(now),
+        ))
+        .execute(conn)
+        .map_err(db)?;
+    Ok(())
+}
+
+/// Поповнення рахунку компанії. Повертає новий баланс.
+pub fn top_up(
+    conn: &mut SqliteConnection,
+    company_id: i32,
+    amount: i64,
+    note: Option<&str>,
+    now: i64,
+) -> QueryResult<i64> {
+    conn.transaction(|conn| {
+        diesel::insert_into(fleet_ledger::table)
+            .values((
+                fleet_ledger::company_id.eq(company_id),
+                fleet_ledger::kind.eq(KIND_TOPUP),
+                fleet_ledger::amount.eq(amount),
+                fleet_ledger::note.eq(note),
+                fleet_ledger::created_at.eq(now),
+            ))
+            .execute(conn)?;
+        diesel::update(fleet_company::table.find(company_id))
+            .set(fleet_company::balance.eq(fleet_company::balance + amount))
+            .returning(fleet_company::balance)
+            .get_result(conn)
+    })
+}
+
+/// Зведена виписка компанії за період [from, to): залишки на початок і кінець,
+/// усі рухи з водіями та пальним, підсумки по водіях
+pub fn statement(
+    conn: &mut SqliteConnection,
+    company: FleetCompany,
+    month: &str,
+    from: i64,
+    to: i64,
+) -> QueryResult<FleetStatement> {
+    // (created_at, kind, amount, note, driver_id, sale_id)
+    type LedgerRow = (i64, String, i64, Option<String>, Option<i32>, Option<i32>);
+
+    let after_from: Vec<i64> = fleet_ledger::table
+        .filter(fleet_ledger::company_id.eq(company.id))
+        .filter(fleet_ledger::created_at.ge(from))
+        .select(fleet_ledger::amount)
+        .load(conn)?;
+    let opening_balance = company.balance - after_from.iter().sum::<i64>();
+
+    let rows: Vec<LedgerRow> = fleet_ledger::table
+        .filter(fleet_ledger::company_id.eq(company.id))
+        .filter(fleet_ledger::created_at.ge(from))
+        .filter(fleet_ledger::created_at.lt(to))
+        .order((fleet_ledger::created_at, fleet_ledger::id))
+        .select((
+            fleet_ledger::created_at,
+            fleet_ledger::kind,
+            fleet_ledger::amount,
+            fleet_ledger::note,
+            fleet_ledger::driver_id,
+            fleet_ledger::sale_id,
+        ))
+        .load(conn)?;
+
+    let logins: HashMap<i32, String> = fleet_driver::table
+        .inner_join(customer::table)
+        .filter(fleet_driver::company_id.eq(company.id))
+        .select((fleet_driver::id, customer::login))
+        .load::<(i32, String)>(conn)?
+        .into_iter()
+        .collect();
+    let sale_ids: Vec<i32> = rows.iter().filter_map(|r| r.5).collect();
+    let sales: HashMap<i32, (String, i32)> = sale::table
+        .inner_join(fuel::table)
+        .filter(sale::id.eq_any(&sale_ids))
+        .select((sale::id, fuel::name, sale::amount))
+        .load::<(i32, String, i32)>(conn)?
+        .into_iter()
+        .map(|(id, name, volume)| (id, (name, volume)))
+        .collect();
+
+    let mut top_ups = 0;
+    let mut purchases = 0;
+    let mut by_driver: Vec<(String, i64)> = Vec::new();
+    let mut lines = Vec::new();
+    for (created_at, kind, amount, note, driver_id, sale_id) in rows {
+        let driver_login = driver_id.and_then(|id| logins.get(&id).cloned());
+        if kind == KIND_PURCHASE {
+            purchases += -amount;
+            let login = driver_login.clone().unwrap_or_default();
+            match by_driver.iter_mut().find(|(l, _)| *l == login) {
+                Some((_, sum)) => *sum += -amount,
+                None => by_driver.push((login, -amount)),
+            }
+        } else {
+            top_ups += amount;
+        }
+        let sold = sale_id.and_then(|id| sales.get(&id));
+        lines.push(FleetStatementLine {
+            created_at,
+            kind,
+            driver_login,
+            fuel_name: sold.map(|(name, _)| name.clone()),
+            volume: sold.map(|(_, volume)| *volume),
+            amount,
+            note,
+        });
+    }
+    by_driver.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+
+    Ok(FleetStatement {
+        company,
+        month: month.to_string(),
+        opening_balance,
+        top_ups,
+        purchases,
+        closing_balance: opening_balance + top_ups - purchases,
+        lines,
+        by_driver,
+    })
+}
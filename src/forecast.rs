@@ -0,0 +1,91 @@
+// Прогноз споживання пального та рекомендовані замовлення
+
+use diesel::dsl;
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+
+use crate::models::{ReorderPolicy, ReorderSuggestion};
+use crate::schema::{fuel, purchase_order, reorder_policy, sale, tank};
+use crate::utils::{average_daily_consumption, days_until_empty, suggested_order_qty};
+
+/// Вікно історії продажів для розрахунку середнього споживання
+pub const LOOKBACK_DAYS: u32 = 14;
+
+/// Перераховує середнє добове споживання та запас у днях для кожного пального з політикою
+pub fn refresh(conn: &mut SqliteConnection) -> QueryResult<usize> {
+    let now = chrono::Utc::now().timestamp();
+    let window_start = now - LOOKBACK_DAYS as i64 * 86_400;
+
+    let policies: Vec<ReorderPolicy> = reorder_policy::table
+        .select(ReorderPolicy::as_select())
+        .load(conn)?;
+
+    for p in &policies {
+        let (sold, first_sale): (Option<i64>, Option<i64>) = sale::table
+            .filter(sale::fuel_id.eq(p.fuel_id))
+            .filter(sale::created_at.ge(window_start))
+            .select((dsl::sum(sale::amount), dsl::min(sale::created_at)))
+            .first(conn)?;
+
+        // Якщо історія коротша за вікно, ділимо на фактичну кількість днів
+        let days = first_sale
+            .map(|ts| ((now - ts) / 86_400 + 1) as u32)
+            .unwrap_or(LOOKBACK_DAYS)
+            .clamp(1, LOOKBACK_DAYS);
+        let avg = average_daily_consumption(sold.unwrap_or(0), days);
+
+        let stored: Option<i64> = tank::table
+            .filter(tank::fuelid.eq(p.fuel_id))
+            .select(dsl::sum(tank::stored))
+            .first(conn)?;
+
+        diesel::update(reorder_policy::table.find(p.fuel_id))
+            .set((
+                reorder_policy::avg_daily_sales.eq(Some(avg)),
+                reorder_policy::days_until_empty.eq(days_until_empty(stored.unwrap_or(0) as i32, avg)),
+                reorder_policy::forecast_at.eq(Some(now)),
+            ))
+            .execute(conn)?;
+    }
+
+    Ok(policies.len())
+}
+
+/// Рекомендовані замовлення: обсяг до цільового рівня з урахуванням відкритих замовлень
+pub fn suggestions(conn: &mut SqliteConnection) -> QueryResult<Vec<ReorderSuggestion>> {
+    let policies: Vec<(ReorderPolicy, String)> = reorder_policy::table
+        .inner_join(fuel::table)
+        .order(reorder_policy::fuel_id)
+        .select((ReorderPolicy::as_select(), fuel::name))
+        .load(conn)?;
+
+    let mut result = Vec::new();
+    for (policy, fuel_name) in policies {
+        let (stored, capacity): (Option<i64>, Option<i64>) = tank::table
+            .filter(tank::fuelid.eq(policy.fuel_id))
+            .select((dsl::sum(tank::stored), dsl::sum(tank::capacity)))
+            .first(conn)?;
+        let open_orders: Vec<(i32, i32)> = purchase_order::table
+            .filter(purchase_order::fuel_id.eq(policy.fuel_id))
+            .filter(purchase_order::status.eq_any(["open", "partial"]))
+            .select((purchase_order::ordered_qty, purchase_order::delivered_qty))
+            .load(conn)?;
+
+        let stored = stored.unwrap_or(0) as i32;
+        let capacity = capacity.unwrap_or(0) as i32;
+        let on_order: i32 = open_orders.iter().map(|(o, d)| o - d).sum();
+
+        result.push(ReorderSuggestion {
+            fuel_id: policy.fuel_id,
+            fuel_name,
+            stored,
+            capacity,
+            on_order,
+            suggested_qty: suggested_order_qty(stored, capacity, on_order, policy.target_level),
+            below_reorder_point: stored + on_order <= policy.reorder_point,
+            policy,
+        });
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,468 @@
+// Керування колонками (forecourt controller) та програмний симулятор колонки.
+//
+// Протокол — кадровий ASCII, такий самий як у більшості контролерів ПРК по RS-485/TCP:
+//
+//   STX | ADDR (2 цифри, номер колонки) | CMD (1 символ) | DATA | ETX | BCC (2 hex)
+//
+// BCC — XOR усіх байтів від ADDR до ETX включно.
+//
+// Команди хоста:
+//   A<preset 8><price 6>  — авторизація (preset у сантилітрах, 0 = до повного бака; ціна в копійках)
+//   S                     — зупинка відпуску
+//   P                     — опитування стану
+//   T                     — читання електронних тоталізаторів
+//
+// Відповіді колонки:
+//   K                                   — підтвердження
+//   N<code 2>                           — відмова
+//   s<state 1><volume 8><amount 10>     — поточний стан відпуску
+//   t<volume 12><amount 12>             — тоталізатори
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const STX: u8 = 0x02;
+const ETX: u8 = 0x03;
+
+#[derive(Debug)]
+pub enum ForecourtError {
+    Io(std::io::Error),
+    Protocol(String),
+    Rejected(u8),
+}
+
+impl std::fmt::Display for ForecourtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForecourtError::Io(e) => write!(f, "Помилка зв'язку з колонкою: {}", e),
+            ForecourtError::Protocol(msg) => write!(f, "Помилка протоколу: {}", msg),
+            ForecourtError::Rejected(code) => write!(f, "Колонка відхилила команду (код {})", code),
+        }
+    }
+}
+
+impl std::error::Error for ForecourtError {}
+
+impl From<std::io::Error> for ForecourtError {
+    fn from(e: std::io::Error) -> Self {
+        ForecourtError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PumpState {
+    Idle,
+    Authorized,
+    Dispensing,
+    Finished,
+}
+
+impl PumpState {
+    fn code(self) -> u8 {
+        match self {
+            PumpState::Idle => b'0',
+            PumpState::Authorized => b'1',
+            PumpState::Dispensing => b'2',
+            PumpState::Finished => b'3',
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            b'0' => Some(PumpState::Idle),
+            b'1' => Some(PumpState::Authorized),
+            b'2' => Some(PumpState::Dispensing),
+            b'3' => Some(PumpState::Finished),
+            _ => None,
+        }
+    }
+}
+
+/// Поточний стан відпуску (обсяг у сантилітрах, сума в копійках)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PumpStatus {
+    pub state: PumpState,
+    pub volume_cl: u32,
+    pub amount: u64,
+}
+
+/// Електронні тоталізатори колонки (накопичувальні лічильники)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PumpTotals {
+    pub volume_cl: u64,
+    pub amount: u64,
+}
+
+/// Результат завершеного відпуску
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DispenseResult {
+    pub volume_cl: u32,
+    pub amount: u64,
+    pub totals: PumpTotals,
+}
+
+pub trait ForecourtController {
+    async fn authorize(&mut self, pump: u8, preset_cl: u32, price: u32)
+        -> Result<(), ForecourtError>;
+    async fn stop(&mut self, pump: u8) -> Result<(), ForecourtError>;
+    async fn poll(&mut self, pump: u8) -> Result<PumpStatus, ForecourtError>;
+    async fn read_totals(&mut self, pump: u8) -> Result<PumpTotals, ForecourtError>;
+}
+
+// ---------- Кадрування ----------
+
+fn bcc(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |acc, b| acc ^ b)
+}
+
+pub fn encode_frame(pump: u8, cmd: u8, data: &str) -> Vec<u8> {
+    let mut body = format!("{:02}", pump).into_bytes();
+    body.push(cmd);
+    body.extend_from_slice(data.as_bytes());
+    body.push(ETX);
+
+    let mut frame = Vec::with_capacity(body.len() + 3);
+    frame.push(STX);
+    frame.extend_from_slice(&body);
+    frame.extend_from_slice(format!("{:02X}", bcc(&body)).as_bytes());
+    frame
+}
+
+/// Розбирає кадр (з STX на початку) на (номер колонки, команда, дані)
+pub fn decode_frame(frame: &[u8]) -> Result<(u8, u8, String), ForecourtError> {
+    if frame.len() < 7 || frame[0] != STX {
+        return Err(ForecourtError::Protocol("некоректний кадр".to_string()));
+    }
+    let etx_pos = frame.len() - 3;
+    if frame[etx_pos] != ETX {
+        return Err(ForecourtError::Protocol("відсутній ETX".to_string()));
+    }
+
+    let body = &frame[1..=etx_pos];
+    let received = std::str::from_utf8(&frame[etx_pos + 1..])
+        .ok()
+        .and_then(|s| u8::from_str_radix(s, 16).ok())
+        .ok_or_else(|| ForecourtError::Protocol("некоректний BCC".to_string()))?;
+    if received != bcc(body) {
+        return Err(ForecourtError::Protocol("невірна контрольна сума".to_string()));
+    }
+
+    // Кадр не коротший за 7 байтів: адреса, команда й ETX у body є завжди.
+    // Поля розбираються з байтів — багатобайтовий символ не зламає зріз рядка
+    let pump = std::str::from_utf8(&body[0..2])
+        .ok()
+        .and_then(|s| s.parse::<u8>().ok())
+        .ok_or_else(|| ForecourtError::Protocol("некоректна адреса".to_string()))?;
+    let cmd = body[2];
+    let data = std::str::from_utf8(&body[3..body.len() - 1])
+        .map_err(|_| ForecourtError::Protocol("не ASCII дані".to_string()))?;
+    Ok((pump, cmd, data.to_string()))
+}
+
+async fn read_frame<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Vec<u8>, ForecourtError> {
+    let mut byte = [0u8; 1];
+    loop {
+        stream.read_exact(&mut byte).await?;
+        if byte[0] == STX {
+            break;
+        }
+    }
+
+    let mut frame = vec![STX];
+    loop {
+        stream.read_exact(&mut byte).await?;
+        frame.push(byte[0]);
+        if byte[0] == ETX {
+            break;
+        }
+        if frame.len() > 64 {
+            return Err(ForecourtError::Protocol("задовгий кадр".to_string()));
+        }
+    }
+
+    let mut checksum = [0u8; 2];
+    stream.read_exact(&mut checksum).await?;
+    frame.extend_from_slice(&checksum);
+    Ok(frame)
+}
+
+fn parse_field<T: std::str::FromStr>(data: &str, range: std::ops::Range<usize>) -> Result<T, ForecourtError> {
+    data.get(range)
+        .and_then(|s| s.parse::<T>().ok())
+        .ok_or_else(|| ForecourtError::Protocol(format!("некоректне поле у '{}'", data)))
+}
+
+// ---------- Адаптер ----------
+
+/// Адаптер протоколу поверх будь-якого байтового потоку (TCP, послідовний порт)
+pub struct DispenserClient<S> {
+    stream: S,
+}
+
+impl DispenserClient<TcpStream> {
+    pub async fn connect(addr: &str) -> Result<Self, ForecourtError> {
+        let stream = TcpStream::connect(addr).await?;
+        Ok(DispenserClient::new(stream))
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> DispenserClient<S> {
+    pub fn new(stream: S) -> Self {
+        DispenserClient { stream }
+    }
+
+    async fn request(&mut self, pump: u8, cmd: u8, data: &str) -> Result<(u8, String), ForecourtError> {
+        self.stream.write_all(&encode_frame(pump, cmd, data)).await?;
+        let frame = read_frame(&mut self.stream).await?;
+        let (reply_pump, reply_cmd, reply_data) = decode_frame(&frame)?;
+        if reply_pump != pump {
+            return Err(ForecourtError::Protocol(format!(
+                "відповідь від колонки {} замість {}",
+                reply_pump, pump
+            )));
+        }
+        if reply_cmd == b'N' {
+            return Err(ForecourtError::Rejected(parse_field(&reply_data, 0..2)?));
+        }
+        Ok((reply_cmd, reply_data))
+    }
+
+    async fn expect_ack(&mut self, pump: u8, cmd: u8, data: &str) -> Result<(), ForecourtError> {
+        match self.request(pump, cmd, data).await? {
+            (b'K', _) => Ok(()),
+            (other, _) => Err(ForecourtError::Protocol(format!(
+                "очікувалось підтвердження, отримано '{}'",
+                other as char
+            ))),
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ForecourtController for DispenserClient<S> {
+    async fn authorize(&mut self, pump: u8, preset_cl: u32, price: u32) -> Result<(), ForecourtError> {
+        self.expect_ack(pump, b'A', &format!("{:08}{:06}", preset_cl, price))
+            .await
+    }
+
+    async fn stop(&mut self, pump: u8) -> Result<(), ForecourtError> {
+        self.expect_ack(pump, b'S', "").await
+    }
+
+    async fn poll(&mut self, pump: u8) -> Result<PumpStatus, ForecourtError> {
+        let (cmd, data) = self.request(pump, b'P', "").await?;
+        if cmd != b's' || data.len() != 19 {
+            return Err(ForecourtError::Protocol(format!("некоректний стан '{}'", data)));
+        }
+        let state = PumpState::from_code(data.as_bytes()[0])
+            .ok_or_else(|| ForecourtError::Protocol(format!("невідомий стан '{}'", data)))?;
+        Ok(PumpStatus {
+            state,
+            volume_cl: parse_field(&data, 1..9)?,
+            amount: parse_field(&data, 9..19)?,
+        })
+    }
+
+    async fn read_totals(&mut self, pump: u8) -> Result<PumpTotals, ForecourtError> {
+        let (cmd, data) = self.request(pump, b'T', "").await?;
+        if cmd != b't' || data.len() != 24 {
+            return Err(ForecourtError::Protocol(format!("некоректні тоталізатори '{}'", data)));
+        }
+        Ok(PumpTotals {
+            volume_cl: parse_field(&data, 0..12)?,
+            amount: parse_field(&data, 12..24)?,
+        })
+    }
+}
+
+/// Повний цикл відпуску: авторизація, опитування до завершення, читання тоталізаторів.
+/// `on_tick` викликається для кожного проміжного стану (живі показники обсягу).
+pub async fn dispense<C: ForecourtController>(
+    controller: &mut C,
+    pump: u8,
+    preset_cl: u32,
+    price: u32,
+    poll_interval: Duration,
+    mut on_tick: impl FnMut(PumpStatus),
+) -> Result<DispenseResult, ForecourtError> {
+    controller.authorize(pump, preset_cl, price).await?;
+
+    loop {
+        let status = controller.poll(pump).await?;
+        on_tick(status);
+        match status.state {
+            PumpState::Finished => {
+                let totals = controller.read_totals(pump).await?;
+                return Ok(DispenseResult {
+                    volume_cl: status.volume_cl,
+                    amount: status.amount,
+                    totals,
+                });
+            }
+            PumpState::Idle => {
+                return Err(ForecourtError::Protocol(
+                    "колонка повернулась у стан очікування без відпуску".to_string(),
+                ));
+            }
+            _ => tokio::time::sleep(poll_interval).await,
+        }
+    }
+}
+
+// ---------- Симулятор ----------
+
+#[derive(Debug, Clone, Copy)]
+pub struct SimulatorConfig {
+    pub pumps: u8,
+    /// Швидкість відпуску, сантилітрів за один такт
+    pub flow_per_tick_cl: u32,
+    pub tick: Duration,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        SimulatorConfig {
+            pumps: 4,
+            flow_per_tick_cl: 50,
+            tick: Duration::from_millis(100),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct SimPump {
+    state: Option<PumpState>,
+    preset_cl: u32,
+    price: u32,
+    volume_cl: u32,
+    totals: PumpTotals,
+}
+
+impl SimPump {
+    fn state(&self) -> PumpState {
+        self.state.unwrap_or(PumpState::Idle)
+    }
+
+    fn amount(&self) -> u64 {
+        self.volume_cl as u64 * self.price as u64 / 100
+    }
+
+    fn finish(&mut self) {
+        self.totals.volume_cl += self.volume_cl as u64;
+        self.totals.amount += self.amount();
+        self.state = Some(PumpState::Finished);
+    }
+
+    fn tick(&mut self, flow_cl: u32) {
+        match self.state() {
+            PumpState::Authorized => self.state = Some(PumpState::Dispensing),
+            PumpState::Dispensing => {
+                // Без пресету симулятор зупиняється на 50 л — "повний бак"
+                let limit = if self.preset_cl == 0 { 5000 } else { self.preset_cl };
+                self.volume_cl = (self.volume_cl + flow_cl).min(limit);
+                if self.volume_cl >= limit {
+                    self.finish();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+type SimState = Arc<Mutex<HashMap<u8, SimPump>>>;
+
+fn handle_command(pumps: &SimState, pump: u8, cmd: u8, data: &str) -> Vec<u8> {
+    let mut pumps = pumps.lock().unwrap();
+    let Some(p) = pumps.get_mut(&pump) else {
+        return encode_frame(pump, b'N', "01");
+    };
+
+    match cmd {
+        b'A' => {
+            if matches!(p.state(), PumpState::Authorized | PumpState::Dispensing) {
+                return encode_frame(pump, b'N', "02");
+            }
+            match (parse_field::<u32>(data, 0..8), parse_field::<u32>(data, 8..14)) {
+                (Ok(preset), Ok(price)) if data.len() == 14 => {
+                    p.preset_cl = preset;
+                    p.price = price;
+                    p.volume_cl = 0;
+                    p.state = Some(PumpState::Authorized);
+                    encode_frame(pump, b'K', "")
+                }
+                _ => encode_frame(pump, b'N', "03"),
+            }
+        }
+        b'S' => {
+            match p.state() {
+                PumpState::Dispensing => p.finish(),
+                PumpState::Authorized => p.state = Some(PumpState::Idle),
+                _ => {}
+            }
+            encode_frame(pump, b'K', "")
+        }
+        b'P' => {
+            let data = format!(
+                "{}{:08}{:010}",
+                p.state().code() as char,
+                p.volume_cl,
+                p.amount()
+            );
+            encode_frame(pump, b's', &data)
+        }
+        b'T' => {
+            let data = format!("{:012}{:012}", p.totals.volume_cl, p.totals.amount);
+            encode_frame(pump, b't', &data)
+        }
+        _ => encode_frame(pump, b'N', "04"),
+    }
+}
+
+async fn serve_client(mut stream: TcpStream, pumps: SimState) {
+    loop {
+        let frame = match read_frame(&mut stream).await {
+            Ok(f) => f,
+            Err(_) => return,
+        };
+        let reply = match decode_frame(&frame) {
+            Ok((pump, cmd, data)) => handle_command(&pumps, pump, cmd, &data),
+            Err(_) => encode_frame(0, b'N', "99"),
+        };
+        if stream.write_all(&reply).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Запускає симулятор колонок на вказаній адресі. Повертає фактичну адресу (корисно з портом 0).
+pub async fn spawn_simulator(addr: &str, config: SimulatorConfig) -> std::io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+
+    let pumps: SimState = Arc::new(Mutex::new(
+        (1..=config.pumps).map(|n| (n, SimPump::default())).collect(),
+    ));
+
+    let ticker = pumps.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.tick);
+        loop {
+            interval.tick().await;
+            for p in ticker.lock().unwrap().values_mut() {
+                p.tick(config.flow_per_tick_cl);
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve_client(stream, pumps.clone()));
+        }
+    });
+
+    Ok(local_addr)
+}
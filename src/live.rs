@@ -0,0 +1,82 @@
+// Живі оновлення залишків, цін і доступності пального для відкритих сторінок (SSE).
+//
+// Після зміни ціни чи залишків викликається `publish`: поточний стан кожного пального
+// порівнюється з останнім надісланим, і змінені записи розсилаються всім підписникам.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+use tokio::sync::broadcast;
+
+use crate::models::FuelWithTank;
+use crate::schema::{fuel, tank};
+
+// Повільний клієнт пропускає старі оновлення (Lagged), а не гальмує інших
+const CHANNEL_CAPACITY: usize = 64;
+
+struct Hub {
+    sender: broadcast::Sender<FuelWithTank>,
+    last: Mutex<HashMap<i32, FuelWithTank>>,
+}
+
+static HUB: OnceLock<Hub> = OnceLock::new();
+
+fn hub() -> &'static Hub {
+    HUB.get_or_init(|| Hub {
+        sender: broadcast::channel(CHANNEL_CAPACITY).0,
+        last: Mutex::new(HashMap::new()),
+    })
+}
+
+/// Пальне з сумарним залишком і місткістю резервуарів
+pub fn load_fuels(conn: &mut SqliteConnection) -> QueryResult<Vec<FuelWithTank>> {
+    let rows: Vec<(i32, String, i64, Option<String>, i32, i32)> = fuel::table
+        .inner_join(tank::table)
+        .order(fuel::id)
+        .select((fuel::id, fuel::name, fuel::price, fuel::fuel_type, tank::stored, tank::capacity))
+        .load(conn)?;
+
+    let mut result: Vec<FuelWithTank> = Vec::new();
+    for (id, name, price, fuel_type, stored, capacity) in rows {
+        match result.last_mut() {
+            Some(f) if f.id == id => {
+                f.stored += stored;
+                f.capacity += capacity;
+            }
+            _ => result.push(FuelWithTank {
+                id,
+                name,
+                price,
+                fuel_type: fuel_type.unwrap_or_else(|| "petrol".to_string()),
+                stored,
+                capacity,
+            }),
+        }
+    }
+    Ok(result)
+}
+
+/// Розсилає змінені з останньої розсилки записи. Повертає кількість змінених.
+pub fn publish(conn: &mut SqliteConnection) -> QueryResult<usize> {
+    let fuels = load_fuels(conn)?;
+    let hub = hub();
+    let mut last = hub.last.lock().unwrap();
+
+    let mut changed = 0;
+    for f in fuels {
+        if last.get(&f.id) == Some(&f) {
+            continue;
+        }
+        // Помилка означає лише відсутність підписників
+        hub.sender.send(f.clone()).ok();
+        last.insert(f.id, f);
+        changed += 1;
+    }
+    Ok(changed)
+}
+
+pub fn subscribe() -> broadcast::Receiver<FuelWithTank> {
+    hub().sender.subscribe()
+}
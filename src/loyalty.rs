@@ -0,0 +1,206 @@
+// Програма лояльності.
+//
+// Рівень клієнта визначається сумою його покупок за TIER_WINDOW_SECS; чим вищий рівень,
+// тим більше балів за літр і за сплачені гривні. Бали нараховуються партіями (earn), кожна
+// згорає через EXPIRY_SECS. Списання при оплаті (1 бал = POINT_VALUE копійок) забирає бали
+// з партій, що згорають найраніше. Згорілі залишки партій фіксуються записом expire.
+
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+
+use crate::models::{LoyaltyAccount, LoyaltyEntry, LoyaltyTier};
+use crate::schema::{loyalty_entry, loyalty_tier, sale};
+
+pub const KIND_EARN: &str = "earn";
+pub const KIND_REDEEM: &str = "redeem";
+pub const KIND_EXPIRE: &str = "expire";
+
+/// Вартість одного бала при оплаті, копійки
+pub const POINT_VALUE: i64 = 100;
+pub const EXPIRY_SECS: i64 = 365 * 24 * 3600;
+pub const TIER_WINDOW_SECS: i64 = 365 * 24 * 3600;
+/// За скільки до згоряння попереджати клієнта
+const EXPIRY_WARNING_SECS: i64 = 30 * 24 * 3600;
+
+/// Сума, сплачена клієнтом за покупки (після знижок за акціями, без оплаченого балами)
+/// за останні TIER_WINDOW_SECS, копійки
+pub fn spent_in_window(conn: &mut SqliteConnection, customer_id: i32, now: i64) -> QueryResult<i64> {
+    sale::table
+        .filter(sale::customer_id.eq(customer_id))
+        .filter(sale::created_at.gt(now - TIER_WINDOW_SECS))
+        .select(sale::total - sale::discount - sale::points_value)
+        .load::<i64>(conn)
+        .map(|totals| totals.iter().sum())
+}
+
+/// Поточний і наступний рівень для суми покупок
+pub fn tiers_for(conn: &mut SqliteConnection, spent: i64) -> QueryResult<(LoyaltyTier, Option<LoyaltyTier>)> {
+    let current = loyalty_tier::table
+        .filter(loyalty_tier::min_spent.le(spent))
+        .order(loyalty_tier::min_spent.desc())
+        .select(LoyaltyTier::as_select())
+        .first(conn)?;
+    let next = loyalty_tier::table
+        .filter(loyalty_tier::min_spent.gt(spent))
+        .order(loyalty_tier::min_spent)
+        .select(LoyaltyTier::as_select())
+        .first(conn)
+        .optional()?;
+    Ok((current, next))
+}
+
+/// Бали, доступні для списання
+pub fn available(conn: &mut SqliteConnection, customer_id: i32, now: i64) -> QueryResult<i64> {
+    loyalty_entry::table
+        .filter(loyalty_entry::customer_id.eq(customer_id))
+        .filter(loyalty_entry::kind.eq(KIND_EARN))
+        .filter(loyalty_entry::remaining.gt(0))
+        .filter(loyalty_entry::expires_at.gt(now))
+        .select(loyalty_entry::remaining)
+        .load::<i64>(conn)
+        .map(|lots| lots.iter().sum())
+}
+
+/// Нарахування партії балів за покупку
+pub fn earn(
+    conn: &mut SqliteConnection,
+    customer_id: i32,
+    points: i64,
+    sale_id: Option<i32>,
+    now: i64,
+) -> QueryResult<()> {
+    diesel::insert_into(loyalty_entry::table)
+        .values((
+            loyalty_entry::customer_id.eq(customer_id),
+            loyalty_entry::kind.eq(KIND_EARN),
+            loyalty_entry::points.eq(points),
+            loyalty_entry::remaining.eq(points),
+            loyalty_entry::expires_at.eq(Some(now + EXPIRY_SECS)),
+            loyalty_entry::sale_id.eq(sale_id),
+            loyalty_entry::created_at.eq(now),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Списання балів при оплаті: спершу з партій, що згорають найраніше.
+/// Викликати в транзакції продажу: якщо балів уже не вистачає (їх встигла списати
+/// паралельна покупка), повертає Err, і транзакція відкочує продаж разом із частковим списанням.
+pub fn redeem(
+    conn: &mut SqliteConnection,
+    customer_id: i32,
+    points: i64,
+    sale_id: Option<i32>,
+    now: i64,
+) -> Result<(), String> {
+    let db = |e: diesel::result::Error| e.to_string();
+    let lots: Vec<(i32, i64)> = loyalty_entry::table
+        .filter(loyalty_entry::customer_id.eq(customer_id))
+        .filter(loyalty_entry::kind.eq(KIND_EARN))
+        .filter(loyalty_entry::remaining.gt(0))
+        .filter(loyalty_entry::expires_at.gt(now))
+        .order((loyalty_entry::expires_at, loyalty_entry::id))
+        .select((loyalty_entry::id, loyalty_entry::remaining))
+        .load(conn)
+        .map_err(db)?;
+
+    let available: i64 = lots.iter().map(|(_, remaining)| remaining).sum();
+    if points > available {
+        return Err(format!("Недостатньо балів: доступно {}", available));
+    }
+
+    let mut left = points;
+    for (id, remaining) in lots {
+        if left == 0 {
+            break;
+        }
+        let taken = remaining.min(left);
+        diesel::update(loyalty_entry::table.find(id))
+            .set(loyalty_entry::remaining.eq(remaining - taken))
+            .execute(conn)
+            .map_err(db)?;
+        left -= taken;
+    }
+
+    diesel::insert_into(loyalty_entry::table)
+        .values((
+            loyalty_entry::customer_id.eq(customer_id),
+            loyalty_entry::kind.eq(KIND_REDEEM),
+            loyalty_entry::points.eq(-points),
+            loyalty_entry::sale_id.eq(sale_id),
+            loyalty_entry::created_at.eq(now),
+        ))
+        .execute(conn)
+        .map_err(db)?;
+    Ok(())
+}
+
+/// Фіксує згорілі залишки партій (усіх клієнтів або одного). Повертає кількість партій.
+pub fn expire_due(conn: &mut SqliteConnection, customer_id: Option<i32>, now: i64) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let mut query = loyalty_entry::table
+            .filter(loyalty_entry::kind.eq(KIND_EARN))
+            .filter(loyalty_entry::remaining.gt(0))
+            .filter(loyalty_entry::expires_at.le(now))
+            .select((loyalty_entry::id, loyalty_entry::customer_id, loyalty_entry::remaining, loyalty_entry::expires_at))
+            .into_boxed();
+        if let Some(id) = customer_id {
+            query = query.filter(loyalty_entry::customer_id.eq(id));
+        }
+        let due: Vec<(i32, i32, i64, Option<i64>)> = query.load(conn)?;
+
+        for (id, owner, remaining, expires_at) in &due {
+            diesel::update(loyalty_entry::table.find(id))
+                .set(loyalty_entry::remaining.eq(0))
+                .execute(conn)?;
+            diesel::insert_into(loyalty_entry::table)
+                .values((
+                    loyalty_entry::customer_id.eq(owner),
+                    loyalty_entry::kind.eq(KIND_EXPIRE),
+                    loyalty_entry::points.eq(-remaining),
+                    loyalty_entry::created_at.eq(expires_at.unwrap_or(now)),
+                ))
+                .execute(conn)?;
+        }
+        Ok(due.len())
+    })
+}
+
+/// Бонусний рахунок клієнта для особистого кабінету
+pub fn account(conn: &mut SqliteConnection, customer_id: i32, now: i64) -> QueryResult<LoyaltyAccount> {
+    expire_due(conn, Some(customer_id), now)?;
+    let spent_year = spent_in_window(conn, customer_id, now)?;
+    let (tier, next_tier) = tiers_for(conn, spent_year)?;
+    let points = available(conn, customer_id, now)?;
+
+    // Скільки балів згорить найближчим часом і коли перша партія
+    let soon: Vec<(i64, Option<i64>)> = loyalty_entry::table
+        .filter(loyalty_entry::customer_id.eq(customer_id))
+        .filter(loyalty_entry::kind.eq(KIND_EARN))
+        .filter(loyalty_entry::remaining.gt(0))
+        .filter(loyalty_entry::expires_at.gt(now))
+        .filter(loyalty_entry::expires_at.le(now + EXPIRY_WARNING_SECS))
+        .order(loyalty_entry::expires_at)
+        .select((loyalty_entry::remaining, loyalty_entry::expires_at))
+        .load(conn)?;
+    let expiring = soon
+        .first()
+        .and_then(|(_, at)| *at)
+        .map(|at| (soon.iter().map(|(p, _)| p).sum(), at));
+
+    let history = loyalty_entry::table
+        .filter(loyalty_entry::customer_id.eq(customer_id))
+        .order((loyalty_entry::created_at.desc(), loyalty_entry::id.desc()))
+        .limit(100)
+        .select(LoyaltyEntry::as_select())
+        .load(conn)?;
+
+    Ok(LoyaltyAccount {
+        tier,
+        next_tier,
+        spent_year,
+        points,
+        expiring,
+        history,
+    })
+}
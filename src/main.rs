@@ -2,11 +2,45 @@
 use dioxus::prelude::*;
 //use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "server")]
+mod alerts;
+#[cfg(feature = "server")]
+mod api_keys;
+#[cfg(feature = "server")]
+mod atg;
 mod db;
+#[cfg(feature = "server")]
+mod fleet;
+#[cfg(feature = "server")]
+mod forecast;
+#[cfg(feature = "server")]
+mod forecourt;
 mod models;
+#[cfg(feature = "server")]
+mod notify;
+#[cfg(feature = "server")]
+mod live;
+#[cfg(feature = "server")]
+mod loyalty;
+#[cfg(feature = "server")]
+mod market;
+#[cfg(feature = "server")]
+mod pricing;
+#[cfg(feature = "server")]
+mod promotions;
 mod rate_limit;
+#[cfg(feature = "server")]
+mod reconciliation;
+#[cfg(feature = "server")]
+mod rest;
+#[cfg(feature = "server")]
+mod scheduler;
 mod schema;
+#[cfg(feature = "server")]
+mod staff;
 mod utils;
+#[cfg(feature = "server")]
+mod webhooks;
 
 #[cfg(feature = "server")]
 use axum::{routing::get, Router};
@@ -35,11 +69,47 @@ enum Route {
     #[route("/prices")]
     PricesPage {},
 
+    #[route("/notifications")]
+    NotificationsPage {},
+
+    #[route("/vehicles")]
+    VehiclesPage {},
+
+    #[route("/loyalty")]
+    LoyaltyPage {},
+
     #[route("/admin/dashboard")]
     AdminDashboard {},
 
     #[route("/admin/management")]
     ManagementPage {},
+
+    #[route("/admin/reconciliation")]
+    ReconciliationPage {},
+
+    #[route("/admin/supply")]
+    SupplyPage {},
+
+    #[route("/admin/margin")]
+    MarginPage {},
+
+    #[route("/admin/events")]
+    StaffEventsPage {},
+    #[route("/admin/webhooks")]
+    WebhooksPage {},
+    #[route("/admin/api-keys")]
+    ApiKeysPage {},
+    #[route("/admin/fleet")]
+    FleetPage {},
+    #[route("/admin/loyalty")]
+    LoyaltyTiersPage {},
+    #[route("/admin/promotions")]
+    PromotionsPage {},
+    #[route("/admin/jobs")]
+    JobsPage {},
+
+    #[route("/admin/pricing")]
+    PricingPage {},
 }
 
 fn main() {
@@ -73,6 +143,8 @@ fn main() {
         use axum::routing::get;
         use tower::ServiceBuilder;
 
+        db::run_migrations();
+
         tokio::runtime::Runtime::new()
             .unwrap()
             .block_on(async move {
@@ -81,9 +153,99 @@ fn main() {
                 println!("Listening on {}", addr);
                 let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
 
+                // Симулятор колонок для локальної розробки
+                if let Ok(sim_addr) = std::env::var("PUMP_SIMULATOR_ADDR") {
+                    match forecourt::spawn_simulator(&sim_addr, Default::default()).await {
+                        Ok(a) => println!("LOG: Pump simulator listening on {}", a),
+                        Err(e) => println!("LOG: Pump simulator failed to start: {}", e),
+                    }
+                }
+
+                // Локальний поштовий приймач для перевірки сповіщень без реального SMTP
+                if let Ok(sink_addr) = std::env::var("MAIL_SINK_ADDR") {
+                    match notify::spawn_mail_sink(&sink_addr).await {
+                        Ok((a, _)) => println!("LOG: Mail sink listening on {}", a),
+                        Err(e) => println!("LOG: Mail sink failed to start: {}", e),
+                    }
+                }
+
+                // Прийом звітів рівнеміра (ATG)
+                if let Ok(atg_addr) = std::env::var("ATG_LISTEN_ADDR") {
+                    let started = atg::listen(&atg_addr, |report| {
+                        tokio::task::spawn_blocking(move || {
+                            if let Err(e) = atg::store_report(&report) {
+                                println!("LOG: Failed to store ATG report: {}", e);
+                            }
+                        });
+                    })
+                    .await;
+                    match started {
+                        Ok(a) => println!("LOG: ATG listener on {}", a),
+                        Err(e) => println!("LOG: ATG listener failed to start: {}", e),
+                    }
+                }
+
+                // Фонові задачі
+                scheduler::Scheduler::new()
+                    .job("rate_limit_cleanup", "*/5 * * * *", || {
+                        let removed = rate_limit::cleanup_old_entries(std::time::Duration::from_secs(3600));
+                        Ok(format!("Видалено записів: {}", removed))
+                    })
+                    .job("reorder_forecast", "@hourly", || {
+                        forecast::refresh(&mut db::connection())
+                            .map(|n| format!("Оновлено прогнозів: {}", n))
+                            .map_err(|e| e.to_string())
+                    })
+                    .job("price_schedule", "* * * * *", || {
+                        let mut conn = db::connection();
+                        let scheduled = pricing::apply_due_schedules(&mut conn).map_err(|e| e.to_string())?;
+                        let time_of_day = pricing::apply_time_of_day(&mut conn).map_err(|e| e.to_string())?;
+                        // Розсилка оновлень лише коли ціна справді змінилася, а не щохвилини
+                        if scheduled + time_of_day > 0 {
+                            on_fuels_changed(&mut conn);
+                        }
+                        Ok(format!("За розкладом: {}, за часом доби: {}", scheduled, time_of_day))
+                    })
+                    .job("market_pricing", "15 * * * *", || {
+                        let market = tokio::runtime::Handle::current().block_on(market::current_prices(true))?;
+                        let mut conn = db::connection();
+                        let created = pricing::propose(&mut conn, &market).map_err(|e| e.to_string())?;
+                        if created > 0 {
+                            on_fuels_changed(&mut conn);
+                        }
+                        Ok(format!("Нових пропозицій: {}", created))
+                    })
+                    .job("variance_alerts", "55 23 * * *", || {
+                        let mut conn = db::connection();
+                        let created = reconciliation::build_report(&mut conn, 1)
+                            .and_then(|report| reconciliation::raise_alerts(&mut conn, &report))
+                            .map_err(|e| e.to_string())?;
+                        if created > 0 {
+                            dispatch_staff_events();
+                        }
+                        Ok(format!("Нових сигналів: {}", created))
+                    })
+                    .job("webhook_delivery", "* * * * *", || {
+                        let (delivered, failed) = tokio::runtime::Handle::current().block_on(webhooks::deliver_due())?;
+                        Ok(format!("Доставлено: {}, невдалих спроб: {}", delivered, failed))
+                    })
+                    .job("loyalty_expiry", "10 0 * * *", || {
+                        let expired = loyalty::expire_due(&mut db::connection(), None, chrono::Utc::now().timestamp())
+                            .map_err(|e| e.to_string())?;
+                        Ok(format!("Згоріло партій балів: {}", expired))
+                    })
+                    .job("staff_events", "* * * * *", || {
+                        let created = staff::check_low_stock(&mut db::connection()).map_err(|e| e.to_string())?;
+                        // Також повторює розсилку подій, записаних поза запитами (тести, ручні правки)
+                        let sent = tokio::runtime::Handle::current().block_on(staff::dispatch_pending())?;
+                        Ok(format!("Нових подій: {}, розіслано: {}", created, sent))
+                    })
+                    .start();
+
                 let app = axum::Router::new()
                 .serve_dioxus_application(ServeConfig::new(), App)
-                .route("/api/test/read", axum::routing::get(test_api::test_read_fuels));
+                .route("/api/test/read", axum::routing::get(test_api::test_read_fuels))
+                .merge(rest::router());
 
                 axum::serve(listener, app).await.unwrap();
             });
@@ -97,12 +259,42 @@ fn App() -> Element {
     use_context_provider(|| Signal::new(None::<Customer>));
     use_context_provider(|| Signal::new(None::<Admin>));
 
+    // Останній стан пального з потоку живих оновлень (id → пальне)
+    let mut live_fuels = use_context_provider(|| Signal::new(LiveFuels::new()));
+    use_future(move || async move {
+        // Підписка лише в браузері, не під час серверного рендерингу
+        if cfg!(feature = "server") {
+            return;
+        }
+        loop {
+            if let Ok(mut updates) = live_fuel_updates().await {
+                while let Some(Ok(update)) = updates.recv().await {
+                    live_fuels.write().insert(update.id, update);
+                }
+            }
+            // З'єднання розірвано — перепідключаємось із паузою
+            sleep_ms(5000).await;
+        }
+    });
+
     rsx! {
         link { rel: "stylesheet", href: asset!("/assets/style.css") }
         Router::<Route> {}
     }
 }
 
+type LiveFuels = std::collections::HashMap<i32, models::FuelWithTank>;
+
+#[cfg(target_arch = "wasm32")]
+async fn sleep_ms(ms: u32) {
+    gloo_timers::future::TimeoutFuture::new(ms).await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn sleep_ms(ms: u32) {
+    tokio::time::sleep(std::time::Duration::from_millis(ms as u64)).await;
+}
+
 #[cfg(feature = "server")]
 mod test_api {
     use super::*;
@@ -299,6 +491,7 @@ async fn get_fuels() -> Result<Vec<models::FuelWithTank>, ServerFnError> {
 
         let results: Vec<(Fuel, Tank)> = fuel::table
             .inner_join(tank::table)
+            .select((Fuel::as_select(), Tank::as_select()))
             .load::<(Fuel, Tank)>(&mut conn)
             .map_err(|e| ServerFnError::new(e.to_string()))?;
 
@@ -347,6 +540,7 @@ async fn get_fuels_unblocked() -> Result<Vec<models::FuelWithTank>, ServerFnErro
 
         let results: Vec<(Fuel, Tank)> = fuel::table
             .inner_join(tank::table)
+            .select((Fuel::as_select(), Tank::as_select()))
             .load::<(Fuel, Tank)>(&mut conn)
             .map_err(|e| ServerFnError::new(e.to_string()))?;
 
@@ -379,17 +573,202 @@ async fn get_fuels_unblocked() -> Result<Vec<models::FuelWithTank>, ServerFnErro
     Err(ServerFnError::new("Server only"))
 }
 
+// Потік змін пального (ціна, залишок, доступність) для відкритих сторінок
+#[server]
+async fn live_fuel_updates() -> Result<dioxus::fullstack::ServerEvents<models::FuelWithTank>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let mut updates = live::subscribe();
+        Ok(dioxus::fullstack::ServerEvents::new(move |mut tx| async move {
+            loop {
+                match updates.recv().await {
+                    Ok(update) => {
+                        // Помилка надсилання — клієнт відключився
+                        if tx.send(update).await.is_err() {
+                            break;
+                        }
+                    }
+                    // Частину оновлень пропущено — надсилаємо поточний стан повністю
+                    Err(RecvError::Lagged(_)) => {
+                        let current = live::load_fuels(&mut db::connection()).unwrap_or_default();
+                        for f in current {
+                            if tx.send(f).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[cfg(feature = "server")]
+fn record_sale(conn: &mut diesel::SqliteConnection, new_sale: crate::models::NewSale) -> diesel::QueryResult<i32> {
+    use crate::schema::{fuel, sale};
+    use diesel::prelude::*;
+
+    let sale_id = diesel::insert_into(sale::table)
+        .values(&new_sale)
+        .returning(sale::id)
+        .get_result(conn)?;
+
+    let crate::models::NewSale { customer_id, fuel_id, amount, total, created_at: now, discount, points_value, .. } = new_sale;
+    let fuel_name: String = fuel::table.find(fuel_id).select(fuel::name).first(conn)?;
+    webhooks::enqueue(
+        conn,
+        webhooks::SALE_COMPLETED,
+        serde_json::json!({
+            "sale_id": sale_id,
+            "customer_id": customer_id,
+            "fuel_id": fuel_id,
+            "fuel_name": fuel_name,
+            "amount": amount,
+            "total": total,
+            "discount": discount,
+            "points_value": points_value,
+            "created_at": now,
+        }),
+    )?;
+    Ok(sale_id)
+}
+
+// Остання виміряна температура продукту в резервуарі
+#[cfg(feature = "server")]
+fn tank_temperature(conn: &mut diesel::SqliteConnection, tank_id: i32) -> diesel::QueryResult<Option<f64>> {
+    use crate::schema::tank_reading;
+    use diesel::prelude::*;
+
+    tank_reading::table
+        .filter(tank_reading::tank_id.eq(tank_id))
+        .order(tank_reading::recorded_at.desc())
+        .select(tank_reading::temperature)
+        .first(conn)
+        .optional()
+}
+
+// Об'єм при 15 °C та температура, за якою його розраховано
+// (виміряна при поставці або остання температура резервуару)
+#[cfg(feature = "server")]
+fn net_quantity(
+    conn: &mut diesel::SqliteConnection,
+    tank_id: i32,
+    gross: i32,
+    thermal: Option<utils::ThermalProperties>,
+    measured_temperature: Option<f64>,
+) -> diesel::QueryResult<(Option<f64>, Option<f64>)> {
+    let Some(props) = thermal else {
+        return Ok((None, None));
+    };
+    let temperature = match measured_temperature {
+        Some(t) => t,
+        None => tank_temperature(conn, tank_id)?.unwrap_or(utils::REFERENCE_TEMPERATURE),
+    };
+    Ok((
+        Some(utils::volume_at_15c(gross as f64, temperature, &props)),
+        Some(temperature),
+    ))
+}
+
+// Списує `take` літрів з партій резервуару (FIFO) і повертає собівартість
+#[cfg(feature = "server")]
+fn consume_lots(conn: &mut diesel::SqliteConnection, tank_id: i32, take: i32) -> diesel::QueryResult<i64> {
+    use crate::models::InventoryLot;
+    use crate::schema::inventory_lot;
+    use diesel::prelude::*;
+
+    let lots: Vec<InventoryLot> = inventory_lot::table
+        .filter(inventory_lot::tank_id.eq(tank_id))
+        .filter(inventory_lot::remaining.gt(0))
+        .order((inventory_lot::created_at, inventory_lot::id))
+        .select(InventoryLot::as_select())
+        .load(conn)?;
+
+    let (taken, cost) = utils::consume_fifo(
+        &lots.iter().map(|l| (l.remaining, l.unit_cost)).collect::<Vec<_>>(),
+        take,
+    );
+    for (lot, t) in lots.iter().zip(taken).filter(|(_, t)| *t > 0) {
+        diesel::update(inventory_lot::table.find(lot.id))
+            .set(inventory_lot::remaining.eq(lot.remaining - t))
+            .execute(conn)?;
+    }
+    Ok(cost)
+}
+
+// Списання пального з резервуарів по черзі із записом руху по кожному.
+// Повертає сумарний об'єм при 15 °C.
+#[cfg(feature = "server")]
+fn take_from_tanks(
+    conn: &mut diesel::SqliteConnection,
+    tanks: Vec<models::Tank>,
+    amount: i32,
+    sale_id: i32,
+    now: i64,
+    thermal: Option<utils::ThermalProperties>,
+) -> diesel::QueryResult<Option<f64>> {
+    use crate::models::NewStockMovement;
+    use crate::schema::{sale, stock_movement, tank};
+    use diesel::prelude::*;
+
+    let mut net_total: Option<f64> = None;
+    let mut cogs = 0i64;
+    let mut remaining_to_take = amount;
+    for t in tanks {
+        if remaining_to_take <= 0 {
+            break;
+        }
+        if t.stored <= 0 {
+            continue;
+        }
+
+        let take = std::cmp::min(remaining_to_take, t.stored);
+        let (net, temperature) = net_quantity(conn, t.id, take, thermal, None)?;
+        cogs += consume_lots(conn, t.id, take)?;
+        diesel::update(tank::table.find(t.id))
+            .set(tank::stored.eq(t.stored - take))
+            .execute(conn)?;
+        diesel::insert_into(stock_movement::table)
+            .values(&NewStockMovement {
+                tank_id: t.id,
+                kind: "sale",
+                quantity: -take,
+                sale_id: Some(sale_id),
+                created_at: now,
+                net_quantity: net.map(|n| -n),
+                temperature,
+                delivery_id: None,
+            })
+            .execute(conn)?;
+
+        if let Some(n) = net {
+            net_total = Some(net_total.unwrap_or(0.0) + n);
+        }
+        remaining_to_take -= take;
+    }
+
+    diesel::update(sale::table.find(sale_id))
+        .set((sale::net_amount.eq(net_total), sale::cogs.eq(Some(cogs))))
+        .execute(conn)?;
+    Ok(net_total)
+}
+
 #[server]
 async fn buy_fuel(
     user_id: i32,
     fuel_id: i32,
     amount_needed: i32,
+    vehicle_id: Option<i32>,
     token_str: String,
 ) -> Result<i64, ServerFnError> {
     #[cfg(feature = "server")]
     {
-        use crate::models::{Bank, Tank};
-        use crate::schema::{bank, customer, fuel, tank};
+        use crate::schema::customer;
         use diesel::prelude::*;
 
         let mut conn = db::connection();
@@ -405,83 +784,7 @@ async fn buy_fuel(
             return Err(ServerFnError::new("Неавторизований доступ (Invalid Token)"));
         }
 
-        // Перевірка доступності пального перед транзакцією
-        let (fuel_price, f_type_opt): (i64, Option<String>) = fuel::table
-            .find(fuel_id)
-            .select((fuel::price, fuel::fuel_type))
-            .first(&mut conn)
-            .map_err(|e| ServerFnError::new(format!("Паливо не знайдено: {}", e)))?;
-
-        let f_type = f_type_opt.as_deref().unwrap_or("petrol");
-        let is_electricity = f_type == "electricity";
-
-        let all_tanks: Vec<Tank> = tank::table
-            .filter(tank::fuelid.eq(fuel_id))
-            .load::<Tank>(&mut conn)
-            .map_err(|e| ServerFnError::new(format!("Помилка завантаження резервуарів: {}", e)))?;
-
-        if is_electricity {
-            let any_working = all_tanks.iter().any(|t| t.stored > 0);
-            if !any_working {
-                return Err(ServerFnError::new("Зарядка тимчасово недоступна"));
-            }
-        } else {
-            let total_stored: i32 = all_tanks.iter().map(|t| t.stored).sum();
-            if total_stored < amount_needed {
-                return Err(ServerFnError::new("Недостатньо пального на складі"));
-            }
-        }
-
-        let total_cost = fuel_price * amount_needed as i64;
-        let current_balance: i64 = customer::table
-            .find(user_id)
-            .select(customer::balance)
-            .first(&mut conn)
-            .map_err(|e| ServerFnError::new(format!("Помилка отримання балансу: {}", e)))?;
-
-        if current_balance < total_cost {
-            return Err(ServerFnError::new("Недостатньо коштів на балансі"));
-        }
-
-        // Транзакція
-        conn.transaction::<i64, diesel::result::Error, _>(|conn| {
-            if !is_electricity {
-                let mut remaining_to_take = amount_needed;
-                for t in all_tanks {
-                    if remaining_to_take <= 0 {
-                        break;
-                    }
-                    if t.stored <= 0 {
-                        continue;
-                    }
-
-                    let take = std::cmp::min(remaining_to_take, t.stored);
-                    diesel::update(tank::table.find(t.id))
-                        .set(tank::stored.eq(t.stored - take))
-                        .execute(conn)?;
-
-                    remaining_to_take -= take;
-                }
-            }
-
-            diesel::update(customer::table.find(user_id))
-                .set(customer::balance.eq(current_balance - total_cost))
-                .execute(conn)?;
-
-            let bank_row = bank::table.first::<Bank>(conn).optional()?;
-            if let Some(b) = bank_row {
-                diesel::update(bank::table.find(b.id))
-                    .set(bank::total.eq(b.total + total_cost))
-                    .execute(conn)?;
-            } else {
-                diesel::insert_into(bank::table)
-                    .values(bank::total.eq(total_cost))
-                    .execute(conn)?;
-            }
-
-            Ok(current_balance - total_cost)
-        })
-        .map_err(|e| ServerFnError::new(format!("Помилка транзакції: {}", e)))
+        complete_purchase(&mut conn, user_id, vec![(fuel_id, amount_needed)], vehicle_id, 0, None)
     }
     #[cfg(not(feature = "server"))]
     Err(ServerFnError::new("Server only"))
@@ -491,12 +794,14 @@ async fn buy_fuel(
 async fn buy_fuel_batch(
     user_id: i32,
     items: Vec<(i32, i32)>, // (fuel_id, amount)
+    vehicle_id: Option<i32>,
+    points: i64, // бали лояльності в оплату
+    coupon: Option<String>,
     token_str: String,
 ) -> Result<i64, ServerFnError> {
     #[cfg(feature = "server")]
     {
-        use crate::models::{Bank, Tank};
-        use crate::schema::{bank, customer, fuel, tank};
+        use crate::schema::customer;
         use diesel::prelude::*;
 
         let mut conn = db::connection();
@@ -512,104 +817,260 @@ async fn buy_fuel_batch(
             return Err(ServerFnError::new("Неавторизований доступ (Invalid Token)"));
         }
 
-        if items.is_empty() {
-            return Err(ServerFnError::new("Кошик пустий"));
-        }
+        complete_purchase(&mut conn, user_id, items, vehicle_id, points, coupon)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
 
-        // Перевірка доступності пального та розрахунок вартості
-        let mut total_cost: i64 = 0;
-        let mut updates = Vec::new();
+// Відмова всередині транзакції покупки (причина для клієнта) або помилка БД — обидві
+// відкочують продаж повністю
+#[cfg(feature = "server")]
+enum PurchaseError {
+    Rejected(String),
+    Database(diesel::result::Error),
+}
 
-        for (f_id, amount) in &items {
-            let (fuel_price, f_type_opt): (i64, Option<String>) = fuel::table
-                .find(f_id)
-                .select((fuel::price, fuel::fuel_type))
-                .first(&mut conn)
-                .map_err(|e| ServerFnError::new(format!("Паливо не знайдено: {}", e)))?;
+#[cfg(feature = "server")]
+impl From<diesel::result::Error> for PurchaseError {
+    fn from(e: diesel::result::Error) -> Self {
+        PurchaseError::Database(e)
+    }
+}
 
-            let f_type = f_type_opt.as_deref().unwrap_or("petrol");
-            let is_electricity = f_type == "electricity";
-            let cost = fuel_price * (*amount as i64);
-            total_cost += cost;
+#[cfg(feature = "server")]
+impl From<String> for PurchaseError {
+    fn from(reason: String) -> Self {
+        PurchaseError::Rejected(reason)
+    }
+}
 
-            let all_tanks: Vec<Tank> = tank::table
-                .filter(tank::fuelid.eq(f_id))
-                .load::<Tank>(&mut conn)
-                .map_err(|e| ServerFnError::new(format!("Помилка завантаження резервуарів: {}", e)))?;
+// Покупка після перевірки доступу (сесія або API-ключ). Водій автопарку платить з рахунку
+// компанії в межах своїх лімітів, решта клієнтів — зі свого балансу. Якщо вибрано авто,
+// пальне має підходити йому і вміщатися в бак. Клієнт може оплатити частину суми балами
+// лояльності (points) і отримує бали за сплачене. Спершу застосовується найвигідніша акція
+// (автоматична або за купоном), бали — до суми після знижки. Повертає баланс клієнта.
+#[cfg(feature = "server")]
+fn complete_purchase(
+    conn: &mut diesel::SqliteConnection,
+    user_id: i32,
+    items: Vec<(i32, i32)>,
+    vehicle_id: Option<i32>,
+    points: i64,
+    coupon: Option<String>,
+) -> Result<i64, ServerFnError> {
+    use crate::models::{Bank, FleetCompany, NewSale, Tank, Vehicle};
+    use crate::schema::{bank, customer, fleet_company, fuel, tank, vehicle};
+    use diesel::prelude::*;
 
-            if is_electricity {
-                let any_working = all_tanks.iter().any(|t| t.stored > 0);
-                if !any_working {
-                    return Err(ServerFnError::new("Зарядка тимчасово недоступна"));
-                }
-            } else {
-                let total_stored: i32 = all_tanks.iter().map(|t| t.stored).sum();
-                if total_stored < *amount {
-                    return Err(ServerFnError::new("Недостатньо пального на складі"));
-                }
-            }
+    if items.is_empty() {
+        return Err(ServerFnError::new("Кошик пустий"));
+    }
+
+    // Перевірка доступності пального та розрахунок вартості
+    let mut total_cost: i64 = 0;
+    let mut updates = Vec::new();
+    let mut fuel_kinds = Vec::new();
+
+    for (f_id, amount) in &items {
+        let (fuel_name, fuel_price, f_type_opt, density, expansion_coeff): (
+            String,
+            i64,
+            Option<String>,
+            Option<f64>,
+            Option<f64>,
+        ) = fuel::table
+            .find(f_id)
+            .select((fuel::name, fuel::price, fuel::fuel_type, fuel::density, fuel::expansion_coeff))
+            .first(conn)
+            .map_err(|e| ServerFnError::new(format!("Паливо не знайдено: {}", e)))?;
+
+        let f_type = f_type_opt.as_deref().unwrap_or("petrol");
+        fuel_kinds.push((fuel_name, f_type.to_string()));
+        let is_electricity = f_type == "electricity";
+        let thermal = utils::ThermalProperties::for_fuel(f_type, density, expansion_coeff);
+        let cost = fuel_price * (*amount as i64);
+        total_cost += cost;
 
-            updates.push((*f_id, *amount, is_electricity, all_tanks));
+        let all_tanks: Vec<Tank> = tank::table
+            .filter(tank::fuelid.eq(f_id))
+            .select(Tank::as_select())
+            .load::<Tank>(conn)
+            .map_err(|e| ServerFnError::new(format!("Помилка завантаження резервуарів: {}", e)))?;
+
+        if is_electricity {
+            let any_working = all_tanks.iter().any(|t| t.stored > 0);
+            if !any_working {
+                return Err(ServerFnError::new("Зарядка тимчасово недоступна"));
+            }
+        } else {
+            let total_stored: i32 = all_tanks.iter().map(|t| t.stored).sum();
+            if total_stored < *amount {
+                return Err(ServerFnError::new("Недостатньо пального на складі"));
+            }
         }
 
-        // Перевірка балансу
-        let current_balance: i64 = customer::table
-            .find(user_id)
-            .select(customer::balance)
-            .first(&mut conn)
-            .map_err(|e| ServerFnError::new(format!("Помилка отримання балансу: {}", e)))?;
+        updates.push((*f_id, *amount, cost, is_electricity, thermal, all_tanks));
+    }
+
+    // Сумісність з авто клієнта
+    if let Some(v_id) = vehicle_id {
+        let car: Vehicle = vehicle::table
+            .find(v_id)
+            .filter(vehicle::customer_id.eq(user_id))
+            .select(Vehicle::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| ServerFnError::new(e.to_string()))?
+            .ok_or_else(|| ServerFnError::new("Авто не знайдено"))?;
+        let fills: Vec<(String, String, i32)> = fuel_kinds
+            .iter()
+            .zip(&items)
+            .map(|((name, f_type), (_, amount))| (name.clone(), f_type.clone(), *amount))
+            .collect();
+        utils::check_vehicle_fill(&car.plate, &car.fuel_type, car.tank_capacity, &fills)
+            .map_err(ServerFnError::new)?;
+    }
 
-        if current_balance < total_cost {
-            return Err(ServerFnError::new("Недостатньо коштів на балансі"));
+    // Перевірка балансу
+    let current_balance: i64 = customer::table
+        .find(user_id)
+        .select(customer::balance)
+        .first(conn)
+        .map_err(|e| ServerFnError::new(format!("Помилка отримання балансу: {}", e)))?;
+
+    let now = chrono::Utc::now().timestamp();
+    let driver = fleet::driver_for(conn, user_id).map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    // Акції: знижка по кожному рядку кошика
+    let lines: Vec<(i32, i32, i64)> = updates.iter().map(|u| (u.0, u.1, u.2)).collect();
+    let promo = promotions::best_for(conn, user_id, &lines, coupon.as_deref(), chrono::Local::now())
+        .map_err(ServerFnError::new)?;
+    let discounts = promo.as_ref().map_or_else(|| vec![0; lines.len()], |p| p.discounts.clone());
+    let total_due = total_cost - discounts.iter().sum::<i64>();
+
+    // Бали лояльності: списання зменшує суму до сплати (лише для особистого балансу)
+    if points < 0 {
+        return Err(ServerFnError::new("Кількість балів не може бути від'ємною"));
+    }
+    if points > 0 {
+        if driver.is_some() {
+            return Err(ServerFnError::new("Бали не можна використати при оплаті рахунком автопарку"));
         }
+        let available = loyalty::available(conn, user_id, now).map_err(|e| ServerFnError::new(e.to_string()))?;
+        if points > available {
+            return Err(ServerFnError::new(format!("Недостатньо балів: доступно {}", available)));
+        }
+        if points * loyalty::POINT_VALUE > total_due {
+            return Err(ServerFnError::new("Бали перевищують суму покупки"));
+        }
+    }
+    let to_pay = total_due - points * loyalty::POINT_VALUE;
 
-        // Виконуємо транзакцію
-        conn.transaction::<i64, diesel::result::Error, _>(|conn| {
+    // Ліміти водія та баланс компанії перевіряються в транзакції — щоб паралельні покупки їх не обійшли
+    if driver.is_none() && current_balance < to_pay {
+        return Err(ServerFnError::new("Недостатньо коштів на балансі"));
+    }
 
-            for (_f_id, amount, is_electricity, all_tanks) in updates {
-                if !is_electricity {
-                    let mut remaining_to_take = amount;
-                    for t in all_tanks {
-                        if remaining_to_take <= 0 {
-                            break;
-                        }
-                        if t.stored <= 0 {
-                            continue;
-                        }
+    // Рівень лояльності — за покупками до цієї; водіям автопарку бали не нараховуються
+    let tier = match driver {
+        Some(_) => None,
+        None => {
+            let spent = loyalty::spent_in_window(conn, user_id, now).map_err(|e| ServerFnError::new(e.to_string()))?;
+            Some(loyalty::tiers_for(conn, spent).map_err(|e| ServerFnError::new(e.to_string()))?.0)
+        }
+    };
+    let volume: i64 = items.iter().map(|(_, amount)| *amount as i64).sum();
+
+    // Виконуємо транзакцію
+    let new_balance = conn.transaction::<i64, PurchaseError, _>(|conn| {
+        if let Some((d, company)) = &driver {
+            // Свіжий стан компанії вже всередині транзакції
+            let company = fleet_company::table
+                .find(company.id)
+                .select(FleetCompany::as_select())
+                .first(conn)?;
+            fleet::check_purchase(conn, d, &company, &fuel_kinds, total_due, chrono::Local::now().date_naive())?;
+        }
+
+        // Оплачене балами розподіляється по рядках кошика, поки не покриє суму кожного
+        let mut points_left = points * loyalty::POINT_VALUE;
+        let mut first_sale = None;
+        for ((f_id, amount, cost, is_electricity, thermal, all_tanks), discount) in updates.into_iter().zip(&discounts) {
+            let points_value = points_left.min(cost - discount);
+            points_left -= points_value;
+            let sale_id = record_sale(
+                conn,
+                NewSale {
+                    customer_id: user_id,
+                    fuel_id: f_id,
+                    amount,
+                    total: cost,
+                    created_at: now,
+                    vehicle_id,
+                    discount: *discount,
+                    promotion_id: promo.as_ref().map(|p| p.promotion.id),
+                    points_value,
+                },
+            )?;
+            first_sale.get_or_insert(sale_id);
+            if !is_electricity {
+                take_from_tanks(conn, all_tanks, amount, sale_id, now, thermal)?;
+            }
+            if let Some((d, _)) = &driver {
+                fleet::record_purchase(conn, d, sale_id, cost - discount, now)?;
+            }
+        }
 
-                        let take = std::cmp::min(remaining_to_take, t.stored);
-                        diesel::update(tank::table.find(t.id))
-                            .set(tank::stored.eq(t.stored - take))
-                            .execute(conn)?;
+        if let (Some(applied), Some(sale_id)) = (&promo, first_sale) {
+            promotions::record_use(conn, &applied.promotion, user_id, sale_id, applied.total(), now)?;
+        }
 
-                        remaining_to_take -= take;
-                    }
+        // Бали прив'язуються до першого продажу покупки. redeem ще раз перевіряє залишок уже
+        // в транзакції: бали, списані паралельною покупкою, відкотять цей продаж
+        if let Some(sale_id) = first_sale {
+            if points > 0 {
+                loyalty::redeem(conn, user_id, points, Some(sale_id), now)?;
+            }
+            if let Some(tier) = &tier {
+                let earned = utils::loyalty_points(volume, to_pay, tier.points_per_litre, tier.points_per_100_uah);
+                if earned > 0 {
+                    loyalty::earn(conn, user_id, earned, Some(sale_id), now)?;
                 }
             }
+        }
 
-            //Update user balance
+        //Update user balance (водію списано з рахунку компанії вище)
+        let balance_after = if driver.is_some() {
+            current_balance
+        } else {
             diesel::update(customer::table.find(user_id))
-                .set(customer::balance.eq(current_balance - total_cost))
+                .set(customer::balance.eq(current_balance - to_pay))
+                .execute(conn)?;
+            current_balance - to_pay
+        };
+
+        //Update bank (оплачене балами — знижка, грошей не надходить)
+        let bank_row = bank::table.first::<Bank>(conn).optional()?;
+        if let Some(b) = bank_row {
+            diesel::update(bank::table.find(b.id))
+                .set(bank::total.eq(b.total + to_pay))
+                .execute(conn)?;
+        } else {
+            diesel::insert_into(bank::table)
+                .values(bank::total.eq(to_pay))
                 .execute(conn)?;
+        }
 
-            //Update bank
-            let bank_row = bank::table.first::<Bank>(conn).optional()?;
-            if let Some(b) = bank_row {
-                diesel::update(bank::table.find(b.id))
-                    .set(bank::total.eq(b.total + total_cost))
-                    .execute(conn)?;
-            } else {
-                diesel::insert_into(bank::table)
-                    .values(bank::total.eq(total_cost))
-                    .execute(conn)?;
-            }
+        Ok(balance_after)
+    })
+    .map_err(|e| match e {
+        PurchaseError::Rejected(reason) => ServerFnError::new(reason),
+        PurchaseError::Database(e) => ServerFnError::new(format!("Помилка транзакції: {}", e)),
+    })?;
 
-            Ok(current_balance - total_cost)
-        })
-        .map_err(|e| ServerFnError::new(format!("Помилка транзакції: {}", e)))
-    }
-    #[cfg(not(feature = "server"))]
-    Err(ServerFnError::new("Server only"))
+    on_fuels_changed(conn);
+    Ok(new_balance)
 }
 
 #[server]
@@ -620,497 +1081,6643 @@ async fn update_fuel_price(
 ) -> Result<(), ServerFnError> {
     #[cfg(feature = "server")]
     {
-        use crate::schema::{admin, fuel};
-        use diesel::prelude::*;
-
         let mut conn = db::connection();
+        let login = verify_admin(&mut conn, &token_str)?;
 
-        // Перевірка токену адміна
-        let count = admin::table
-            .filter(admin::session_token.eq(&token_str))
-            .count()
-            .get_result::<i64>(&mut conn)
-            .unwrap_or(0);
-
-        if count == 0 {
-            return Err(ServerFnError::new("Unauthorized Admin"));
+        if new_price <= 0 {
+            return Err(ServerFnError::new("Ціна має бути додатною"));
         }
 
-        diesel::update(fuel::table.find(fuel_id))
-            .set(fuel::price.eq(new_price))
-            .execute(&mut conn)
+        pricing::set_base_price(&mut conn, fuel_id, new_price, "manual", Some(&login))
             .map_err(|e| ServerFnError::new(e.to_string()))?;
+        on_fuels_changed(&mut conn);
         Ok(())
     }
     #[cfg(not(feature = "server"))]
     Err(ServerFnError::new("Server only"))
 }
 
+// Увімкнення/вимкнення зарядної станції: залишок електрики — ознака доступності
 #[server]
-async fn refill_fuel(fuel_id: i32, amount: i32, token_str: String) -> Result<(), ServerFnError> {
+async fn set_charger_available(fuel_id: i32, available: bool, token_str: String) -> Result<(), ServerFnError> {
     #[cfg(feature = "server")]
     {
-        use crate::models::{Bank, Tank};
-        use crate::schema::{admin, bank, fuel, tank};
+        use crate::schema::{fuel, tank};
         use diesel::prelude::*;
 
         let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
 
-        // Перевірка токену адміна
-        let count = admin::table
-            .filter(admin::session_token.eq(&token_str))
-            .count()
-            .get_result::<i64>(&mut conn)
-            .unwrap_or(0);
-
-        if count == 0 {
-            return Err(ServerFnError::new("Unauthorized Admin"));
-        }
-
-        // Перевірка ціни та розрахунок вартості
-        let price: i64 = fuel::table
+        let fuel_type: Option<String> = fuel::table
             .find(fuel_id)
-            .select(fuel::price)
+            .select(fuel::fuel_type)
             .first(&mut conn)
             .map_err(|e| ServerFnError::new(format!("Паливо не знайдено: {}", e)))?;
-        
-        let cost_per_unit = price / 2;
-        let total_cost = cost_per_unit * amount as i64;
-
-        // Перевірка балансу банку
-        let bank_row = bank::table
-            .first::<Bank>(&mut conn)
-            .optional()
-            .map_err(|e| ServerFnError::new(format!("Помилка отримання балансу банку: {}", e)))?;
-        
-        let current_bank_total = bank_row.as_ref().map(|b| b.total).unwrap_or(0);
+        if fuel_type.as_deref() != Some("electricity") {
+            return Err(ServerFnError::new("Доступність вмикається лише для зарядки"));
+        }
 
-        if current_bank_total < total_cost {
-            return Err(ServerFnError::new("Недостатньо коштів у банку"));
+        let target = tank::table.filter(tank::fuelid.eq(fuel_id));
+        if available {
+            diesel::update(target).set(tank::stored.eq(tank::capacity)).execute(&mut conn)
+        } else {
+            diesel::update(target).set(tank::stored.eq(0)).execute(&mut conn)
         }
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
 
-        if bank_row.is_none() {
-            return Err(ServerFnError::new("Банк не ініціалізовано"));
+        match staff::charger_status(&mut conn, fuel_id, available) {
+            Ok(true) => dispatch_staff_events(),
+            Ok(false) => {}
+            Err(e) => println!("LOG: Failed to record charger event: {}", e),
         }
+        on_fuels_changed(&mut conn);
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
 
-        // Перевірка наявності місця в резервуарах
-        let tanks: Vec<Tank> = tank::table
-            .filter(tank::fuelid.eq(fuel_id))
-            .load::<Tank>(&mut conn)
-            .map_err(|e| ServerFnError::new(format!("Помилка завантаження резервуарів: {}", e)))?;
+#[server]
+async fn get_price_timeline(fuel_id: i32, token_str: String) -> Result<models::PriceTimeline, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::{PriceHistory, PriceSchedule, PriceTimeline};
+        use crate::schema::{fuel, price_history, price_schedule};
+        use diesel::prelude::*;
 
-        let total_space: i32 = tanks.iter().map(|t| t.capacity - t.stored).sum();
-        if total_space < amount {
-            return Err(ServerFnError::new("Недостатньо місця в резервуарах"));
-        }
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
 
-        // Виконуємо транзакцію
-        conn.transaction::<_, diesel::result::Error, _>(|conn| {
-            if let Some(b) = bank_row {
-                diesel::update(bank::table.find(b.id))
-                    .set(bank::total.eq(b.total - total_cost))
-                    .execute(conn)?;
-            }
+        let (current_price, base_price): (i64, i64) = fuel::table
+            .find(fuel_id)
+            .select((fuel::price, fuel::base_price))
+            .first(&mut conn)
+            .map_err(|e| ServerFnError::new(format!("Паливо не знайдено: {}", e)))?;
 
-            let mut remaining = amount;
-            for t in tanks {
-                if remaining <= 0 {
-                    break;
-                }
-                let space = t.capacity - t.stored;
-                if space > 0 {
-                    let add = std::cmp::min(remaining, space);
-                    diesel::update(tank::table.find(t.id))
-                        .set(tank::stored.eq(t.stored + add))
-                        .execute(conn)?;
-                    remaining -= add;
-                }
-            }
+        let history = price_history::table
+            .filter(price_history::fuel_id.eq(fuel_id))
+            .order(price_history::changed_at.desc())
+            .limit(50)
+            .select(PriceHistory::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
 
-            Ok(())
+        let upcoming = price_schedule::table
+            .filter(price_schedule::fuel_id.eq(fuel_id))
+            .filter(price_schedule::applied_at.is_null())
+            .filter(price_schedule::cancelled.eq(false))
+            .order(price_schedule::effective_at)
+            .select(PriceSchedule::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        let rules = pricing::rules_for(&mut conn, fuel_id).map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        Ok(PriceTimeline {
+            base_price,
+            current_price,
+            history,
+            upcoming,
+            rules,
         })
-        .map_err(|e| ServerFnError::new(format!("Помилка транзакції: {}", e)))
     }
     #[cfg(not(feature = "server"))]
     Err(ServerFnError::new("Server only"))
 }
 
+// Історія діючої ціни за останні `range_days` днів; перша точка — ціна на початок періоду
 #[server]
-async fn get_bank_info() -> Result<models::Bank, ServerFnError> {
+async fn get_price_history(fuel_id: i32, range_days: u32) -> Result<Vec<models::PricePoint>, ServerFnError> {
     #[cfg(feature = "server")]
     {
-        use crate::models::Bank;
-        use crate::schema::bank;
+        use crate::models::PricePoint;
+        use crate::schema::{fuel, price_history};
         use diesel::prelude::*;
 
         let mut conn = db::connection();
-        let bank_row = bank::table
-            .first::<Bank>(&mut conn)
+        let from = chrono::Utc::now().timestamp() - range_days.max(1) as i64 * 86_400;
+
+        let before: Option<i64> = price_history::table
+            .filter(price_history::fuel_id.eq(fuel_id))
+            .filter(price_history::changed_at.lt(from))
+            .order((price_history::changed_at.desc(), price_history::id.desc()))
+            .select(price_history::price)
+            .first(&mut conn)
             .optional()
             .map_err(|e| ServerFnError::new(e.to_string()))?;
 
-        match bank_row {
-            Some(b) => Ok(b),
-            None => Ok(Bank { id: 0, total: 0 }),
+        let changes: Vec<(i64, i64)> = price_history::table
+            .filter(price_history::fuel_id.eq(fuel_id))
+            .filter(price_history::changed_at.ge(from))
+            .order((price_history::changed_at, price_history::id))
+            .select((price_history::changed_at, price_history::price))
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        let mut points: Vec<PricePoint> = before
+            .map(|price| PricePoint { at: from, price })
+            .into_iter()
+            .chain(changes.into_iter().map(|(at, price)| PricePoint { at, price }))
+            .collect();
+
+        if points.is_empty() {
+            let price: i64 = fuel::table
+                .find(fuel_id)
+                .select(fuel::price)
+                .first(&mut conn)
+                .map_err(|e| ServerFnError::new(format!("Паливо не знайдено: {}", e)))?;
+            points.push(PricePoint { at: from, price });
         }
+        Ok(points)
     }
     #[cfg(not(feature = "server"))]
     Err(ServerFnError::new("Server only"))
 }
 
+// Планування зміни базової ціни. `effective_at` — місцевий час у форматі "YYYY-MM-DDTHH:MM".
 #[server]
-async fn fetch_fuel_prices() -> Result<Vec<models::FuelPriceStats>, ServerFnError> {
+async fn schedule_price_change(
+    fuel_id: i32,
+    price: i64,
+    effective_at: String,
+    token_str: String,
+) -> Result<(), ServerFnError> {
     #[cfg(feature = "server")]
     {
-        use models::FuelPriceStats;
-        use reqwest::get;
-        use scraper::{Html, Selector};
-
-        let url = "https://index.minfin.com.ua/ua/markets/fuel/tm/";
+        use crate::schema::price_schedule;
+        use chrono::TimeZone;
+        use diesel::prelude::*;
 
-        let response = get(url)
-            .await
-            .map_err(|e| ServerFnError::new(format!("Помилка завантаження: {}", e)))?;
-        let html_content = response
-            .text()
-            .await
-            .map_err(|e| ServerFnError::new(format!("Помилка читання: {}", e)))?;
-        let document = Html::parse_document(&html_content);
-
-        let row_selector = Selector::parse("table tr")
-            .map_err(|e| ServerFnError::new(format!("Помилка селектора: {}", e)))?;
-        let cell_selector = Selector::parse("td")
-            .map_err(|e| ServerFnError::new(format!("Помилка селектора: {}", e)))?;
-
-        let mut a95_premium = Vec::new();
-        let mut a95 = Vec::new();
-        let mut a92 = Vec::new();
-        let mut diesel = Vec::new();
-        let mut gas = Vec::new();
-
-        for row in document.select(&row_selector) {
-            let cells: Vec<String> = row
-                .select(&cell_selector)
-                .map(|c| c.text().collect::<Vec<_>>().join("").trim().to_string())
-                .collect();
+        let mut conn = db::connection();
+        let login = verify_admin(&mut conn, &token_str)?;
 
-            if cells.len() >= 6 && !cells[0].is_empty() {
-                if let Some(price) = parse_price(&cells[1]) {
-                    a95_premium.push(price);
-                }
-                if let Some(price) = parse_price(&cells[2]) {
-                    a95.push(price);
-                }
-                if let Some(price) = parse_price(&cells[3]) {
-                    a92.push(price);
-                }
-                if let Some(price) = parse_price(&cells[4]) {
-                    diesel.push(price);
-                }
-                if let Some(price) = parse_price(&cells[5]) {
-                    gas.push(price);
-                }
-            }
+        if price <= 0 {
+            return Err(ServerFnError::new("Ціна має бути додатною"));
         }
+        let effective = chrono::NaiveDateTime::parse_from_str(&effective_at, "%Y-%m-%dT%H:%M")
+            .ok()
+            .and_then(|dt| chrono::Local.from_local_datetime(&dt).earliest())
+            .ok_or_else(|| ServerFnError::new("Некоректна дата"))?
+            .timestamp();
+        let now = chrono::Utc::now().timestamp();
+        if effective <= now {
+            return Err(ServerFnError::new("Дата має бути в майбутньому"));
+        }
+
+        diesel::insert_into(price_schedule::table)
+            .values((
+                price_schedule::fuel_id.eq(fuel_id),
+                price_schedule::price.eq(price),
+                price_schedule::effective_at.eq(effective),
+                price_schedule::created_by.eq(&login),
+                price_schedule::created_at.eq(now),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
 
-        let mut results = Vec::new();
+#[server]
+async fn cancel_price_change(schedule_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::price_schedule;
+        use diesel::prelude::*;
 
-        if !a95_premium.is_empty() {
-            results.push(calculate_stats("А-95 Преміум", &a95_premium));
-        }
-        if !a95.is_empty() {
-            results.push(calculate_stats("А-95", &a95));
-        }
-        if !a92.is_empty() {
-            results.push(calculate_stats("А-92", &a92));
-        }
-        if !diesel.is_empty() {
-            results.push(calculate_stats("Дизель", &diesel));
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let updated = diesel::update(
+            price_schedule::table
+                .find(schedule_id)
+                .filter(price_schedule::applied_at.is_null()),
+        )
+        .set(price_schedule::cancelled.eq(true))
+        .execute(&mut conn)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        if updated == 0 {
+            return Err(ServerFnError::new("Зміну вже застосовано"));
         }
-        if !gas.is_empty() {
-            results.push(calculate_stats("Газ", &gas));
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Правило ціни за часом доби, `start`/`end` у форматі "HH:MM"
+#[server]
+async fn create_price_rule(
+    fuel_id: i32,
+    start: String,
+    end: String,
+    price: i64,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::price_rule;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        let login = verify_admin(&mut conn, &token_str)?;
+
+        let (Some(start_minute), Some(end_minute)) = (utils::parse_time_of_day(&start), utils::parse_time_of_day(&end)) else {
+            return Err(ServerFnError::new("Час має бути у форматі ГГ:ХХ"));
+        };
+        if start_minute == end_minute || price <= 0 {
+            return Err(ServerFnError::new("Некоректне правило"));
         }
 
-        Ok(results)
+        diesel::insert_into(price_rule::table)
+            .values((
+                price_rule::fuel_id.eq(fuel_id),
+                price_rule::start_minute.eq(start_minute),
+                price_rule::end_minute.eq(end_minute),
+                price_rule::price.eq(price),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        pricing::refresh_price(&mut conn, fuel_id, "time_of_day", Some(&login))
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
     }
     #[cfg(not(feature = "server"))]
     Err(ServerFnError::new("Server only"))
 }
 
-#[cfg(feature = "server")]
-fn parse_price(raw: &str) -> Option<f64> {
-    if raw.is_empty() || raw == "-" {
-        return None;
+#[server]
+async fn delete_price_rule(rule_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::price_rule;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        let login = verify_admin(&mut conn, &token_str)?;
+
+        let fuel_id: i32 = price_rule::table
+            .find(rule_id)
+            .select(price_rule::fuel_id)
+            .first(&mut conn)
+            .map_err(|e| ServerFnError::new(format!("Правило не знайдено: {}", e)))?;
+
+        diesel::delete(price_rule::table.find(rule_id))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        pricing::refresh_price(&mut conn, fuel_id, "time_of_day", Some(&login))
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
     }
-    raw.replace(",", ".").parse::<f64>().ok()
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
 }
 
-#[cfg(feature = "server")]
-fn calculate_stats(name: &str, prices: &[f64]) -> models::FuelPriceStats {
-    let sum: f64 = prices.iter().sum();
-    let count = prices.len();
-    let avg = sum / count as f64;
-    let min = prices.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-    let max = prices.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+#[server]
+async fn get_market_rules(token_str: String) -> Result<Vec<models::MarketPricingRule>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::MarketPricingRule;
+        use crate::schema::market_pricing_rule;
+        use diesel::prelude::*;
 
-    models::FuelPriceStats {
-        name: name.to_string(),
-        average: avg,
-        min,
-        max,
-        count,
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        market_pricing_rule::table
+            .order(market_pricing_rule::fuel_id)
+            .select(MarketPricingRule::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
     }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
 }
 
-#[component]
-fn Navbar() -> Element {
-    let mut user_state = use_context::<Signal<Option<Customer>>>();
-    let mut admin_state = use_context::<Signal<Option<Admin>>>();
-    let nav = use_navigator();
-    let fmt_price = |cents: i64| format!("{:.2} грн", cents as f64 / 100.0);
-
-    let handle_logout = move |_| {
-        user_state.set(None);
-        admin_state.set(None);
-        nav.push(Route::LoginPage {});
-    };
+#[server]
+async fn set_market_rule(
+    fuel_id: i32,
+    market_offset: i64,
+    margin_floor: i64,
+    auto_apply: bool,
+    enabled: bool,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::market_pricing_rule;
+        use diesel::prelude::*;
 
-    rsx! {
-        nav { class: "navbar",
-            div { class: "nav-brand",
-                Link { to: Route::Home {}, "GasStation" }
-            }
-            div { class: "nav-links",
-                Link { to: Route::Home {}, class: "nav-item", "Головна" }
-                Link { to: Route::PricesPage {}, class: "nav-item", "Ціни" }
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
 
-                if let Some(user) = user_state() {
-                    div { class: "user-badge",
-                        span { class: "user-name", "{user.login}" }
-                        span { class: "user-balance", "{fmt_price(user.balance)}" }
-                    }
-                    button { class: "nav-item logout-btn", onclick: handle_logout, "Вийти" }
-                } else if let Some(admin) = admin_state() {
-                    div { class: "user-badge",
-                        style: "background-color: #4309a7ff; color: white;",
-                        span { class: "user-name", "Admin: {admin.login}" }
-                    }
-                    //Link { to: Route::AdminDashboard {}, class: "nav-item", "Дашборд" }
-                    Link { to: Route::ManagementPage {}, class: "nav-item", "Керування" }
-                    button { class: "nav-item logout-btn", onclick: handle_logout, "Вийти" }
-                } else {
-                    Link { to: Route::LoginPage {}, class: "nav-item", "Вхід" }
-                    Link { to: Route::RegisterPage {}, class: "nav-item highlight", "Реєстрація" }
-                }
-            }
+        if margin_floor < 0 {
+            return Err(ServerFnError::new("Мінімальна націнка не може бути від'ємною"));
         }
-        Outlet::<Route> {}
+
+        diesel::insert_into(market_pricing_rule::table)
+            .values((
+                market_pricing_rule::fuel_id.eq(fuel_id),
+                market_pricing_rule::market_offset.eq(market_offset),
+                market_pricing_rule::margin_floor.eq(margin_floor),
+                market_pricing_rule::auto_apply.eq(auto_apply),
+                market_pricing_rule::enabled.eq(enabled),
+            ))
+            .on_conflict(market_pricing_rule::fuel_id)
+            .do_update()
+            .set((
+                market_pricing_rule::market_offset.eq(market_offset),
+                market_pricing_rule::margin_floor.eq(margin_floor),
+                market_pricing_rule::auto_apply.eq(auto_apply),
+                market_pricing_rule::enabled.eq(enabled),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
     }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
 }
 
-// Helper function to clean error messages
-fn clean_error_msg(error: String) -> String {
-    error
-        .replace("error running server function: ", "")
-        .replace(" (details: None)", "")
+// Попередній перегляд ринкових правил за поточними цінами ринку (без змін у БД)
+#[server]
+async fn preview_market_pricing(token_str: String) -> Result<Vec<models::PricePreview>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        verify_admin(&mut db::connection(), &token_str)?;
+        let market = market::current_prices(false).await.map_err(ServerFnError::new)?;
+
+        pricing::preview(&mut db::connection(), &market).map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
 }
 
-#[component]
-fn FuelCard(props: models::FuelCardProps) -> Element {
-    let item = props.item;
-    let mut cart = props.cart;
-    let mut amount = use_signal(|| 1);
-    let mut user_state = use_context::<Signal<Option<Customer>>>();
-    let mut error_msg = use_signal(|| "".to_string());
-    let nav = use_navigator();
+#[server]
+async fn run_market_pricing(token_str: String) -> Result<usize, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        verify_admin(&mut db::connection(), &token_str)?;
+        let market = market::current_prices(false).await.map_err(ServerFnError::new)?;
 
-    let is_electric = item.fuel_type == "electricity";
+        let mut conn = db::connection();
+        let created = pricing::propose(&mut conn, &market).map_err(|e| ServerFnError::new(e.to_string()))?;
+        on_fuels_changed(&mut conn);
+        Ok(created)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
 
-    let icon = match item.fuel_type.as_str() {
-        "electricity" => "⚡",
-        "gas" => "☁️",
-        "diesel" => "🛢️",
-        _ => "⛽",
-    };
+#[server]
+async fn get_price_proposals(token_str: String) -> Result<Vec<models::PriceProposal>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::PriceProposal;
+        use crate::schema::price_proposal;
+        use diesel::prelude::*;
 
-    let unit = if is_electric { "кВт год" } else { "л" };
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        price_proposal::table
+            .filter(price_proposal::status.eq("pending"))
+            .order(price_proposal::created_at.desc())
+            .select(PriceProposal::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
 
-    let price_val = item.price;
-    let total_cost = price_val * amount() as i64;
-    let fmt_money = |cents: i64| format!("{:.2}", cents as f64 / 100.0);
+#[server]
+async fn decide_price_proposal(proposal_id: i32, approve: bool, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let mut conn = db::connection();
+        let login = verify_admin(&mut conn, &token_str)?;
 
-    // progress bar logic
-    let percentage = if !is_electric && item.capacity > 0 {
-        (item.stored as f64 / item.capacity as f64) * 100.0
-    } else {
-        0.0
-    };
-    let bar_color = if percentage < 20.0 {
-        "#ef4444"
-    } else {
-        "#059669"
-    };
+        let decided = pricing::decide_proposal(&mut conn, proposal_id, approve, &login)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        if !decided {
+            return Err(ServerFnError::new("Пропозицію вже розглянуто"));
+        }
+        on_fuels_changed(&mut conn);
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
 
-    let is_available = if is_electric {
-        item.stored > 0
-    } else {
-        item.stored >= 1
-    };
+#[server]
+async fn get_suppliers(token_str: String) -> Result<Vec<models::Supplier>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::Supplier;
+        use crate::schema::supplier;
+        use diesel::prelude::*;
 
-    let handle_buy = move |_| async move {
-        if let Some(user) = user_state() {
-            error_msg.set("Processing".to_string());
-            let token = user.session_token.clone().unwrap_or_default();
-            match buy_fuel(user.id, item.id, amount(), token).await {
-                Ok(new_balance) => {
-                    let mut updated_user = user.clone();
-                    updated_user.balance = new_balance;
-                    user_state.set(Some(updated_user));
-                    amount.set(1);
-                    error_msg.set("Успішно!".to_string());
-                }
-                Err(e) => error_msg.set(clean_error_msg(e.to_string())),
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        supplier::table
+            .order(supplier::name)
+            .select(Supplier::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn create_supplier(
+    name: String,
+    contact: Option<String>,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::supplier;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ServerFnError::new("Вкажіть назву постачальника"));
+        }
+
+        diesel::insert_into(supplier::table)
+            .values((
+                supplier::name.eq(name),
+                supplier::contact.eq(contact.filter(|c| !c.trim().is_empty())),
+                supplier::created_at.eq(chrono::Utc::now().timestamp()),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(format!("Не вдалося додати постачальника: {}", e)))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_purchase_orders(token_str: String) -> Result<Vec<models::PurchaseOrderView>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::{PurchaseOrder, PurchaseOrderView};
+        use crate::schema::{fuel, purchase_order, supplier};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let rows: Vec<(PurchaseOrder, String, String)> = purchase_order::table
+            .inner_join(supplier::table)
+            .inner_join(fuel::table)
+            .order(purchase_order::created_at.desc())
+            .select((PurchaseOrder::as_select(), supplier::name, fuel::name))
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(order, supplier_name, fuel_name)| PurchaseOrderView {
+                order,
+                supplier_name,
+                fuel_name,
+            })
+            .collect())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn create_purchase_order(
+    supplier_id: i32,
+    fuel_id: i32,
+    quantity: i32,
+    unit_cost: i64,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::purchase_order;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        if quantity <= 0 || unit_cost <= 0 {
+            return Err(ServerFnError::new("Кількість та ціна мають бути додатними"));
+        }
+
+        diesel::insert_into(purchase_order::table)
+            .values((
+                purchase_order::supplier_id.eq(supplier_id),
+                purchase_order::fuel_id.eq(fuel_id),
+                purchase_order::ordered_qty.eq(quantity),
+                purchase_order::unit_cost.eq(unit_cost),
+                purchase_order::created_at.eq(chrono::Utc::now().timestamp()),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(format!("Не вдалося створити замовлення: {}", e)))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Приймання поставки за замовленням: поповнює резервуари та оплачує з банку.
+// Допускається часткова поставка; повертає новий статус замовлення.
+#[server]
+async fn receive_delivery(
+    order_id: i32,
+    quantity: i32,
+    temperature: Option<f64>,
+    token_str: String,
+) -> Result<String, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::{Bank, NewDelivery, NewInventoryLot, NewStockMovement, PurchaseOrder, Tank};
+        use crate::schema::{bank, delivery, fuel, inventory_lot, purchase_order, stock_movement, tank};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let order: PurchaseOrder = purchase_order::table
+            .find(order_id)
+            .select(PurchaseOrder::as_select())
+            .first(&mut conn)
+            .map_err(|e| ServerFnError::new(format!("Замовлення не знайдено: {}", e)))?;
+
+        if order.status != "open" && order.status != "partial" {
+            return Err(ServerFnError::new("Замовлення вже закрито"));
+        }
+        let remaining_qty = order.ordered_qty - order.delivered_qty;
+        if quantity <= 0 || quantity > remaining_qty {
+            return Err(ServerFnError::new(format!(
+                "Кількість має бути від 1 до {} л",
+                remaining_qty
+            )));
+        }
+
+        let (f_type_opt, density, expansion_coeff): (Option<String>, Option<f64>, Option<f64>) = fuel::table
+            .find(order.fuel_id)
+            .select((fuel::fuel_type, fuel::density, fuel::expansion_coeff))
+            .first(&mut conn)
+            .map_err(|e| ServerFnError::new(format!("Паливо не знайдено: {}", e)))?;
+        let thermal = utils::ThermalProperties::for_fuel(
+            f_type_opt.as_deref().unwrap_or("petrol"),
+            density,
+            expansion_coeff,
+        );
+
+        let total_cost = utils::calculate_delivery_cost(order.unit_cost, quantity);
+
+        // Перевірка балансу банку
+        let bank_row = bank::table
+            .first::<Bank>(&mut conn)
+            .optional()
+            .map_err(|e| ServerFnError::new(format!("Помилка отримання балансу банку: {}", e)))?;
+
+        let Some(bank_row) = bank_row else {
+            return Err(refill_failed(&mut conn, &order, "Банк не ініціалізовано"));
+        };
+        if bank_row.total < total_cost {
+            return Err(refill_failed(&mut conn, &order, "Недостатньо коштів у банку"));
+        }
+
+        // Перевірка наявності місця в резервуарах
+        let tanks: Vec<Tank> = tank::table
+            .filter(tank::fuelid.eq(order.fuel_id))
+            .select(Tank::as_select())
+            .load::<Tank>(&mut conn)
+            .map_err(|e| ServerFnError::new(format!("Помилка завантаження резервуарів: {}", e)))?;
+
+        let total_space: i32 = tanks.iter().map(|t| t.capacity - t.stored).sum();
+        if total_space < quantity {
+            return Err(refill_failed(&mut conn, &order, "Недостатньо місця в резервуарах"));
+        }
+
+        // Виконуємо транзакцію
+        let status = conn.transaction::<String, diesel::result::Error, _>(|conn| {
+            let now = chrono::Utc::now().timestamp();
+
+            diesel::update(bank::table.find(bank_row.id))
+                .set(bank::total.eq(bank_row.total - total_cost))
+                .execute(conn)?;
+
+            let delivery_id: i32 = diesel::insert_into(delivery::table)
+                .values(&NewDelivery {
+                    order_id,
+                    fuel_id: order.fuel_id,
+                    quantity,
+                    unit_cost: order.unit_cost,
+                    total_cost,
+                    temperature,
+                    net_quantity: None,
+                    created_at: now,
+                })
+                .returning(delivery::id)
+                .get_result(conn)?;
+
+            let mut net_total: Option<f64> = None;
+            let mut filled = Vec::new();
+            let mut remaining = quantity;
+            for t in tanks {
+                if remaining <= 0 {
+                    break;
+                }
+                let space = t.capacity - t.stored;
+                if space > 0 {
+                    let add = std::cmp::min(remaining, space);
+                    let (net, tank_temp) = net_quantity(conn, t.id, add, thermal, temperature)?;
+                    diesel::update(tank::table.find(t.id))
+                        .set(tank::stored.eq(t.stored + add))
+                        .execute(conn)?;
+                    diesel::insert_into(stock_movement::table)
+                        .values(&NewStockMovement {
+                            tank_id: t.id,
+                            kind: "delivery",
+                            quantity: add,
+                            sale_id: None,
+                            created_at: now,
+                            net_quantity: net,
+                            temperature: tank_temp,
+                            delivery_id: Some(delivery_id),
+                        })
+                        .execute(conn)?;
+                    diesel::insert_into(inventory_lot::table)
+                        .values(&NewInventoryLot {
+                            tank_id: t.id,
+                            delivery_id: Some(delivery_id),
+                            unit_cost: order.unit_cost,
+                            quantity: add,
+                            remaining: add,
+                            created_at: now,
+                        })
+                        .execute(conn)?;
+                    if let Some(n) = net {
+                        net_total = Some(net_total.unwrap_or(0.0) + n);
+                    }
+                    filled.push(serde_json::json!({ "tank_id": t.id, "quantity": add }));
+                    remaining -= add;
+                }
+            }
+
+            diesel::update(delivery::table.find(delivery_id))
+                .set(delivery::net_quantity.eq(net_total))
+                .execute(conn)?;
+
+            let delivered = order.delivered_qty + quantity;
+            let status = utils::order_status_after_delivery(order.ordered_qty, delivered);
+            diesel::update(purchase_order::table.find(order_id))
+                .set((
+                    purchase_order::delivered_qty.eq(delivered),
+                    purchase_order::status.eq(status),
+                    purchase_order::closed_at.eq(if status == "delivered" { Some(now) } else { None }),
+                ))
+                .execute(conn)?;
+
+            webhooks::enqueue(
+                conn,
+                webhooks::TANK_REFILLED,
+                serde_json::json!({
+                    "delivery_id": delivery_id,
+                    "order_id": order_id,
+                    "fuel_id": order.fuel_id,
+                    "quantity": quantity,
+                    "net_quantity": net_total,
+                    "tanks": filled,
+                    "order_status": status,
+                    "created_at": now,
+                }),
+            )?;
+
+            Ok(status.to_string())
+        })
+        .map_err(|e| refill_failed(&mut conn, &order, &format!("Помилка транзакції: {}", e)))?;
+
+        on_fuels_changed(&mut conn);
+        Ok(status)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Закриття замовлення без очікування решти поставки
+#[server]
+async fn close_purchase_order(order_id: i32, token_str: String) -> Result<String, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::PurchaseOrder;
+        use crate::schema::purchase_order;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let order: PurchaseOrder = purchase_order::table
+            .find(order_id)
+            .select(PurchaseOrder::as_select())
+            .first(&mut conn)
+            .map_err(|e| ServerFnError::new(format!("Замовлення не знайдено: {}", e)))?;
+
+        if order.status != "open" && order.status != "partial" {
+            return Err(ServerFnError::new("Замовлення вже закрито"));
+        }
+
+        let status = utils::order_status_on_close(order.delivered_qty);
+        diesel::update(purchase_order::table.find(order_id))
+            .set((
+                purchase_order::status.eq(status),
+                purchase_order::closed_at.eq(Some(chrono::Utc::now().timestamp())),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(status.to_string())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_bank_info() -> Result<models::Bank, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::Bank;
+        use crate::schema::bank;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        let bank_row = bank::table
+            .first::<Bank>(&mut conn)
+            .optional()
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        match bank_row {
+            Some(b) => Ok(b),
+            None => Ok(Bank { id: 0, total: 0 }),
+        }
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Перевірка токену адміна, повертає логін
+#[cfg(feature = "server")]
+fn verify_admin(
+    conn: &mut diesel::SqliteConnection,
+    token_str: &str,
+) -> Result<String, ServerFnError> {
+    use crate::schema::admin;
+    use diesel::prelude::*;
+
+    admin::table
+        .filter(admin::session_token.eq(token_str))
+        .select(admin::login)
+        .first::<String>(conn)
+        .optional()
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .ok_or_else(|| ServerFnError::new("Unauthorized Admin"))
+}
+
+// Перевірка сесії клієнта
+#[cfg(feature = "server")]
+fn verify_customer(
+    conn: &mut diesel::SqliteConnection,
+    user_id: i32,
+    token_str: &str,
+) -> Result<(), ServerFnError> {
+    use crate::schema::customer;
+    use diesel::prelude::*;
+
+    let stored_token: Option<String> = customer::table
+        .find(user_id)
+        .select(customer::session_token)
+        .first(conn)
+        .map_err(|_| ServerFnError::new("User not found"))?;
+
+    match stored_token {
+        Some(t) if t == token_str => Ok(()),
+        _ => Err(ServerFnError::new("Неавторизований доступ (Invalid Token)")),
+    }
+}
+
+// Після зміни цін чи залишків: живі оновлення відкритих сторінок, перевірка підписок
+// і низьких залишків для персоналу
+#[cfg(feature = "server")]
+fn on_fuels_changed(conn: &mut diesel::SqliteConnection) {
+    if let Err(e) = live::publish(conn) {
+        println!("LOG: Failed to publish fuel updates: {}", e);
+    }
+    check_price_alerts(conn);
+    match staff::check_low_stock(conn) {
+        Ok(created) if created > 0 => dispatch_staff_events(),
+        Ok(_) => {}
+        Err(e) => println!("LOG: Failed to check low stock: {}", e),
+    }
+    dispatch_webhooks();
+}
+
+// Доставка вебхуків, що чекають у черзі (продажі, ціни, поставки, залишки)
+#[cfg(feature = "server")]
+fn dispatch_webhooks() {
+    tokio::spawn(async {
+        if let Err(e) = webhooks::deliver_due().await {
+            println!("LOG: Failed to deliver webhooks: {}", e);
+        }
+    });
+}
+
+// Перевіряє підписки клієнтів; листи надсилаються у фоні.
+// Помилки не переривають основну операцію.
+#[cfg(feature = "server")]
+fn check_price_alerts(conn: &mut diesel::SqliteConnection) {
+    match alerts::evaluate(conn) {
+        Ok(messages) if !messages.is_empty() => {
+            tokio::spawn(notify::deliver(messages));
+        }
+        Ok(_) => {}
+        Err(e) => println!("LOG: Failed to evaluate price alerts: {}", e),
+    }
+}
+
+// Розсилка нових подій персоналу в канали у фоні
+#[cfg(feature = "server")]
+fn dispatch_staff_events() {
+    tokio::spawn(async {
+        if let Err(e) = staff::dispatch_pending().await {
+            println!("LOG: Failed to dispatch staff events: {}", e);
+        }
+    });
+}
+
+// Невдале поповнення: подія для персоналу і помилка для відповіді
+#[cfg(feature = "server")]
+fn refill_failed(
+    conn: &mut diesel::SqliteConnection,
+    order: &models::PurchaseOrder,
+    reason: &str,
+) -> ServerFnError {
+    let message = format!("Поставку за замовленням №{} не прийнято: {}", order.id, reason);
+    match staff::raise(
+        conn,
+        staff::KIND_REFILL_FAILED,
+        "critical",
+        &format!("refill_failed:order:{}", order.id),
+        &message,
+        None,
+        Some(order.fuel_id),
+    ) {
+        Ok(true) => dispatch_staff_events(),
+        Ok(false) => {}
+        Err(e) => println!("LOG: Failed to record refill failure: {}", e),
+    }
+    ServerFnError::new(reason)
+}
+
+#[server]
+async fn get_tank_stock(token_str: String) -> Result<Vec<models::TankStock>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+        load_tank_stock(&mut conn)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Облікові залишки з останнім виміром по кожному резервуару (після перевірки доступу)
+#[cfg(feature = "server")]
+fn load_tank_stock(conn: &mut diesel::SqliteConnection) -> Result<Vec<models::TankStock>, ServerFnError> {
+    use crate::models::{TankReading, TankStock};
+    use crate::schema::{fuel, tank, tank_reading};
+    use diesel::prelude::*;
+
+    let tanks: Vec<(i32, String, i32, i32)> = tank::table
+        .inner_join(fuel::table)
+        .select((tank::id, fuel::name, tank::stored, tank::capacity))
+        .order(tank::id)
+        .load(conn)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    let mut result = Vec::new();
+    for (t_id, f_name, stored, capacity) in tanks {
+        let last: Option<TankReading> = tank_reading::table
+            .filter(tank_reading::tank_id.eq(t_id))
+            .order(tank_reading::recorded_at.desc())
+            .select(TankReading::as_select())
+            .first(conn)
+            .optional()
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        result.push(TankStock {
+            tank_id: t_id,
+            fuel_name: f_name,
+            capacity,
+            book: stored,
+            measured: last.as_ref().map(|r| r.volume),
+            measured_net: last.as_ref().and_then(|r| r.net_volume),
+            water_height: last.as_ref().map(|r| r.water_height),
+            temperature: last.as_ref().map(|r| r.temperature),
+            measured_at: last.as_ref().map(|r| r.recorded_at),
+        });
+    }
+    Ok(result)
+}
+
+#[server]
+async fn import_strapping_chart(
+    tank_id: i32,
+    csv: String,
+    token_str: String,
+) -> Result<usize, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::tank_strapping;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let chart = utils::parse_strapping_csv(&csv).map_err(ServerFnError::new)?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::delete(tank_strapping::table.filter(tank_strapping::tank_id.eq(tank_id)))
+                .execute(conn)?;
+            let rows: Vec<_> = chart
+                .iter()
+                .map(|(h, v)| {
+                    (
+                        tank_strapping::tank_id.eq(tank_id),
+                        tank_strapping::height_mm.eq(*h),
+                        tank_strapping::volume.eq(*v),
+                    )
+                })
+                .collect();
+            diesel::insert_into(tank_strapping::table)
+                .values(&rows)
+                .execute(conn)
+        })
+        .map_err(|e| ServerFnError::new(format!("Помилка імпорту: {}", e)))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Ручний замір щупом: висота продукту та води в мм, повертає об'єм продукту в літрах
+#[server]
+async fn record_dip(
+    tank_id: i32,
+    height_mm: f64,
+    water_mm: f64,
+    temperature: f64,
+    token_str: String,
+) -> Result<f64, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::NewTankReading;
+        use crate::schema::{fuel, tank, tank_reading, tank_strapping};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        if water_mm < 0.0 || water_mm > height_mm {
+            return Err(ServerFnError::new("Рівень води має бути між 0 та рівнем продукту"));
+        }
+
+        let chart: Vec<(i32, f64)> = tank_strapping::table
+            .filter(tank_strapping::tank_id.eq(tank_id))
+            .order(tank_strapping::height_mm)
+            .select((tank_strapping::height_mm, tank_strapping::volume))
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        if chart.is_empty() {
+            return Err(ServerFnError::new("Для резервуару немає градуювальної таблиці"));
+        }
+
+        let total = utils::interpolate_volume(&chart, height_mm)
+            .ok_or_else(|| ServerFnError::new("Висота поза межами градуювальної таблиці"))?;
+        let water = utils::interpolate_volume(&chart, water_mm).unwrap_or(0.0);
+        let volume = total - water;
+
+        let (f_type, density, expansion_coeff): (Option<String>, Option<f64>, Option<f64>) = tank::table
+            .inner_join(fuel::table)
+            .filter(tank::id.eq(tank_id))
+            .select((fuel::fuel_type, fuel::density, fuel::expansion_coeff))
+            .first(&mut conn)
+            .map_err(|e| ServerFnError::new(format!("Резервуар не знайдено: {}", e)))?;
+        let net_volume = utils::ThermalProperties::for_fuel(
+            f_type.as_deref().unwrap_or("petrol"),
+            density,
+            expansion_coeff,
+        )
+        .map(|props| utils::volume_at_15c(volume, temperature, &props));
+
+        diesel::insert_into(tank_reading::table)
+            .values(&NewTankReading {
+                tank_id,
+                recorded_at: chrono::Utc::now().timestamp(),
+                height: height_mm,
+                volume,
+                water_height: water_mm,
+                temperature,
+                source: "dip",
+                net_volume,
+            })
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        Ok(volume)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_reconciliation(
+    days: u32,
+    token_str: String,
+) -> Result<Vec<models::TankReconciliation>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let report = reconciliation::build_report(&mut conn, days.clamp(1, 90) as u64)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        reconciliation::raise_alerts(&mut conn, &report)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(report)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn set_variance_threshold(
+    tank_id: i32,
+    litres: i32,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::tank;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        if litres < 0 {
+            return Err(ServerFnError::new("Поріг не може бути від'ємним"));
+        }
+        diesel::update(tank::table.find(tank_id))
+            .set(tank::variance_threshold.eq(litres))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Події для персоналу: спершу непідтверджені, далі останні за часом
+#[server]
+async fn get_staff_events(token_str: String) -> Result<Vec<models::StaffEvent>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::StaffEvent;
+        use crate::schema::staff_event;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        staff_event::table
+            .order((staff_event::acknowledged_at.is_not_null(), staff_event::last_seen_at.desc()))
+            .limit(200)
+            .select(StaffEvent::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn acknowledge_staff_event(event_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let mut conn = db::connection();
+        let login = verify_admin(&mut conn, &token_str)?;
+
+        let updated = staff::acknowledge(&mut conn, event_id, &login)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        if updated == 0 {
+            return Err(ServerFnError::new("Подію не знайдено або вже підтверджено"));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_staff_channels(token_str: String) -> Result<Vec<models::StaffChannel>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::StaffChannel;
+        use crate::schema::staff_channel;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        staff_channel::table
+            .order(staff_channel::id)
+            .select(StaffChannel::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn add_staff_channel(
+    kind: String,
+    target: String,
+    min_severity: String,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::staff_channel;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let target = target.trim();
+        match kind.as_str() {
+            "email" if !utils::is_valid_email(target) => {
+                return Err(ServerFnError::new("Некоректна адреса пошти"));
+            }
+            "webhook" if !(target.starts_with("http://") || target.starts_with("https://")) => {
+                return Err(ServerFnError::new("URL вебхука має починатися з http:// або https://"));
+            }
+            "email" | "webhook" => {}
+            _ => return Err(ServerFnError::new("Невідомий тип каналу")),
+        }
+        if min_severity != "warning" && min_severity != "critical" {
+            return Err(ServerFnError::new("Невідомий рівень подій"));
+        }
+
+        diesel::insert_into(staff_channel::table)
+            .values((
+                staff_channel::kind.eq(&kind),
+                staff_channel::target.eq(target),
+                staff_channel::min_severity.eq(&min_severity),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn set_staff_channel_enabled(channel_id: i32, enabled: bool, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::staff_channel;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        diesel::update(staff_channel::table.find(channel_id))
+            .set(staff_channel::enabled.eq(enabled))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn delete_staff_channel(channel_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::staff_channel;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        diesel::delete(staff_channel::table.find(channel_id))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Пороги низького залишку по резервуарах (крім зарядки)
+#[server]
+async fn get_tank_thresholds(token_str: String) -> Result<Vec<models::TankThreshold>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::TankThreshold;
+        use crate::schema::{fuel, tank};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let rows: Vec<(i32, String, i32, i32, Option<i32>)> = tank::table
+            .inner_join(fuel::table)
+            .filter(fuel::fuel_type.ne("electricity").or(fuel::fuel_type.is_null()))
+            .order(tank::id)
+            .select((tank::id, fuel::name, tank::stored, tank::capacity, tank::low_stock_threshold))
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(tank_id, fuel_name, stored, capacity, threshold)| TankThreshold {
+                tank_id,
+                fuel_name,
+                stored,
+                capacity,
+                threshold,
+                effective: utils::low_stock_threshold(capacity, threshold),
+            })
+            .collect())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// None — повернути типовий поріг (20% місткості)
+#[server]
+async fn set_low_stock_threshold(
+    tank_id: i32,
+    litres: Option<i32>,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::tank;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let capacity: i32 = tank::table
+            .find(tank_id)
+            .select(tank::capacity)
+            .first(&mut conn)
+            .map_err(|e| ServerFnError::new(format!("Резервуар не знайдено: {}", e)))?;
+        if litres.is_some_and(|l| l < 0 || l > capacity) {
+            return Err(ServerFnError::new(format!("Поріг має бути від 0 до {} л", capacity)));
+        }
+
+        diesel::update(tank::table.find(tank_id))
+            .set(tank::low_stock_threshold.eq(litres))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        // Новий поріг одразу відкриває або закриває подію
+        if staff::check_low_stock(&mut conn).map_err(|e| ServerFnError::new(e.to_string()))? > 0 {
+            dispatch_staff_events();
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_webhook_subscriptions(token_str: String) -> Result<Vec<models::WebhookSubscription>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::WebhookSubscription;
+        use crate::schema::webhook_subscription;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        webhook_subscription::table
+            .order(webhook_subscription::id)
+            .select(WebhookSubscription::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Нова підписка; секрет підпису генерується на сервері
+#[server]
+async fn create_webhook_subscription(
+    url: String,
+    events: Vec<String>,
+    token_str: String,
+) -> Result<models::WebhookSubscription, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::WebhookSubscription;
+        use crate::schema::webhook_subscription;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let url = url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(ServerFnError::new("URL має починатися з http:// або https://"));
+        }
+        if events.is_empty() {
+            return Err(ServerFnError::new("Оберіть хоча б одну подію"));
+        }
+        if let Some(unknown) = events.iter().find(|e| !webhooks::EVENTS.contains(&e.as_str())) {
+            return Err(ServerFnError::new(format!("Невідома подія: {}", unknown)));
+        }
+        let events = if events.len() == webhooks::EVENTS.len() {
+            "*".to_string()
+        } else {
+            events.join(",")
+        };
+
+        diesel::insert_into(webhook_subscription::table)
+            .values((
+                webhook_subscription::url.eq(url),
+                webhook_subscription::secret.eq(webhooks::generate_secret()),
+                webhook_subscription::events.eq(events),
+                webhook_subscription::created_at.eq(chrono::Utc::now().timestamp()),
+            ))
+            .returning(WebhookSubscription::as_returning())
+            .get_result(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn set_webhook_enabled(subscription_id: i32, enabled: bool, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::webhook_subscription;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        diesel::update(webhook_subscription::table.find(subscription_id))
+            .set(webhook_subscription::enabled.eq(enabled))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Видалення підписки разом із її журналом доставок
+#[server]
+async fn delete_webhook_subscription(subscription_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::{webhook_delivery, webhook_subscription};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::delete(webhook_delivery::table.filter(webhook_delivery::subscription_id.eq(subscription_id)))
+                .execute(conn)?;
+            diesel::delete(webhook_subscription::table.find(subscription_id)).execute(conn)?;
+            Ok(())
+        })
+        .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Журнал доставок, останні спершу
+#[server]
+async fn get_webhook_deliveries(token_str: String) -> Result<Vec<models::WebhookDelivery>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::WebhookDelivery;
+        use crate::schema::webhook_delivery;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        webhook_delivery::table
+            .order(webhook_delivery::id.desc())
+            .limit(100)
+            .select(WebhookDelivery::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Повторна доставка після остаточної помилки: лічильник спроб починається спочатку
+#[server]
+async fn retry_webhook_delivery(delivery_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::webhook_delivery;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let updated = diesel::update(
+            webhook_delivery::table
+                .find(delivery_id)
+                .filter(webhook_delivery::status.eq("failed")),
+        )
+        .set((
+            webhook_delivery::status.eq("pending"),
+            webhook_delivery::attempts.eq(0),
+            webhook_delivery::next_attempt_at.eq(chrono::Utc::now().timestamp()),
+        ))
+        .execute(&mut conn)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        if updated == 0 {
+            return Err(ServerFnError::new("Повторити можна лише невдалу доставку"));
+        }
+
+        dispatch_webhooks();
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Типи пального (для обмежень водіїв і авто клієнтів)
+#[cfg(feature = "server")]
+const FUEL_TYPES: [&str; 4] = ["petrol", "diesel", "gas", "electricity"];
+
+// Перелік дозволених типів для запису в БД: None — усі типи
+#[cfg(feature = "server")]
+fn allowed_fuel_types(types: &[String]) -> Result<Option<String>, ServerFnError> {
+    if let Some(unknown) = types.iter().find(|t| !FUEL_TYPES.contains(&t.as_str())) {
+        return Err(ServerFnError::new(format!("Невідомий тип пального: {}", unknown)));
+    }
+    if types.is_empty() {
+        return Err(ServerFnError::new("Дозвольте водію хоча б один тип пального"));
+    }
+    Ok((types.len() < FUEL_TYPES.len()).then(|| types.join(",")))
+}
+
+#[cfg(feature = "server")]
+fn validate_limits(daily_limit: Option<i64>, monthly_limit: Option<i64>) -> Result<(), ServerFnError> {
+    if daily_limit.is_some_and(|l| l <= 0) || monthly_limit.is_some_and(|l| l <= 0) {
+        return Err(ServerFnError::new("Ліміт має бути додатним"));
+    }
+    Ok(())
+}
+
+#[server]
+async fn get_fleet_companies(token_str: String) -> Result<Vec<models::FleetCompany>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::FleetCompany;
+        use crate::schema::fleet_company;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        fleet_company::table
+            .order(fleet_company::name)
+            .select(FleetCompany::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn create_fleet_company(name: String, token_str: String) -> Result<models::FleetCompany, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::FleetCompany;
+        use crate::schema::fleet_company;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ServerFnError::new("Вкажіть назву компанії"));
+        }
+        diesel::insert_into(fleet_company::table)
+            .values((
+                fleet_company::name.eq(name),
+                fleet_company::created_at.eq(chrono::Utc::now().timestamp()),
+            ))
+            .returning(FleetCompany::as_returning())
+            .get_result(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                    ServerFnError::new(format!("Компанія {} вже існує", name))
+                }
+                e => ServerFnError::new(e.to_string()),
+            })
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Поповнення рахунку компанії (копійки); повертає новий баланс
+#[server]
+async fn top_up_fleet_company(
+    company_id: i32,
+    amount: i64,
+    note: String,
+    token_str: String,
+) -> Result<i64, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let mut conn = db::connection();
+        let login = verify_admin(&mut conn, &token_str)?;
+
+        if amount <= 0 {
+            return Err(ServerFnError::new("Сума поповнення має бути додатною"));
+        }
+        let note = note.trim();
+        let note = if note.is_empty() { format!("Поповнення ({})", login) } else { note.to_string() };
+        fleet::top_up(&mut conn, company_id, amount, Some(&note), chrono::Utc::now().timestamp())
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => ServerFnError::new("Компанію не знайдено"),
+                e => ServerFnError::new(e.to_string()),
+            })
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Водії компанії з витратами за сьогодні та поточний місяць
+#[server]
+async fn get_fleet_drivers(company_id: i32, token_str: String) -> Result<Vec<models::FleetDriverView>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::{FleetDriver, FleetDriverView};
+        use crate::schema::{customer, fleet_driver};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let rows: Vec<(FleetDriver, String)> = fleet_driver::table
+            .inner_join(customer::table)
+            .filter(fleet_driver::company_id.eq(company_id))
+            .order(customer::login)
+            .select((FleetDriver::as_select(), customer::login))
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        let (day_from, month_from) = fleet::period_starts(chrono::Local::now().date_naive());
+        rows.into_iter()
+            .map(|(driver, login)| {
+                let spent_today = fleet::spent_since(&mut conn, driver.id, day_from)?;
+                let spent_month = fleet::spent_since(&mut conn, driver.id, month_from)?;
+                Ok(FleetDriverView { driver, login, spent_today, spent_month })
+            })
+            .collect::<diesel::QueryResult<Vec<_>>>()
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Прив'язка клієнта до компанії як водія; ліміти в копійках
+#[server]
+async fn add_fleet_driver(
+    company_id: i32,
+    customer_login: String,
+    card_number: String,
+    daily_limit: Option<i64>,
+    monthly_limit: Option<i64>,
+    fuel_types: Vec<String>,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::{customer, fleet_driver};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        validate_limits(daily_limit, monthly_limit)?;
+        let allowed = allowed_fuel_types(&fuel_types)?;
+        let customer_login = customer_login.trim();
+        let customer_id: i32 = customer::table
+            .filter(customer::login.eq(customer_login))
+            .select(customer::id)
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| ServerFnError::new(e.to_string()))?
+            .ok_or_else(|| ServerFnError::new(format!("Клієнта {} не знайдено", customer_login)))?;
+        let card_number = Some(card_number.trim()).filter(|c| !c.is_empty());
+
+        diesel::insert_into(fleet_driver::table)
+            .values((
+                fleet_driver::company_id.eq(company_id),
+                fleet_driver::customer_id.eq(customer_id),
+                fleet_driver::card_number.eq(card_number),
+                fleet_driver::daily_limit.eq(daily_limit),
+                fleet_driver::monthly_limit.eq(monthly_limit),
+                fleet_driver::allowed_fuel_types.eq(allowed),
+                fleet_driver::created_at.eq(chrono::Utc::now().timestamp()),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                    ServerFnError::new("Клієнт уже є водієм автопарку або картка зайнята")
+                }
+                e => ServerFnError::new(e.to_string()),
+            })?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Ліміти, дозволене пальне та блокування картки водія
+#[server]
+async fn update_fleet_driver(
+    driver_id: i32,
+    daily_limit: Option<i64>,
+    monthly_limit: Option<i64>,
+    fuel_types: Vec<String>,
+    active: bool,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::fleet_driver;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        validate_limits(daily_limit, monthly_limit)?;
+        let allowed = allowed_fuel_types(&fuel_types)?;
+        let updated = diesel::update(fleet_driver::table.find(driver_id))
+            .set((
+                fleet_driver::daily_limit.eq(daily_limit),
+                fleet_driver::monthly_limit.eq(monthly_limit),
+                fleet_driver::allowed_fuel_types.eq(allowed),
+                fleet_driver::active.eq(active),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        if updated == 0 {
+            return Err(ServerFnError::new("Водія не знайдено"));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Видалення водія без операцій; водія з історією лише блокують, щоб виписки не змінювались
+#[server]
+async fn remove_fleet_driver(driver_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::{fleet_driver, fleet_ledger};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let operations: i64 = fleet_ledger::table
+            .filter(fleet_ledger::driver_id.eq(driver_id))
+            .count()
+            .get_result(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        if operations > 0 {
+            return Err(ServerFnError::new("Водій має операції — заблокуйте картку замість видалення"));
+        }
+        diesel::delete(fleet_driver::table.find(driver_id))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Зведена місячна виписка компанії; month — "РРРР-ММ"
+#[server]
+async fn get_fleet_statement(
+    company_id: i32,
+    month: String,
+    token_str: String,
+) -> Result<models::FleetStatement, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::FleetCompany;
+        use crate::schema::fleet_company;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let (first, next) = utils::month_range(&month)
+            .ok_or_else(|| ServerFnError::new("Місяць має бути у форматі РРРР-ММ"))?;
+        let company: FleetCompany = fleet_company::table
+            .find(company_id)
+            .select(FleetCompany::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| ServerFnError::new(e.to_string()))?
+            .ok_or_else(|| ServerFnError::new("Компанію не знайдено"))?;
+
+        fleet::statement(
+            &mut conn,
+            company,
+            month.trim(),
+            reconciliation::day_start(first),
+            reconciliation::day_start(next),
+        )
+        .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Корпоративний рахунок клієнта-водія (None — звичайний клієнт)
+#[server]
+async fn get_driver_account(user_id: i32, token_str: String) -> Result<Option<models::DriverAccount>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::DriverAccount;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        let Some((driver, company)) =
+            fleet::driver_for(&mut conn, user_id).map_err(|e| ServerFnError::new(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+        let (day_from, month_from) = fleet::period_starts(chrono::Local::now().date_naive());
+        let spent = |conn: &mut diesel::SqliteConnection, from| {
+            fleet::spent_since(conn, driver.id, from).map_err(|e| ServerFnError::new(e.to_string()))
+        };
+        Ok(Some(DriverAccount {
+            company_name: company.name,
+            company_balance: company.balance,
+            spent_today: spent(&mut conn, day_from)?,
+            spent_month: spent(&mut conn, month_from)?,
+            driver,
+        }))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Авто клієнта з підсумками заправок
+#[server]
+async fn get_vehicles(user_id: i32, token_str: String) -> Result<Vec<models::VehicleView>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::{Vehicle, VehicleView};
+        use crate::schema::{sale, vehicle};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        let vehicles: Vec<Vehicle> = vehicle::table
+            .filter(vehicle::customer_id.eq(user_id))
+            .order(vehicle::created_at)
+            .select(Vehicle::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        let ids: Vec<i32> = vehicles.iter().map(|v| v.id).collect();
+        let fills: Vec<(Option<i32>, i32, i64, i64)> = sale::table
+            .filter(sale::vehicle_id.eq_any(&ids))
+            .select((sale::vehicle_id, sale::amount, sale::total - sale::discount, sale::created_at))
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        Ok(vehicles
+            .into_iter()
+            .map(|vehicle| {
+                let own: Vec<_> = fills.iter().filter(|f| f.0 == Some(vehicle.id)).collect();
+                VehicleView {
+                    fills: own.len() as i64,
+                    total_volume: own.iter().map(|f| f.1 as i64).sum(),
+                    total_cost: own.iter().map(|f| f.2).sum(),
+                    last_fill_at: own.iter().map(|f| f.3).max(),
+                    vehicle,
+                }
+            })
+            .collect())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Нове авто: номер, тип пального, об'єм бака (л) або батареї (кВт·год)
+#[server]
+async fn add_vehicle(
+    user_id: i32,
+    plate: String,
+    fuel_type: String,
+    tank_capacity: i32,
+    token_str: String,
+) -> Result<models::Vehicle, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::Vehicle;
+        use crate::schema::vehicle;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        let plate = utils::normalize_plate(&plate)
+            .ok_or_else(|| ServerFnError::new("Невірний номер авто"))?;
+        if !FUEL_TYPES.contains(&fuel_type.as_str()) {
+            return Err(ServerFnError::new(format!("Невідомий тип пального: {}", fuel_type)));
+        }
+        if !(1..=1000).contains(&tank_capacity) {
+            return Err(ServerFnError::new("Об'єм бака має бути від 1 до 1000"));
+        }
+
+        diesel::insert_into(vehicle::table)
+            .values((
+                vehicle::customer_id.eq(user_id),
+                vehicle::plate.eq(&plate),
+                vehicle::fuel_type.eq(&fuel_type),
+                vehicle::tank_capacity.eq(tank_capacity),
+                vehicle::created_at.eq(chrono::Utc::now().timestamp()),
+            ))
+            .returning(Vehicle::as_returning())
+            .get_result(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                    ServerFnError::new(format!("Авто {} вже додано", plate))
+                }
+                e => ServerFnError::new(e.to_string()),
+            })
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn delete_vehicle(user_id: i32, vehicle_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::{sale, vehicle};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let owned = vehicle::table
+                .filter(vehicle::id.eq(vehicle_id))
+                .filter(vehicle::customer_id.eq(user_id));
+            // Продажі залишаються в історії клієнта без посилання на авто
+            diesel::update(sale::table.filter(sale::vehicle_id.eq(vehicle_id)))
+                .filter(sale::customer_id.eq(user_id))
+                .set(sale::vehicle_id.eq(None::<i32>))
+                .execute(conn)?;
+            diesel::delete(owned).execute(conn)
+        })
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Заправки авто (нові першими) і споживання по місяцях
+#[server]
+async fn get_vehicle_history(
+    user_id: i32,
+    vehicle_id: i32,
+    token_str: String,
+) -> Result<models::VehicleHistory, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::{Vehicle, VehicleFill, VehicleHistory};
+        use crate::schema::{fuel, sale, vehicle};
+        use chrono::TimeZone;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        let vehicle: Vehicle = vehicle::table
+            .find(vehicle_id)
+            .filter(vehicle::customer_id.eq(user_id))
+            .select(Vehicle::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| ServerFnError::new(e.to_string()))?
+            .ok_or_else(|| ServerFnError::new("Авто не знайдено"))?;
+
+        let fills: Vec<VehicleFill> = sale::table
+            .inner_join(fuel::table)
+            .filter(sale::vehicle_id.eq(vehicle_id))
+            .order((sale::created_at.desc(), sale::id.desc()))
+            .select((sale::id, sale::created_at, fuel::name, sale::amount, sale::total - sale::discount))
+            .load::<(i32, i64, String, i32, i64)>(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?
+            .into_iter()
+            .map(|(sale_id, created_at, fuel_name, amount, total)| VehicleFill {
+                sale_id,
+                created_at,
+                fuel_name,
+                amount,
+                total,
+            })
+            .collect();
+
+        let mut by_month: Vec<(String, i64, i64)> = Vec::new();
+        for fill in &fills {
+            let month = chrono::Local
+                .timestamp_opt(fill.created_at, 0)
+                .single()
+                .map(|dt| dt.format("%Y-%m").to_string())
+                .unwrap_or_default();
+            match by_month.iter_mut().find(|(m, _, _)| *m == month) {
+                Some((_, volume, cost)) => {
+                    *volume += fill.amount as i64;
+                    *cost += fill.total;
+                }
+                None => by_month.push((month, fill.amount as i64, fill.total)),
+            }
+        }
+
+        Ok(VehicleHistory { vehicle, fills, by_month })
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Бонусний рахунок клієнта: рівень, бали, згоряння, історія
+#[server]
+async fn get_loyalty_account(user_id: i32, token_str: String) -> Result<models::LoyaltyAccount, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+        loyalty::account(&mut conn, user_id, chrono::Utc::now().timestamp())
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_loyalty_tiers(token_str: String) -> Result<Vec<models::LoyaltyTier>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::LoyaltyTier;
+        use crate::schema::loyalty_tier;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+        loyalty_tier::table
+            .order(loyalty_tier::min_spent)
+            .select(LoyaltyTier::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Новий (tier_id = None) або змінений рівень. Рівень від 0 грн завжди лишається базовим.
+#[server]
+async fn save_loyalty_tier(
+    tier_id: Option<i32>,
+    name: String,
+    min_spent: i64,
+    points_per_litre: i32,
+    points_per_100_uah: i32,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::loyalty_tier;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ServerFnError::new("Вкажіть назву рівня"));
+        }
+        if min_spent < 0 || points_per_litre < 0 || points_per_100_uah < 0 {
+            return Err(ServerFnError::new("Значення не можуть бути від'ємними"));
+        }
+        let values = (
+            loyalty_tier::name.eq(name),
+            loyalty_tier::min_spent.eq(min_spent),
+            loyalty_tier::points_per_litre.eq(points_per_litre),
+            loyalty_tier::points_per_100_uah.eq(points_per_100_uah),
+        );
+        let result = match tier_id {
+            Some(id) => {
+                let current: i64 = loyalty_tier::table
+                    .find(id)
+                    .select(loyalty_tier::min_spent)
+                    .first(&mut conn)
+                    .map_err(|_| ServerFnError::new("Рівень не знайдено"))?;
+                if current == 0 && min_spent != 0 {
+                    return Err(ServerFnError::new("Базовий рівень має починатися від 0 грн"));
+                }
+                diesel::update(loyalty_tier::table.find(id)).set(values).execute(&mut conn)
+            }
+            None => diesel::insert_into(loyalty_tier::table).values(values).execute(&mut conn),
+        };
+        result.map_err(|e| match e {
+            diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                ServerFnError::new("Рівень з такою назвою або порогом вже існує")
+            }
+            e => ServerFnError::new(e.to_string()),
+        })?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn delete_loyalty_tier(tier_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::loyalty_tier;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let deleted = diesel::delete(
+            loyalty_tier::table
+                .find(tier_id)
+                .filter(loyalty_tier::min_spent.gt(0)),
+        )
+        .execute(&mut conn)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        if deleted == 0 {
+            return Err(ServerFnError::new("Базовий рівень видалити не можна"));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Сума кошика з найвигіднішою акцією (і купоном, якщо вказано) — до оплати
+#[server]
+async fn preview_checkout(
+    user_id: i32,
+    items: Vec<(i32, i32)>,
+    coupon: Option<String>,
+    token_str: String,
+) -> Result<models::CheckoutQuote, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::CheckoutQuote;
+        use crate::schema::fuel;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        let mut lines = Vec::new();
+        for (fuel_id, amount) in items {
+            let price: i64 = fuel::table
+                .find(fuel_id)
+                .select(fuel::price)
+                .first(&mut conn)
+                .map_err(|_| ServerFnError::new("Паливо не знайдено"))?;
+            lines.push((fuel_id, amount, price * amount as i64));
+        }
+        let subtotal: i64 = lines.iter().map(|l| l.2).sum();
+        let applied = promotions::best_for(&mut conn, user_id, &lines, coupon.as_deref(), chrono::Local::now())
+            .map_err(ServerFnError::new)?;
+        let discount = applied.as_ref().map_or(0, |a| a.total());
+        Ok(CheckoutQuote {
+            subtotal,
+            discount,
+            promotion_name: applied.map(|a| a.promotion.name),
+            total: subtotal - discount,
+        })
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Усі акції з кількістю використань і сумою наданих знижок
+#[server]
+async fn get_promotions(token_str: String) -> Result<Vec<models::PromotionView>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::{Promotion, PromotionView};
+        use crate::schema::{fuel, promotion, promotion_use};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let rows: Vec<(Promotion, Option<String>)> = promotion::table
+            .left_join(fuel::table)
+            .order((promotion::active.desc(), promotion::created_at.desc()))
+            .select((Promotion::as_select(), fuel::name.nullable()))
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        let used: Vec<(i32, i64)> = promotion_use::table
+            .select((promotion_use::promotion_id, promotion_use::discount))
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(promotion, fuel_name)| {
+                let own: Vec<i64> = used.iter().filter(|u| u.0 == promotion.id).map(|u| u.1).collect();
+                PromotionView {
+                    uses: own.len() as i64,
+                    total_discount: own.iter().sum(),
+                    promotion,
+                    fuel_name,
+                }
+            })
+            .collect())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn create_promotion(form: models::PromotionForm, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::promotion;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let name = form.name.trim();
+        if name.is_empty() {
+            return Err(ServerFnError::new("Вкажіть назву акції"));
+        }
+        let max_value = match form.kind.as_str() {
+            promotions::KIND_PERCENT => 100,
+            promotions::KIND_PER_LITRE => i32::MAX,
+            _ => return Err(ServerFnError::new("Невідомий тип знижки")),
+        };
+        if form.value <= 0 || form.value > max_value {
+            return Err(ServerFnError::new("Некоректний розмір знижки"));
+        }
+        if form.min_quantity.is_some_and(|q| q <= 0) || form.per_customer_limit.is_some_and(|l| l <= 0) {
+            return Err(ServerFnError::new("Мінімальний обсяг і ліміт мають бути більші за 0"));
+        }
+        let code = form
+            .code
+            .as_deref()
+            .map(promotions::normalize_code)
+            .filter(|c| !c.is_empty());
+        if code.as_deref().is_some_and(|c| !c.chars().all(|ch| ch.is_alphanumeric() || ch == '-')) {
+            return Err(ServerFnError::new("Купон може містити лише літери, цифри та дефіс"));
+        }
+
+        let (happy_start, happy_end) = match &form.happy_hour {
+            Some((start, end)) => {
+                let (Some(start), Some(end)) = (utils::parse_time_of_day(start), utils::parse_time_of_day(end)) else {
+                    return Err(ServerFnError::new("Час має бути у форматі ГГ:ХХ"));
+                };
+                if start == end {
+                    return Err(ServerFnError::new("Некоректне вікно happy hour"));
+                }
+                (Some(start), Some(end))
+            }
+            None => (None, None),
+        };
+        let parse_day = |value: &Option<String>| -> Result<Option<chrono::NaiveDate>, ServerFnError> {
+            match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+                Some(v) => chrono::NaiveDate::parse_from_str(v, "%Y-%m-%d")
+                    .map(Some)
+                    .map_err(|_| ServerFnError::new("Дата має бути у форматі РРРР-ММ-ДД")),
+                None => Ok(None),
+            }
+        };
+        let valid_from = parse_day(&form.valid_from)?.map(reconciliation::day_start);
+        // Останній день діє повністю
+        let valid_until = parse_day(&form.valid_until)?.map(|d| reconciliation::day_start(d + chrono::Days::new(1)));
+        if let (Some(from), Some(until)) = (valid_from, valid_until) {
+            if from >= until {
+                return Err(ServerFnError::new("Дата завершення раніше за дату початку"));
+            }
+        }
+
+        diesel::insert_into(promotion::table)
+            .values((
+                promotion::name.eq(name),
+                promotion::code.eq(code),
+                promotion::kind.eq(&form.kind),
+                promotion::value.eq(form.value),
+                promotion::fuel_id.eq(form.fuel_id),
+                promotion::min_quantity.eq(form.min_quantity),
+                promotion::happy_hour_start.eq(happy_start),
+                promotion::happy_hour_end.eq(happy_end),
+                promotion::valid_from.eq(valid_from),
+                promotion::valid_until.eq(valid_until),
+                promotion::per_customer_limit.eq(form.per_customer_limit),
+                promotion::created_at.eq(chrono::Utc::now().timestamp()),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) => {
+                    ServerFnError::new("Такий купон вже існує")
+                }
+                e => ServerFnError::new(e.to_string()),
+            })?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn set_promotion_active(promotion_id: i32, active: bool, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::promotion;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+        diesel::update(promotion::table.find(promotion_id))
+            .set(promotion::active.eq(active))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Невикористану акцію видаляємо, використану лише вимикаємо — щоб лишилася історія знижок
+#[server]
+async fn delete_promotion(promotion_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::{promotion, promotion_use};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let used: i64 = promotion_use::table
+            .filter(promotion_use::promotion_id.eq(promotion_id))
+            .count()
+            .get_result(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        let result = if used > 0 {
+            diesel::update(promotion::table.find(promotion_id))
+                .set(promotion::active.eq(false))
+                .execute(&mut conn)
+        } else {
+            diesel::delete(promotion::table.find(promotion_id)).execute(&mut conn)
+        };
+        result.map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// API-ключі з логіном рахунку автопарку, останні спершу
+#[server]
+async fn get_api_keys(token_str: String) -> Result<Vec<models::ApiKeyView>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::{ApiKey, ApiKeyView};
+        use crate::schema::{api_key, customer};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let rows: Vec<(ApiKey, Option<String>)> = api_key::table
+            .left_join(customer::table)
+            .order(api_key::id.desc())
+            .select((ApiKey::as_select(), customer::login.nullable()))
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(rows
+            .into_iter()
+            .map(|(key, customer_login)| ApiKeyView { key, customer_login })
+            .collect())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Новий ключ; повертає повний ключ, який більше ніде не зберігається
+#[server]
+async fn create_api_key(
+    name: String,
+    scopes: Vec<String>,
+    customer_login: String,
+    rate_limit: i32,
+    token_str: String,
+) -> Result<String, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::customer;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        let login = verify_admin(&mut conn, &token_str)?;
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ServerFnError::new("Вкажіть назву ключа"));
+        }
+        if scopes.is_empty() {
+            return Err(ServerFnError::new("Оберіть хоча б один дозвіл"));
+        }
+        if let Some(unknown) = scopes.iter().find(|s| !api_keys::SCOPES.contains(&s.as_str())) {
+            return Err(ServerFnError::new(format!("Невідомий дозвіл: {}", unknown)));
+        }
+        if !(1..=10_000).contains(&rate_limit) {
+            return Err(ServerFnError::new("Ліміт має бути від 1 до 10000 запитів на хвилину"));
+        }
+
+        let customer_login = customer_login.trim();
+        let customer_id = if customer_login.is_empty() {
+            None
+        } else {
+            let id: i32 = customer::table
+                .filter(customer::login.eq(customer_login))
+                .select(customer::id)
+                .first(&mut conn)
+                .optional()
+                .map_err(|e| ServerFnError::new(e.to_string()))?
+                .ok_or_else(|| ServerFnError::new(format!("Клієнта {} не знайдено", customer_login)))?;
+            Some(id)
+        };
+        if customer_id.is_none() && scopes.iter().any(|s| s == api_keys::SCOPE_PURCHASES_CREATE) {
+            return Err(ServerFnError::new("Для покупок вкажіть рахунок автопарку"));
+        }
+
+        let (_, key) = api_keys::issue(&mut conn, name, &scopes, customer_id, rate_limit, &login)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        println!("LOG: API key '{}' issued by {}", name, login);
+        Ok(key)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Ротація: новий секрет, старий діє ще добу
+#[server]
+async fn rotate_api_key(key_id: i32, token_str: String) -> Result<String, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        api_keys::rotate(&mut conn, key_id, chrono::Utc::now().timestamp())
+            .map_err(|e| ServerFnError::new(e.to_string()))?
+            .ok_or_else(|| ServerFnError::new("Активний ключ не знайдено"))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn revoke_api_key(key_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let revoked = api_keys::revoke(&mut conn, key_id, chrono::Utc::now().timestamp())
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        if revoked == 0 {
+            return Err(ServerFnError::new("Активний ключ не знайдено"));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_job_statuses(token_str: String) -> Result<Vec<models::JobStatus>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        scheduler::statuses(&mut conn).map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn run_job_now(name: String, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let mut conn = db::connection();
+        let login = verify_admin(&mut conn, &token_str)?;
+        println!("LOG: Admin {} started job {} manually", login, name);
+
+        scheduler::run_now(&name).map_err(ServerFnError::new)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_reorder_suggestions(token_str: String) -> Result<Vec<models::ReorderSuggestion>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        forecast::suggestions(&mut conn).map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn set_reorder_policy(
+    fuel_id: i32,
+    reorder_point: i32,
+    target_level: i32,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::reorder_policy;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        if reorder_point < 0 || target_level <= reorder_point {
+            return Err(ServerFnError::new(
+                "Цільовий рівень має бути більшим за точку перезамовлення",
+            ));
+        }
+
+        diesel::insert_into(reorder_policy::table)
+            .values((
+                reorder_policy::fuel_id.eq(fuel_id),
+                reorder_policy::reorder_point.eq(reorder_point),
+                reorder_policy::target_level.eq(target_level),
+            ))
+            .on_conflict(reorder_policy::fuel_id)
+            .do_update()
+            .set((
+                reorder_policy::reorder_point.eq(reorder_point),
+                reorder_policy::target_level.eq(target_level),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        forecast::refresh(&mut conn).map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Валовий прибуток по пальному за останні `days` днів.
+// Враховуються лише продажі з відомою собівартістю (після запуску обліку партій).
+#[server]
+async fn get_margin_report(days: u32, token_str: String) -> Result<Vec<models::FuelMargin>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::FuelMargin;
+        use crate::schema::{fuel, sale};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        let first_day = chrono::Local::now().date_naive() - chrono::Days::new(days.saturating_sub(1) as u64);
+        let from = reconciliation::day_start(first_day);
+
+        let rows: Vec<(i32, String, i32, i64, Option<i64>)> = sale::table
+            .inner_join(fuel::table)
+            .filter(sale::created_at.ge(from))
+            .filter(sale::cogs.is_not_null())
+            // Виручка — після знижок за акціями і без оплаченого балами
+            .select((sale::fuel_id, fuel::name, sale::amount, sale::total - sale::discount - sale::points_value, sale::cogs))
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        let mut report: Vec<FuelMargin> = Vec::new();
+        for (fuel_id, fuel_name, amount, total, cogs) in rows {
+            let idx = match report.iter().position(|m| m.fuel_id == fuel_id) {
+                Some(i) => i,
+                None => {
+                    report.push(FuelMargin {
+                        fuel_id,
+                        fuel_name,
+                        litres: 0,
+                        revenue: 0,
+                        cogs: 0,
+                        margin: 0,
+                        margin_pct: None,
+                    });
+                    report.len() - 1
+                }
+            };
+            let m = &mut report[idx];
+            m.litres += amount as i64;
+            m.revenue += total;
+            m.cogs += cogs.unwrap_or(0);
+        }
+
+        for m in report.iter_mut() {
+            m.margin = m.revenue - m.cogs;
+            m.margin_pct = utils::margin_percent(m.revenue, m.cogs);
+        }
+        report.sort_by_key(|m| m.fuel_id);
+        Ok(report)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_variance_alerts(token_str: String) -> Result<Vec<models::VarianceAlert>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::VarianceAlert;
+        use crate::schema::variance_alert;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        variance_alert::table
+            .filter(variance_alert::acknowledged.eq(false))
+            .order(variance_alert::created_at.desc())
+            .select(VarianceAlert::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn acknowledge_variance_alert(alert_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::variance_alert;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_admin(&mut conn, &token_str)?;
+
+        diesel::update(variance_alert::table.find(alert_id))
+            .set(variance_alert::acknowledged.eq(true))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn fetch_fuel_prices() -> Result<Vec<models::FuelPriceStats>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        market::current_prices(false).await.map_err(ServerFnError::new)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Ціни окремих мереж АЗС з ринкового джерела
+#[server]
+async fn fetch_market_quotes() -> Result<Vec<models::MarketQuote>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        market::current_quotes(false).await.map_err(ServerFnError::new)
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_notifications(user_id: i32, token_str: String) -> Result<Vec<models::Notification>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::Notification;
+        use crate::schema::notification;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        notification::table
+            .filter(notification::customer_id.eq(user_id))
+            .order(notification::created_at.desc())
+            .limit(50)
+            .select(Notification::as_select())
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn mark_notifications_read(user_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::notification;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        diesel::update(
+            notification::table
+                .filter(notification::customer_id.eq(user_id))
+                .filter(notification::read_at.is_null()),
+        )
+        .set(notification::read_at.eq(Some(chrono::Utc::now().timestamp())))
+        .execute(&mut conn)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_price_alerts(user_id: i32, token_str: String) -> Result<Vec<models::PriceAlertView>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::models::{PriceAlert, PriceAlertView};
+        use crate::schema::{fuel, price_alert};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        let rows: Vec<(PriceAlert, String)> = price_alert::table
+            .inner_join(fuel::table)
+            .filter(price_alert::customer_id.eq(user_id))
+            .order(price_alert::created_at.desc())
+            .select((PriceAlert::as_select(), fuel::name))
+            .load(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(alert, fuel_name)| PriceAlertView { alert, fuel_name })
+            .collect())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Підписка на зниження ціни (threshold у копійках) або на появу пального
+#[server]
+async fn create_price_alert(
+    user_id: i32,
+    fuel_id: i32,
+    kind: String,
+    threshold: Option<i64>,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::price_alert;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        let threshold = match kind.as_str() {
+            alerts::KIND_PRICE_BELOW => match threshold {
+                Some(t) if t > 0 => Some(t),
+                _ => return Err(ServerFnError::new("Вкажіть порогову ціну")),
+            },
+            alerts::KIND_AVAILABLE => None,
+            _ => return Err(ServerFnError::new("Невідомий тип сповіщення")),
+        };
+
+        diesel::insert_into(price_alert::table)
+            .values((
+                price_alert::customer_id.eq(user_id),
+                price_alert::fuel_id.eq(fuel_id),
+                price_alert::kind.eq(&kind),
+                price_alert::threshold.eq(threshold),
+                price_alert::created_at.eq(chrono::Utc::now().timestamp()),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        // Умова може виконуватись уже зараз
+        check_price_alerts(&mut conn);
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Поновлення спрацьованої підписки (active = true) або її вимкнення
+#[server]
+async fn set_price_alert_active(
+    user_id: i32,
+    alert_id: i32,
+    active: bool,
+    token_str: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::price_alert;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        let updated = diesel::update(
+            price_alert::table
+                .filter(price_alert::id.eq(alert_id))
+                .filter(price_alert::customer_id.eq(user_id)),
+        )
+        .set((price_alert::active.eq(active), price_alert::triggered_at.eq(None::<i64>)))
+        .execute(&mut conn)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        if updated == 0 {
+            return Err(ServerFnError::new("Підписку не знайдено"));
+        }
+
+        if active {
+            check_price_alerts(&mut conn);
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn delete_price_alert(user_id: i32, alert_id: i32, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::{notification, price_alert};
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let owned = price_alert::table
+                .filter(price_alert::id.eq(alert_id))
+                .filter(price_alert::customer_id.eq(user_id));
+            // Сповіщення залишаються в історії без посилання на підписку
+            diesel::update(notification::table.filter(notification::alert_id.eq(alert_id)))
+                .filter(notification::customer_id.eq(user_id))
+                .set(notification::alert_id.eq(None::<i32>))
+                .execute(conn)?;
+            diesel::delete(owned).execute(conn)
+        })
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[server]
+async fn get_notification_email(user_id: i32, token_str: String) -> Result<Option<String>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::customer;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        customer::table
+            .find(user_id)
+            .select(customer::email)
+            .first(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+// Порожній рядок вимикає сповіщення поштою
+#[server]
+async fn set_notification_email(user_id: i32, email: String, token_str: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        use crate::schema::customer;
+        use diesel::prelude::*;
+
+        let mut conn = db::connection();
+        verify_customer(&mut conn, user_id, &token_str)?;
+
+        let email = email.trim();
+        if !email.is_empty() && !utils::is_valid_email(email) {
+            return Err(ServerFnError::new("Некоректна адреса пошти"));
+        }
+
+        diesel::update(customer::table.find(user_id))
+            .set(customer::email.eq((!email.is_empty()).then_some(email)))
+            .execute(&mut conn)
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "server"))]
+    Err(ServerFnError::new("Server only"))
+}
+
+#[component]
+fn Navbar() -> Element {
+    let mut user_state = use_context::<Signal<Option<Customer>>>();
+    let mut admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let fmt_price = |cents: i64| format!("{:.2} грн", cents as f64 / 100.0);
+
+    let handle_logout = move |_| {
+        user_state.set(None);
+        admin_state.set(None);
+        nav.push(Route::LoginPage {});
+    };
+
+    rsx! {
+        nav { class: "navbar",
+            div { class: "nav-brand",
+                Link { to: Route::Home {}, "GasStation" }
+            }
+            div { class: "nav-links",
+                Link { to: Route::Home {}, class: "nav-item", "Головна" }
+                Link { to: Route::PricesPage {}, class: "nav-item", "Ціни" }
+
+                if let Some(user) = user_state() {
+                    Link { to: Route::NotificationsPage {}, class: "nav-item", "Сповіщення" }
+                    Link { to: Route::VehiclesPage {}, class: "nav-item", "Мої авто" }
+                    Link { to: Route::LoyaltyPage {}, class: "nav-item", "Бонуси" }
+                    div { class: "user-badge",
+                        span { class: "user-name", "{user.login}" }
+                        span { class: "user-balance", "{fmt_price(user.balance)}" }
+                    }
+                    button { class: "nav-item logout-btn", onclick: handle_logout, "Вийти" }
+                } else if let Some(admin) = admin_state() {
+                    div { class: "user-badge",
+                        style: "background-color: #4309a7ff; color: white;",
+                        span { class: "user-name", "Admin: {admin.login}" }
+                    }
+                    //Link { to: Route::AdminDashboard {}, class: "nav-item", "Дашборд" }
+                    Link { to: Route::ManagementPage {}, class: "nav-item", "Керування" }
+                    Link { to: Route::PricingPage {}, class: "nav-item", "Розклад цін" }
+                    Link { to: Route::ReconciliationPage {}, class: "nav-item", "Звірка" }
+                    Link { to: Route::SupplyPage {}, class: "nav-item", "Постачання" }
+                    Link { to: Route::MarginPage {}, class: "nav-item", "Маржа" }
+                    Link { to: Route::StaffEventsPage {}, class: "nav-item", "Події" }
+                    Link { to: Route::WebhooksPage {}, class: "nav-item", "Вебхуки" }
+                    Link { to: Route::ApiKeysPage {}, class: "nav-item", "API-ключі" }
+                    Link { to: Route::FleetPage {}, class: "nav-item", "Автопарки" }
+                    Link { to: Route::LoyaltyTiersPage {}, class: "nav-item", "Лояльність" }
+                    Link { to: Route::PromotionsPage {}, class: "nav-item", "Акції" }
+                    Link { to: Route::JobsPage {}, class: "nav-item", "Задачі" }
+                    button { class: "nav-item logout-btn", onclick: handle_logout, "Вийти" }
+                } else {
+                    Link { to: Route::LoginPage {}, class: "nav-item", "Вхід" }
+                    Link { to: Route::RegisterPage {}, class: "nav-item highlight", "Реєстрація" }
+                }
+            }
+        }
+        Outlet::<Route> {}
+    }
+}
+
+// Helper function to clean error messages
+fn clean_error_msg(error: String) -> String {
+    error
+        .replace("error running server function: ", "")
+        .replace(" (details: None)", "")
+}
+
+#[component]
+fn FuelCard(props: models::FuelCardProps) -> Element {
+    // Живе оновлення має пріоритет над даними, завантаженими разом зі сторінкою
+    let live_fuels = use_context::<Signal<LiveFuels>>();
+    let item = live_fuels.read().get(&props.item.id).cloned().unwrap_or(props.item);
+    let mut cart = props.cart;
+    let vehicle = props.vehicle;
+    let mut amount = use_signal(|| 1);
+    let mut user_state = use_context::<Signal<Option<Customer>>>();
+    let mut error_msg = use_signal(|| "".to_string());
+    let nav = use_navigator();
+
+    let is_electric = item.fuel_type == "electricity";
+
+    let icon = match item.fuel_type.as_str() {
+        "electricity" => "⚡",
+        "gas" => "☁️",
+        "diesel" => "🛢️",
+        _ => "⛽",
+    };
+
+    let unit = if is_electric { "кВт год" } else { "л" };
+
+    let price_val = item.price;
+    let total_cost = price_val * amount() as i64;
+    let fmt_money = |cents: i64| format!("{:.2}", cents as f64 / 100.0);
+
+    // progress bar logic
+    let percentage = if !is_electric && item.capacity > 0 {
+        (item.stored as f64 / item.capacity as f64) * 100.0
+    } else {
+        0.0
+    };
+    let bar_color = if percentage < 20.0 {
+        "#ef4444"
+    } else {
+        "#059669"
+    };
+
+    let is_available = if is_electric {
+        item.stored > 0
+    } else {
+        item.stored >= 1
+    };
+
+    let handle_buy = move |_| async move {
+        if let Some(user) = user_state() {
+            error_msg.set("Processing".to_string());
+            let token = user.session_token.clone().unwrap_or_default();
+            match buy_fuel(user.id, item.id, amount(), vehicle(), token).await {
+                Ok(new_balance) => {
+                    let mut updated_user = user.clone();
+                    updated_user.balance = new_balance;
+                    user_state.set(Some(updated_user));
+                    amount.set(1);
+                    error_msg.set("Успішно!".to_string());
+                }
+                Err(e) => error_msg.set(clean_error_msg(e.to_string())),
                 // Err(e) => error_msg.set(e.to_string()),
             }
-        } else {
-            nav.push(Route::LoginPage {});
+        } else {
+            nav.push(Route::LoginPage {});
+        }
+    };
+
+    let is_selected = cart().contains_key(&item.id);
+    let handle_toggle_cart = move |_| {
+        if cart().contains_key(&item.id) {
+            cart.write().remove(&item.id);
+        } else {
+            cart.write().insert(item.id, amount());
+        }
+    };
+
+    rsx! {
+        div { class: "fuel-item",
+            div { class: "fuel-header",
+                div { class: "fuel-icon", "{icon}" }
+                div {
+                    span { class: "fuel-name", "{item.name}" }
+                    div {  }
+                    span { class: "fuel-price", "{fmt_money(price_val)} грн/{unit}" }
+                }
+                if user_state().is_some() && is_available {
+                    div { style: "margin-left: auto;",
+                        input {
+                            type: "checkbox",
+                            checked: "{is_selected}",
+                            onchange: handle_toggle_cart,
+                            style: "transform: scale(1.5); cursor: pointer;"
+                        }
+                    }
+                }
+            }
+
+            if !is_electric {
+                div {
+                    style: "padding: 10px 20px 0 20px; display: flex; flex-direction: column; gap: 5px;",
+                    div {
+                        style: "width: 100%; height: 10px; background-color: #e5e7eb; border-radius: 5px; overflow: hidden;",
+                        div { style: "height: 100%; width: {percentage}%; background-color: {bar_color}; transition: width 0.5s ease;" }
+                    }
+                }
+            } else {
+                div {
+                    style: "padding: 10px 20px 0 20px; font-size: 0.9rem; font-weight: bold;",
+                    if is_available {
+                        span { style: "color: #059669;", "🟢 Зарядка доступна" }
+                    } else {
+                        span { style: "color: #dc2626;", "🔴 Тимчасово недоступно" }
+                    }
+                }
+            }
+
+            div { class: "fuel-controls",
+                div { class: "slider-container",
+                    label { "Купити: {amount} {unit}" }
+                    input {
+                        type: "range",
+                        min: "1",
+                        max: if is_electric { "100" } else { "{item.stored}" },
+                        value: "{amount}",
+                        oninput: move |e| {
+                            let val = e.value().parse().unwrap_or(1);
+                            amount.set(val);
+                            if cart().contains_key(&item.id) {
+                                cart.write().insert(item.id, val);
+                            }
+                        }
+                    }
+                }
+
+                div { class: "total-price",
+                    "До сплати: "
+                    span { class: "price-tag", "{fmt_money(total_cost)} грн" }
+                }
+
+                if !error_msg().is_empty() {
+                     div { class: "mini-error", "{error_msg}" }
+                }
+
+                button {
+                    class: "buy-button",
+                    disabled: !is_available,
+                    style: if !is_available { "background-color: gray; cursor: not-allowed;" } else { "" },
+                    onclick: handle_buy,
+                    if !is_available { "Недоступно" } else { "Купити" }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn About() -> Element {
+    const ABOUT_HTML: &str = include_str!("../assets/about.html");
+    rsx! {
+        div {
+            dangerous_inner_html: "{ABOUT_HTML}"
+        }
+    }
+}
+
+#[component]
+fn PricesPage() -> Element {
+    let prices = use_resource(|| fetch_fuel_prices());
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Середні ціни на пальне в Україні" }
+                p { class: "subtitle", style: "margin-bottom: 2rem;",
+                    "Дані оновлюються згідно з даними Мінфін"
+                }
+
+                match &*prices.read() {
+                    Some(Ok(list)) => rsx! {
+                        div { style: "overflow-x: auto;",
+                            table { style: "width: 100%; border-collapse: collapse;",
+                                thead {
+                                    tr { style: "background-color: #f3f4f6;",
+                                        th { style: "padding: 12px; text-align: left; border-bottom: 2px solid #e5e7eb;", "Тип пального" }
+                                        th { style: "padding: 12px; text-align: right; border-bottom: 2px solid #e5e7eb;", "Середня ціна" }
+                                        th { style: "padding: 12px; text-align: right; border-bottom: 2px solid #e5e7eb;", "Мін" }
+                                        th { style: "padding: 12px; text-align: right; border-bottom: 2px solid #e5e7eb;", "Макс" }
+                                    }
+                                }
+                                tbody {
+                                    for item in list {
+                                        tr { key: "{item.name}", style: "border-bottom: 1px solid #e5e7eb;",
+                                            td { style: "padding: 12px; font-weight: 600;", "{item.name}" }
+                                            td { style: "padding: 12px; text-align: right; color: #2563eb; font-weight: bold;",
+                                                "{item.average:.2} грн"
+                                            }
+                                            td { style: "padding: 12px; text-align: right; color: #6b7280;",
+                                                "{item.min:.2}"
+                                            }
+                                            td { style: "padding: 12px; text-align: right; color: #6b7280;",
+                                                "{item.max:.2}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! {
+                        div { class: "error-message",
+                            "Помилка завантаження даних: {e}"
+                        }
+                    },
+                    None => rsx! {
+                        div { class: "loading",
+                            "Завантаження цін"
+                        }
+                    }
+                }
+
+                h2 { style: "margin-top: 2rem;", "Ціни по мережах АЗС" }
+                BrandPrices {}
+
+                h2 { style: "margin-top: 2rem;", "Наші ціни" }
+                OurPriceCharts { market: prices }
+            }
+        }
+    }
+}
+
+// Ціни конкурентів по мережах із фільтром, сортуванням та місцем нашої АЗС
+#[component]
+fn BrandPrices() -> Element {
+    let quotes = use_resource(fetch_market_quotes);
+    let fuels = use_resource(get_fuels);
+    let mut fuel_filter = use_signal(String::new);
+    let mut brand_filter = use_signal(String::new);
+    let mut sort = use_signal(|| "price".to_string());
+
+    let quotes = match &*quotes.read() {
+        Some(Ok(list)) => list.clone(),
+        Some(Err(e)) => return rsx! { div { class: "error-message", "Помилка завантаження даних: {e}" } },
+        None => return rsx! { div { class: "loading", "Завантаження цін" } },
+    };
+    let ours: Vec<models::FuelWithTank> = match &*fuels.read() {
+        Some(Ok(list)) => list.clone(),
+        _ => Vec::new(),
+    };
+
+    let mut categories: Vec<String> = Vec::new();
+    for q in &quotes {
+        if !categories.contains(&q.fuel) {
+            categories.push(q.fuel.clone());
+        }
+    }
+
+    // Місце кожного нашого пального серед мереж у відповідній категорії
+    let ranks: Vec<(String, f64, usize, usize)> = ours
+        .iter()
+        .filter_map(|f| {
+            let category = utils::market_fuel_name(&f.name)?;
+            let competitors: Vec<f64> = quotes.iter().filter(|q| q.fuel == category).map(|q| q.price).collect();
+            if competitors.is_empty() {
+                return None;
+            }
+            let price = f.price as f64 / 100.0;
+            let (rank, total) = utils::price_rank(price, &competitors);
+            Some((f.name.clone(), price, rank, total))
+        })
+        .collect();
+
+    let brand_query = brand_filter().trim().to_lowercase();
+    let mut rows: Vec<models::MarketQuote> = quotes
+        .into_iter()
+        .filter(|q| fuel_filter().is_empty() || q.fuel == fuel_filter())
+        .filter(|q| brand_query.is_empty() || q.brand.to_lowercase().contains(&brand_query))
+        .collect();
+    match sort().as_str() {
+        "brand" => rows.sort_by(|a, b| a.brand.cmp(&b.brand).then(a.fuel.cmp(&b.fuel))),
+        "price_desc" => rows.sort_by(|a, b| b.price.total_cmp(&a.price)),
+        _ => rows.sort_by(|a, b| a.price.total_cmp(&b.price)),
+    }
+
+    rsx! {
+        if !ranks.is_empty() {
+            div { style: "display: flex; gap: 10px; flex-wrap: wrap; margin-bottom: 1rem;",
+                for (name, price, rank, total) in ranks {
+                    div { key: "{name}", class: "content-card", style: "padding: 10px 14px; margin: 0;",
+                        div { style: "font-weight: 600;", "{name}: {price:.2} грн" }
+                        div { style: if rank == 1 { "color: #16a34a;" } else { "color: #6b7280;" },
+                            "{rank}-е місце з {total}"
+                        }
+                    }
+                }
+            }
+        }
+        div { style: "display: flex; gap: 10px; align-items: center; flex-wrap: wrap; margin-bottom: 1rem;",
+            select {
+                class: "modern-input",
+                style: "width: auto;",
+                onchange: move |e| fuel_filter.set(e.value()),
+                option { value: "", "Усе пальне" }
+                for c in categories {
+                    option { key: "{c}", value: "{c}", "{c}" }
+                }
+            }
+            input {
+                class: "modern-input",
+                style: "width: auto;",
+                placeholder: "Мережа",
+                value: "{brand_filter}",
+                oninput: move |e| brand_filter.set(e.value()),
+            }
+            select {
+                class: "modern-input",
+                style: "width: auto;",
+                onchange: move |e| sort.set(e.value()),
+                option { value: "price", "Спершу дешевші" }
+                option { value: "price_desc", "Спершу дорожчі" }
+                option { value: "brand", "За назвою мережі" }
+            }
+        }
+        div { style: "overflow-x: auto;",
+            table { class: "data-table",
+                thead {
+                    tr {
+                        th { "Мережа" }
+                        th { "Пальне" }
+                        th { style: "text-align: right;", "Ціна" }
+                    }
+                }
+                tbody {
+                    for q in rows {
+                        tr { key: "{q.brand}-{q.fuel}",
+                            td { "{q.brand}" }
+                            td { "{q.fuel}" }
+                            td { style: "text-align: right;", "{q.price:.2} грн" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Графіки наших цін із середньою ринковою ціною для порівняння
+#[component]
+fn OurPriceCharts(market: Resource<Result<Vec<models::FuelPriceStats>, ServerFnError>>) -> Element {
+    let fuels = use_resource(get_fuels);
+    let mut fuel_id = use_signal(|| None::<i32>);
+    let mut range_days = use_signal(|| 30u32);
+
+    let list = match &*fuels.read() {
+        Some(Ok(list)) => list.clone(),
+        Some(Err(e)) => return rsx! { div { class: "error-message", "Помилка: {e}" } },
+        None => return rsx! { div { class: "loading", "Завантаження" } },
+    };
+    let Some(selected) = fuel_id().or(list.first().map(|f| f.id)) else {
+        return rsx! {};
+    };
+    let fuel_name = list.iter().find(|f| f.id == selected).map(|f| f.name.clone()).unwrap_or_default();
+
+    rsx! {
+        div { style: "display: flex; gap: 10px; align-items: center; flex-wrap: wrap; margin-bottom: 1rem;",
+            select {
+                class: "modern-input",
+                style: "width: auto;",
+                onchange: move |e| fuel_id.set(e.value().parse().ok()),
+                for f in list.iter() {
+                    option { key: "{f.id}", value: "{f.id}", selected: f.id == selected, "{f.name}" }
+                }
+            }
+            select {
+                class: "modern-input",
+                style: "width: auto;",
+                onchange: move |e| range_days.set(e.value().parse().unwrap_or(30)),
+                option { value: "7", "Тиждень" }
+                option { value: "30", selected: true, "Місяць" }
+                option { value: "90", "3 місяці" }
+                option { value: "365", "Рік" }
+            }
+        }
+        PriceChart {
+            key: "{selected}-{range_days}",
+            fuel_id: selected,
+            range_days: range_days(),
+            market_average: market_average(&market, &fuel_name),
+        }
+    }
+}
+
+// Середня ринкова ціна (у копійках) для нашого пального, якщо є відповідна категорія
+fn market_average(
+    market: &Resource<Result<Vec<models::FuelPriceStats>, ServerFnError>>,
+    fuel_name: &str,
+) -> Option<i64> {
+    let market_name = utils::market_fuel_name(fuel_name)?;
+    match &*market.read() {
+        Some(Ok(list)) => list
+            .iter()
+            .find(|s| s.name == market_name)
+            .map(|s| (s.average * 100.0).round() as i64),
+        _ => None,
+    }
+}
+
+#[component]
+fn PriceChart(fuel_id: i32, range_days: u32, market_average: Option<i64>) -> Element {
+    const WIDTH: f64 = 600.0;
+    const HEIGHT: f64 = 200.0;
+
+    let history = use_resource(move || async move { get_price_history(fuel_id, range_days).await });
+
+    let points = match &*history.read() {
+        Some(Ok(points)) => points.clone(),
+        Some(Err(e)) => return rsx! { div { class: "error-message", "Помилка: {e}" } },
+        None => return rsx! { div { class: "loading", "Завантаження" } },
+    };
+    let Some(first) = points.first() else {
+        return rsx! {};
+    };
+
+    let to = chrono::Utc::now().timestamp();
+    let from = first.at;
+    let steps = utils::step_chart_points(&points.iter().map(|p| (p.at, p.price)).collect::<Vec<_>>(), to);
+
+    // Запас по вертикалі, щоб лінії не торкались країв
+    let prices = steps.iter().map(|p| p.1).chain(market_average);
+    let (min, max) = prices.fold((i64::MAX, i64::MIN), |(lo, hi), p| (lo.min(p), hi.max(p)));
+    let pad = ((max - min) / 10).max(50);
+    let scale = (min - pad, max + pad);
+
+    let line = utils::svg_polyline(&steps, (from, to), scale, (WIDTH, HEIGHT));
+    let market_y = market_average.map(|avg| {
+        HEIGHT - (avg - scale.0) as f64 / (scale.1 - scale.0) as f64 * HEIGHT
+    });
+    let current = steps.last().map(|p| p.1).unwrap_or(first.price);
+
+    rsx! {
+        svg {
+            view_box: "0 0 {WIDTH} {HEIGHT}",
+            width: "100%",
+            style: "background: #f8fafc; border: 1px solid #e2e8f0; border-radius: 8px;",
+            polyline { points: "{line}", fill: "none", stroke: "#2563eb", stroke_width: "2" }
+            if let Some(y) = market_y {
+                line {
+                    x1: "0", y1: "{y:.1}", x2: "{WIDTH}", y2: "{y:.1}",
+                    stroke: "#f59e0b", stroke_width: "1.5", stroke_dasharray: "6 4"
+                }
+            }
+            text { x: "5", y: "14", font_size: "12", fill: "#6b7280", {utils::format_money(scale.1)} }
+            text { x: "5", y: "{HEIGHT - 5.0}", font_size: "12", fill: "#6b7280", {utils::format_money(scale.0)} }
+        }
+        div { style: "display: flex; justify-content: space-between; flex-wrap: wrap; gap: 10px; font-size: 0.9rem; color: #6b7280;",
+            span { {utils::format_timestamp(from)} }
+            span { style: "color: #2563eb; font-weight: bold;", "Наша ціна: {utils::format_money(current)}" }
+            if let Some(avg) = market_average {
+                span { style: "color: #f59e0b; font-weight: bold;",
+                    "Середня по ринку: {utils::format_money(avg)} ({utils::format_money(current - avg)})"
+                }
+            }
+            span { {utils::format_timestamp(to)} }
+        }
+    }
+}
+
+#[component]
+fn Footer() -> Element {
+    rsx! {
+        footer {
+            style: "background-color: #1f2937; color: white; padding: 2rem; margin-top: auto; width: 100%;",
+            div { style: "max-width: 1200px; margin: 0 auto; display: flex; justify-content: space-between; align-items: center; flex-wrap: wrap; gap: 20px;",
+                div {
+                    h3 { style: "margin: 0;", "GasStation" }
+                    p { style: "font-size: 0.9rem; color: #9ca3af; margin: 5px 0 0 0;", "© 2025 Всі права захищені" }
+                }
+                div { style: "display: flex; gap: 20px;",
+                    a { href: "/about", style: "color: white; text-decoration: none;", "Про компанію" }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn Home() -> Element {
+    let mut fuels = use_resource(|| get_fuels());
+    let mut user_state = use_context::<Signal<Option<Customer>>>();
+    let mut cart = use_signal(|| std::collections::HashMap::<i32, i32>::new());
+    let mut error_msg = use_signal(|| "".to_string());
+    let nav = use_navigator();
+    let selected_vehicle = use_signal(|| None::<i32>);
+    let vehicles = use_resource(move || async move {
+        match user_state() {
+            Some(user) => get_vehicles(user.id, user.session_token.unwrap_or_default()).await,
+            None => Ok(Vec::new()),
+        }
+    });
+    let mut driver_account = use_resource(move || async move {
+        match user_state() {
+            Some(user) => get_driver_account(user.id, user.session_token.unwrap_or_default()).await,
+            None => Ok(None),
+        }
+    });
+    let mut loyalty = use_resource(move || async move {
+        match user_state() {
+            Some(user) => get_loyalty_account(user.id, user.session_token.unwrap_or_default())
+                .await
+                .map(Some),
+            None => Ok(None),
+        }
+    });
+    let mut points_input = use_signal(String::new);
+    let mut coupon_input = use_signal(String::new);
+    let mut applied_coupon = use_signal(|| None::<String>);
+    // Знижку за акціями рахує сервер — ті самі правила, що й при оплаті
+    let quote = use_resource(move || async move {
+        let items: Vec<(i32, i32)> = cart().into_iter().collect();
+        match user_state() {
+            Some(user) if !items.is_empty() => {
+                preview_checkout(user.id, items, applied_coupon(), user.session_token.unwrap_or_default())
+                    .await
+                    .map(Some)
+            }
+            _ => Ok(None),
+        }
+    });
+
+    let handle_buy_batch = move |_| async move {
+        if let Some(user) = user_state() {
+            error_msg.set("Processing...".to_string());
+            let token = user.session_token.clone().unwrap_or_default();
+            let items: Vec<(i32, i32)> = cart().into_iter().collect();
+
+            if items.is_empty() {
+                error_msg.set("Кошик пустий".to_string());
+                return;
+            }
+            let input = points_input();
+            let points = match input.trim() {
+                "" => 0,
+                value => match value.parse::<i64>() {
+                    Ok(p) => p,
+                    Err(_) => {
+                        error_msg.set("Вкажіть кількість балів цілим числом".to_string());
+                        return;
+                    }
+                },
+            };
+
+            match buy_fuel_batch(user.id, items, selected_vehicle(), points, applied_coupon(), token).await {
+                Ok(new_balance) => {
+                    let mut updated_user = user.clone();
+                    updated_user.balance = new_balance;
+                    user_state.set(Some(updated_user));
+                    cart.write().clear();
+                    error_msg.set("Успішно куплено!".to_string());
+                    fuels.restart(); // Refresh fuel data
+                    driver_account.restart();
+                    loyalty.restart();
+                    points_input.set(String::new());
+                    coupon_input.set(String::new());
+                    applied_coupon.set(None);
+                }
+                Err(e) => error_msg.set(clean_error_msg(e.to_string())),
+            }
+        } else {
+            nav.push(Route::LoginPage {});
+        }
+    };
+
+    let live_fuels = use_context::<Signal<LiveFuels>>();
+    let total_cart_cost = {
+        let current_cart = cart();
+        let live = live_fuels.read();
+        if let Some(Ok(list)) = &*fuels.read() {
+            list.iter()
+                .filter_map(|f| {
+                    let price = live.get(&f.id).map_or(f.price, |l| l.price);
+                    current_cart
+                        .get(&f.id)
+                        .map(|&amount| price * amount as i64)
+                })
+                .sum::<i64>()
+        } else {
+            0
+        }
+    };
+    let fmt_money = |cents: i64| format!("{:.2}", cents as f64 / 100.0);
+
+    // Бали можна витратити лише з особистого балансу (не водієві автопарку)
+    let is_driver = matches!(&*driver_account.read(), Some(Ok(Some(_))));
+    let available_points = match &*loyalty.read() {
+        Some(Ok(Some(account))) if !is_driver => account.points,
+        _ => 0,
+    };
+    let points_discount = points_input().trim().parse::<i64>().unwrap_or(0).max(0) * 100;
+    let promo_discount = match &*quote.read() {
+        Some(Ok(Some(q))) => q.discount,
+        _ => 0,
+    };
+
+    rsx! {
+        div { style: "display: flex; flex-direction: column; min-height: 100vh;",
+            div { class: "page-container", style: "flex: 1; flex-direction: column; align-items: center; gap: 2rem;",
+                div { class: "content-card",
+                    if user_state().is_none() {
+                        h1 { "Асортимент" }
+                        p { class: "subtitle", "Увійдіть, щоб купити пальне" }
+                    } else {
+                        h1 { "Оберіть пальне" }
+                    }
+
+                    if let Some(Ok(Some(account))) = &*driver_account.read() {
+                        DriverAccountPanel { account: account.clone() }
+                    }
+
+                    if let Some(Ok(list)) = &*vehicles.read() {
+                        if !list.is_empty() {
+                            VehicleSelect { vehicles: list.clone(), selected: selected_vehicle }
+                        }
+                    }
+
+                    match &*fuels.read() {
+                        Some(Ok(list)) => rsx! {
+                            div { class: "fuel-grid",
+                                for item in list {
+                                    FuelCard {
+                                        key: "{item.id}",
+                                        item: item.clone(),
+                                        cart: cart,
+                                        vehicle: selected_vehicle
+                                    }
+                                }
+                            }
+                        },
+                        Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                        None => rsx! { div { class: "loading", "Завантаження" } }
+                    }
+
+                    if !cart().is_empty() && user_state().is_some() {
+                        div { class: "batch-controls", style: "margin-top: 20px; padding: 20px; border-top: 1px solid #e5e7eb; display: flex; flex-direction: column; align-items: center; gap: 10px;",
+                            div { style: "display: flex; gap: 10px; align-items: center;",
+                                input {
+                                    class: "modern-input",
+                                    style: "width: 160px;",
+                                    placeholder: "Купон",
+                                    value: "{coupon_input}",
+                                    oninput: move |e| coupon_input.set(e.value()),
+                                }
+                                button {
+                                    onclick: move |_| {
+                                        let code = coupon_input().trim().to_string();
+                                        applied_coupon.set((!code.is_empty()).then_some(code));
+                                    },
+                                    "Застосувати"
+                                }
+                            }
+                            match &*quote.read() {
+                                Some(Ok(Some(q))) if q.discount > 0 => rsx! {
+                                    div { style: "color: #16a34a;",
+                                        "Знижка ({q.promotion_name.clone().unwrap_or_default()}): −{fmt_money(q.discount)} грн"
+                                    }
+                                },
+                                Some(Err(e)) => rsx! { div { class: "mini-error", {clean_error_msg(e.to_string())} } },
+                                _ => rsx! {},
+                            }
+                            if available_points > 0 {
+                                div { style: "display: flex; gap: 10px; align-items: center;",
+                                    span { "Списати балів (доступно {available_points}, 1 бал = 1 грн):" }
+                                    input {
+                                        class: "modern-input",
+                                        style: "width: 100px;",
+                                        r#type: "number",
+                                        min: "0",
+                                        max: "{available_points}",
+                                        value: "{points_input}",
+                                        oninput: move |e| points_input.set(e.value()),
+                                    }
+                                }
+                            }
+                            div { style: "font-size: 1.2rem; font-weight: bold;",
+                                "Разом до сплати: "
+                                span { style: "color: #2563eb;", "{fmt_money((total_cart_cost - promo_discount - points_discount).max(0))} грн" }
+                            }
+                            if !error_msg().is_empty() {
+                                div { class: "mini-error", "{error_msg}" }
+                            }
+                            button {
+                                class: "modern-button",
+                                style: "width: 100%; max-width: 300px; background-color: #2563eb;",
+                                onclick: handle_buy_batch,
+                                "Купити вибране"
+                            }
+                        }
+                    }
+                }
+            }
+            Footer {}
+        }
+    }
+}
+
+fn fuel_type_label(fuel_type: &str) -> &str {
+    FUEL_TYPE_LABELS
+        .iter()
+        .find(|(k, _)| *k == fuel_type)
+        .map_or(fuel_type, |(_, label)| *label)
+}
+
+fn capacity_label(vehicle: &models::Vehicle) -> String {
+    let unit = if vehicle.fuel_type == "electricity" { "кВт·год" } else { "л" };
+    format!("{} {}", vehicle.tank_capacity, unit)
+}
+
+// Вибір авто для покупки: сервер перевіряє тип пального та об'єм бака
+#[component]
+fn VehicleSelect(vehicles: Vec<models::VehicleView>, selected: Signal<Option<i32>>) -> Element {
+    let mut selected = selected;
+    rsx! {
+        div { style: "display: flex; gap: 10px; align-items: center; margin-bottom: 1rem;",
+            span { "Заправляю:" }
+            select {
+                class: "modern-input",
+                style: "width: auto;",
+                onchange: move |e| selected.set(e.value().parse().ok()),
+                option { value: "", selected: selected().is_none(), "Без вибору авто" }
+                for v in vehicles {
+                    option {
+                        key: "{v.vehicle.id}",
+                        value: "{v.vehicle.id}",
+                        selected: selected() == Some(v.vehicle.id),
+                        "{v.vehicle.plate} — {fuel_type_label(&v.vehicle.fuel_type)}, {capacity_label(&v.vehicle)}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Рахунок автопарку для водія: покупки списуються з балансу компанії
+#[component]
+fn DriverAccountPanel(account: models::DriverAccount) -> Element {
+    let limit = |spent: i64, limit: Option<i64>| match limit {
+        Some(l) => format!("{} з {}", utils::format_money(spent), utils::format_money(l)),
+        None => format!("{} (без ліміту)", utils::format_money(spent)),
+    };
+    let fuel_types = account.driver.allowed_fuel_types.clone().map(|types| {
+        types
+            .split(',')
+            .map(fuel_type_label)
+            .collect::<Vec<_>>()
+            .join(", ")
+    });
+
+    rsx! {
+        div { style: "margin-bottom: 1.5rem; padding: 1rem; border: 1px solid #bfdbfe; border-radius: 8px; background-color: #eff6ff;",
+            div { style: "font-weight: bold; margin-bottom: 0.5rem;",
+                "Автопарк {account.company_name}: рахунок компанії {utils::format_money(account.company_balance)}"
+            }
+            if !account.driver.active {
+                div { class: "mini-error", "Картку водія заблоковано" }
+            }
+            div { "Сьогодні: {limit(account.spent_today, account.driver.daily_limit)}" }
+            div { "Цього місяця: {limit(account.spent_month, account.driver.monthly_limit)}" }
+            if let Some(types) = fuel_types {
+                div { "Дозволене пальне: {types}" }
+            }
+        }
+    }
+}
+
+fn loyalty_kind_label(kind: &str) -> &str {
+    match kind {
+        "earn" => "Нараховано",
+        "redeem" => "Оплата балами",
+        "expire" => "Згоріли",
+        other => other,
+    }
+}
+
+// Бонусний рахунок клієнта: рівень, бали та їх рух
+#[component]
+fn LoyaltyPage() -> Element {
+    let user_state = use_context::<Signal<Option<Customer>>>();
+    let nav = use_navigator();
+
+    let account = use_resource(move || async move {
+        let user_id = user_state().map(|u| u.id).unwrap_or_default();
+        let token = user_state().and_then(|u| u.session_token).unwrap_or_default();
+        get_loyalty_account(user_id, token).await
+    });
+
+    if user_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let rate = |tier: &models::LoyaltyTier| {
+        let mut parts = Vec::new();
+        if tier.points_per_100_uah > 0 {
+            parts.push(format!("{} за кожні 100 грн", tier.points_per_100_uah));
+        }
+        if tier.points_per_litre > 0 {
+            parts.push(format!("{} за літр", tier.points_per_litre));
+        }
+        if parts.is_empty() {
+            "бали не нараховуються".to_string()
+        } else {
+            parts.join(" + ")
+        }
+    };
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Бонуси" }
+                match &*account.read() {
+                    Some(Ok(acc)) => rsx! {
+                        div { style: "display: flex; gap: 2rem; flex-wrap: wrap; margin-bottom: 1rem;",
+                            div {
+                                div { class: "subtitle", "Доступно балів" }
+                                div { style: "font-size: 2rem; font-weight: bold; color: #2563eb;", "{acc.points}" }
+                            }
+                            div {
+                                div { class: "subtitle", "Рівень" }
+                                div { style: "font-size: 1.5rem; font-weight: bold;", "{acc.tier.name}" }
+                                div { "Бали: {rate(&acc.tier)}" }
+                            }
+                        }
+                        p { "Покупки за рік: " b { {utils::format_money(acc.spent_year)} } }
+                        if let Some(next) = acc.next_tier.clone() {
+                            p {
+                                "До рівня «{next.name}» ({rate(&next)}) залишилось "
+                                b { {utils::format_money(next.min_spent - acc.spent_year)} }
+                            }
+                        }
+                        if let Some((points, at)) = acc.expiring {
+                            div { class: "mini-error", "{points} балів згорять {utils::format_timestamp(at)}" }
+                        }
+                        p { class: "subtitle",
+                            "1 бал = 1 грн при оплаті кошика. Бали діють рік від нарахування."
+                        }
+
+                        h2 { style: "margin-top: 1.5rem;", "Історія" }
+                        if acc.history.is_empty() {
+                            p { class: "subtitle", "Балів ще не було" }
+                        } else {
+                            table { class: "data-table",
+                                thead {
+                                    tr {
+                                        th { "Час" }
+                                        th { "Операція" }
+                                        th { "Бали" }
+                                        th { "Діють до" }
+                                    }
+                                }
+                                tbody {
+                                    for entry in acc.history.clone() {
+                                        tr { key: "{entry.id}",
+                                            td { {utils::format_timestamp(entry.created_at)} }
+                                            td { {loyalty_kind_label(&entry.kind)} }
+                                            td {
+                                                style: if entry.points < 0 { "color: #b91c1c;" } else { "color: #047857;" },
+                                                if entry.points > 0 { "+{entry.points}" } else { "{entry.points}" }
+                                            }
+                                            td {
+                                                if entry.kind == "earn" && entry.remaining > 0 {
+                                                    {entry.expires_at.map(utils::format_timestamp).unwrap_or_default()}
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+            }
+        }
+    }
+}
+
+// Авто клієнта та історія їх заправок
+#[component]
+fn VehiclesPage() -> Element {
+    let user_state = use_context::<Signal<Option<Customer>>>();
+    let nav = use_navigator();
+    let mut msg = use_signal(|| "".to_string());
+    let mut plate = use_signal(String::new);
+    let mut fuel_type = use_signal(|| "petrol".to_string());
+    let mut capacity = use_signal(String::new);
+    let mut history_for = use_signal(|| None::<i32>);
+
+    let user_id = move || user_state().map(|u| u.id).unwrap_or_default();
+    let token = move || {
+        user_state()
+            .and_then(|u| u.session_token)
+            .unwrap_or_default()
+    };
+    let mut vehicles = use_resource(move || async move { get_vehicles(user_id(), token()).await });
+    let history = use_resource(move || async move {
+        match history_for() {
+            Some(id) => get_vehicle_history(user_id(), id, token()).await.map(Some),
+            None => Ok(None),
+        }
+    });
+
+    if user_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let handle_add = move |_| async move {
+        let Ok(tank) = capacity().trim().parse::<i32>() else {
+            msg.set("Вкажіть об'єм бака цілим числом".to_string());
+            return;
+        };
+        match add_vehicle(user_id(), plate(), fuel_type(), tank, token()).await {
+            Ok(v) => {
+                msg.set(format!("Авто {} додано", v.plate));
+                plate.set(String::new());
+                capacity.set(String::new());
+                vehicles.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Мої авто" }
+                p { class: "subtitle",
+                    "Оберіть авто на головній перед покупкою — пальне іншого типу або понад об'єм бака не буде продано."
+                }
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+
+                div { style: "display: flex; gap: 10px; align-items: center; margin-bottom: 1rem;",
+                    input {
+                        class: "modern-input",
+                        placeholder: "Номер (AA1234BB)",
+                        value: "{plate}",
+                        oninput: move |e| plate.set(e.value()),
+                    }
+                    select {
+                        class: "modern-input",
+                        style: "width: auto;",
+                        onchange: move |e| fuel_type.set(e.value()),
+                        for (value, label) in FUEL_TYPE_LABELS {
+                            option { key: "{value}", value: "{value}", selected: fuel_type() == value, "{label}" }
+                        }
+                    }
+                    input {
+                        class: "modern-input",
+                        style: "width: 160px;",
+                        placeholder: if fuel_type() == "electricity" { "Батарея, кВт·год" } else { "Бак, л" },
+                        value: "{capacity}",
+                        oninput: move |e| capacity.set(e.value()),
+                    }
+                    button { onclick: handle_add, "Додати авто" }
+                }
+
+                match &*vehicles.read() {
+                    Some(Ok(list)) if list.is_empty() => rsx! {
+                        p { class: "subtitle", "Авто ще не додано" }
+                    },
+                    Some(Ok(list)) => rsx! {
+                        table { class: "data-table",
+                            thead {
+                                tr {
+                                    th { "Номер" }
+                                    th { "Пальне" }
+                                    th { "Бак" }
+                                    th { "Заправок" }
+                                    th { "Обсяг" }
+                                    th { "Сума" }
+                                    th { "Остання" }
+                                    th { "" }
+                                }
+                            }
+                            tbody {
+                                for v in list.clone() {
+                                    tr { key: "{v.vehicle.id}",
+                                        style: if history_for() == Some(v.vehicle.id) { "background-color: #eff6ff;" } else { "" },
+                                        td { "{v.vehicle.plate}" }
+                                        td { {fuel_type_label(&v.vehicle.fuel_type)} }
+                                        td { {capacity_label(&v.vehicle)} }
+                                        td { "{v.fills}" }
+                                        td { "{v.total_volume}" }
+                                        td { {utils::format_money(v.total_cost)} }
+                                        td { {v.last_fill_at.map(utils::format_timestamp).unwrap_or("-".to_string())} }
+                                        td {
+                                            div { style: "display: flex; gap: 5px;",
+                                                button {
+                                                    onclick: move |_| history_for.set(Some(v.vehicle.id)),
+                                                    "Історія"
+                                                }
+                                                button {
+                                                    onclick: move |_| async move {
+                                                        match delete_vehicle(user_id(), v.vehicle.id, token()).await {
+                                                            Ok(_) => {
+                                                                if history_for() == Some(v.vehicle.id) {
+                                                                    history_for.set(None);
+                                                                }
+                                                                vehicles.restart();
+                                                            }
+                                                            Err(e) => msg.set(clean_error_msg(e.to_string())),
+                                                        }
+                                                    },
+                                                    "Видалити"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+
+                match &*history.read() {
+                    Some(Ok(Some(h))) => rsx! {
+                        h2 { style: "margin-top: 2rem;", "Споживання {h.vehicle.plate}" }
+                        if h.fills.is_empty() {
+                            p { class: "subtitle", "Заправок з цим авто ще не було" }
+                        } else {
+                            table { class: "data-table", style: "margin-bottom: 1rem;",
+                                thead {
+                                    tr {
+                                        th { "Місяць" }
+                                        th { "Обсяг" }
+                                        th { "Сума" }
+                                    }
+                                }
+                                tbody {
+                                    for (month, volume, cost) in h.by_month.clone() {
+                                        tr { key: "{month}",
+                                            td { "{month}" }
+                                            td { "{volume}" }
+                                            td { {utils::format_money(cost)} }
+                                        }
+                                    }
+                                }
+                            }
+                            table { class: "data-table",
+                                thead {
+                                    tr {
+                                        th { "Час" }
+                                        th { "Пальне" }
+                                        th { "Обсяг" }
+                                        th { "Сума" }
+                                    }
+                                }
+                                tbody {
+                                    for fill in h.fills.clone() {
+                                        tr { key: "{fill.sale_id}",
+                                            td { {utils::format_timestamp(fill.created_at)} }
+                                            td { "{fill.fuel_name}" }
+                                            td { "{fill.amount}" }
+                                            td { {utils::format_money(fill.total)} }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    _ => rsx! {}
+                }
+            }
+        }
+    }
+}
+
+// Центр сповіщень клієнта: отримані сповіщення, підписки на ціну/наявність та пошта
+#[component]
+fn NotificationsPage() -> Element {
+    let user_state = use_context::<Signal<Option<Customer>>>();
+    let nav = use_navigator();
+    let mut msg = use_signal(|| "".to_string());
+
+    let user_id = move || user_state().map(|u| u.id).unwrap_or_default();
+    let token = move || {
+        user_state()
+            .and_then(|u| u.session_token)
+            .unwrap_or_default()
+    };
+    let mut notifications = use_resource(move || async move { get_notifications(user_id(), token()).await });
+    let mut alerts = use_resource(move || async move { get_price_alerts(user_id(), token()).await });
+    let fuels = use_resource(get_fuels);
+    let email = use_resource(move || async move { get_notification_email(user_id(), token()).await });
+
+    let mut fuel_id = use_signal(|| None::<i32>);
+    let mut kind = use_signal(|| "price_below".to_string());
+    let mut threshold = use_signal(String::new);
+    let mut email_input = use_signal(String::new);
+
+    use_effect(move || {
+        if let Some(Ok(Some(e))) = &*email.read() {
+            email_input.set(e.clone());
+        }
+    });
+
+    if user_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let fuel_list = match &*fuels.read() {
+        Some(Ok(list)) => list.clone(),
+        _ => Vec::new(),
+    };
+    let selected_fuel = fuel_id().or(fuel_list.first().map(|f| f.id));
+
+    let handle_create = move |_| async move {
+        let Some(f) = selected_fuel else {
+            return;
+        };
+        let limit = if kind() == "price_below" {
+            match threshold().replace(',', ".").parse::<f64>() {
+                Ok(v) if v > 0.0 => Some((v * 100.0).round() as i64),
+                _ => {
+                    msg.set("Невірний формат ціни".to_string());
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        match create_price_alert(user_id(), f, kind(), limit, token()).await {
+            Ok(_) => {
+                msg.set("Підписку створено".to_string());
+                threshold.set(String::new());
+                alerts.restart();
+                notifications.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_active = move |(id, active): (i32, bool)| async move {
+        match set_price_alert_active(user_id(), id, active, token()).await {
+            Ok(_) => {
+                alerts.restart();
+                notifications.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_delete = move |id: i32| async move {
+        match delete_price_alert(user_id(), id, token()).await {
+            Ok(_) => alerts.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_read = move |_| async move {
+        match mark_notifications_read(user_id(), token()).await {
+            Ok(_) => notifications.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_email = move |_| async move {
+        match set_notification_email(user_id(), email_input(), token()).await {
+            Ok(_) => msg.set("Пошту збережено".to_string()),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let fmt_price = |cents: i64| format!("{:.2} грн", cents as f64 / 100.0);
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Сповіщення" }
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+
+                match &*notifications.read() {
+                    Some(Ok(list)) if list.is_empty() => rsx! {
+                        p { class: "subtitle", "Сповіщень поки немає" }
+                    },
+                    Some(Ok(list)) => rsx! {
+                        div { style: "display: flex; justify-content: flex-end; margin-bottom: 1rem;",
+                            button { onclick: handle_read, "Позначити прочитаними" }
+                        }
+                        table { class: "data-table",
+                            tbody {
+                                for n in list.clone() {
+                                    tr { key: "{n.id}",
+                                        style: if n.read_at.is_none() { "font-weight: 600;" } else { "" },
+                                        td { "{utils::format_timestamp(n.created_at)}" }
+                                        td { "{n.message}" }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } },
+                }
+
+                h2 { style: "margin-top: 2rem;", "Мої підписки" }
+                div { style: "display: flex; gap: 10px; align-items: center; flex-wrap: wrap; margin-bottom: 1rem;",
+                    select {
+                        class: "modern-input",
+                        style: "width: auto;",
+                        onchange: move |e| fuel_id.set(e.value().parse().ok()),
+                        for f in fuel_list.iter() {
+                            option { key: "{f.id}", value: "{f.id}", selected: Some(f.id) == selected_fuel, "{f.name}" }
+                        }
+                    }
+                    select {
+                        class: "modern-input",
+                        style: "width: auto;",
+                        onchange: move |e| kind.set(e.value()),
+                        option { value: "price_below", "Ціна нижче за" }
+                        option { value: "available", "Знову доступне" }
+                    }
+                    if kind() == "price_below" {
+                        input {
+                            class: "modern-input",
+                            style: "width: 120px;",
+                            placeholder: "грн",
+                            value: "{threshold}",
+                            oninput: move |e| threshold.set(e.value()),
+                        }
+                    }
+                    button { onclick: handle_create, "Підписатися" }
+                }
+
+                match &*alerts.read() {
+                    Some(Ok(list)) if !list.is_empty() => rsx! {
+                        table { class: "data-table",
+                            thead {
+                                tr {
+                                    th { "Пальне" }
+                                    th { "Умова" }
+                                    th { "Стан" }
+                                    th { "" }
+                                }
+                            }
+                            tbody {
+                                for a in list.clone() {
+                                    tr { key: "{a.alert.id}",
+                                        td { "{a.fuel_name}" }
+                                        td {
+                                            if let Some(t) = a.alert.threshold {
+                                                "Ціна нижче {fmt_price(t)}"
+                                            } else {
+                                                "Знову доступне"
+                                            }
+                                        }
+                                        td {
+                                            if a.alert.active {
+                                                "Очікує"
+                                            } else if let Some(ts) = a.alert.triggered_at {
+                                                "Спрацювало {utils::format_timestamp(ts)}"
+                                            } else {
+                                                "Вимкнено"
+                                            }
+                                        }
+                                        td {
+                                            if a.alert.active {
+                                                button { onclick: move |_| handle_active((a.alert.id, false)), "Вимкнути" }
+                                            } else {
+                                                button { onclick: move |_| handle_active((a.alert.id, true)), "Поновити" }
+                                            }
+                                            button { onclick: move |_| handle_delete(a.alert.id), "Видалити" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    _ => rsx! {},
+                }
+
+                h2 { style: "margin-top: 2rem;", "Сповіщення поштою" }
+                div { style: "display: flex; gap: 10px; align-items: center; flex-wrap: wrap;",
+                    input {
+                        class: "modern-input",
+                        style: "width: auto;",
+                        r#type: "email",
+                        placeholder: "name@example.com",
+                        value: "{email_input}",
+                        oninput: move |e| email_input.set(e.value()),
+                    }
+                    button { onclick: handle_email, "Зберегти" }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn LoginPage() -> Element {
+    let mut login = use_signal(|| "".to_string());
+    let mut password = use_signal(|| "".to_string());
+    let mut error_msg = use_signal(|| "".to_string());
+
+    let mut user_state = use_context::<Signal<Option<Customer>>>();
+    let mut admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+
+    let handle_login = move |_| async move {
+        error_msg.set("".to_string());
+        let l = login();
+        let p = password();
+
+        // Try user login first
+        match login_user(l.clone(), p.clone()).await {
+            Ok(user) => {
+                user_state.set(Some(user));
+                nav.push(Route::Home {});
+            }
+            Err(_) => {
+                // Try admin login
+                match login_admin(l, p).await {
+                    Ok(admin) => {
+                        admin_state.set(Some(admin));
+                        nav.push(Route::ManagementPage {});
+                    }
+                    Err(_) => error_msg.set("Невірний логін або пароль".to_string()),
+                }
+            }
+        }
+    };
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "auth-card",
+                h2 { "Вхід в кабінет" }
+                div { class: "form-content",
+                    input { class: "modern-input", placeholder: "Логін", value: "{login}", oninput: move |e| login.set(e.value()) }
+                    input { class: "modern-input", type: "password", placeholder: "Пароль", value: "{password}", oninput: move |e| password.set(e.value()) }
+                    if !error_msg().is_empty() { div { class: "error-message", "{error_msg}" } }
+                    button { class: "modern-button", onclick: handle_login, "Увійти" }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn RegisterPage() -> Element {
+    let mut login = use_signal(|| "".to_string());
+    let mut password = use_signal(|| "".to_string());
+    let mut error_msg = use_signal(|| "".to_string());
+    let nav = use_navigator();
+
+    let handle_register = move |_| async move {
+        error_msg.set("".to_string());
+        match register_user(login(), password()).await {
+            Ok(_) => {
+                nav.push(Route::LoginPage {});
+            }
+            Err(e) => error_msg.set(e.to_string()),
+        }
+    };
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "auth-card",
+                h2 { "Створити акаунт" }
+                div { class: "form-content",
+                    input { class: "modern-input", placeholder: "Логін", value: "{login}", oninput: move |e| login.set(e.value()) }
+                    input { class: "modern-input", type: "password", placeholder: "Пароль", value: "{password}", oninput: move |e| password.set(e.value()) }
+                    if !error_msg().is_empty() { div { class: "error-message", "{error_msg}" } }
+                    button { class: "modern-button", onclick: handle_register, "Зареєструватися" }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+#[component]
+fn AdminDashboard() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "page-container",
+            h1 { "Панель адміністратора" }
+            div { class: "admin-header",
+                p { "Вітаємо, {admin_state().unwrap().login}!" }
+            }
+            div { class: "content-card",
+                p { "Тут поки що пусто. Перейдіть на вкладку 'Керування'." }
+            }
+        }
+    }
+}
+
+#[component]
+fn ManagementPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let fuels = use_resource(|| get_fuels());
+    let bank_info = use_resource(|| get_bank_info());
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let fmt_money = |cents: i64| format!("{:.2} грн", cents as f64 / 100.0);
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "management-layout",
+                // Left Column: Header and Bank Info
+                div { class: "management-sidebar",
+                    h1 { style: "text-align: left;", "Керування заправкою" }
+                    div { class: "admin-header",
+                        match &*bank_info.read() {
+                            Some(Ok(bank)) => rsx! {
+                                div { class: "bank-info",
+                                    span { "Баланс банку: " }
+                                    span { class: "money-value", "{fmt_money(bank.total)}" }
+                                }
+                            },
+                            Some(Err(e)) => rsx! { div { class: "error", "Помилка банку: {e}" } },
+                            None => rsx! { "Завантаження..." }
+                        }
+                    }
+                }
+
+                // Right Column: Fuel Management
+                div { class: "content-card management-content",
+                    h2 { "Керування пальним" }
+                    match &*fuels.read() {
+                        Some(Ok(list)) => rsx! {
+                            div { class: "fuel-grid",
+                                for item in list {
+                                    AdminFuelItem { key: "{item.id}", item: item.clone() }
+                                }
+                            }
+                        },
+                        Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                        None => rsx! { div { class: "loading", "Завантаження" } }
+                    }
+
+                    ReorderSuggestions {}
+                    TankStockTable {}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ReorderSuggestions() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let mut suggestions = use_resource(move || async move {
+        let token = admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default();
+        get_reorder_suggestions(token).await
+    });
+
+    rsx! {
+        h2 { style: "margin-top: 2rem;", "Рекомендовані замовлення" }
+        match &*suggestions.read() {
+            Some(Ok(list)) => rsx! {
+                div { style: "overflow-x: auto;",
+                    table { class: "data-table",
+                        thead {
+                            tr {
+                                th { "Пальне" }
+                                th { "Залишок, л" }
+                                th { "В дорозі, л" }
+                                th { "Споживання, л/добу" }
+                                th { "Вистачить, днів" }
+                                th { "Точка / ціль, л" }
+                                th { "Замовити, л" }
+                            }
+                        }
+                        tbody {
+                            for s in list.clone() {
+                                ReorderRow {
+                                    key: "{s.fuel_id}",
+                                    item: s,
+                                    on_saved: move |_| suggestions.restart(),
+                                }
+                            }
+                        }
+                    }
+                }
+                if list.iter().any(|s| s.below_reorder_point && s.suggested_qty > 0) {
+                    Link { to: Route::SupplyPage {}, class: "nav-item highlight", "Оформити замовлення" }
+                }
+            },
+            Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+            None => rsx! { div { class: "loading", "Завантаження" } }
+        }
+    }
+}
+
+#[component]
+fn ReorderRow(item: models::ReorderSuggestion, on_saved: EventHandler<()>) -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let mut point = use_signal(|| item.policy.reorder_point.to_string());
+    let mut target = use_signal(|| item.policy.target_level.to_string());
+    let mut msg = use_signal(|| "".to_string());
+    let fuel_id = item.fuel_id;
+
+    let handle_save = move |_| async move {
+        let token = admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default();
+        match (point().parse::<i32>(), target().parse::<i32>()) {
+            (Ok(p), Ok(t)) => match set_reorder_policy(fuel_id, p, t, token).await {
+                Ok(_) => on_saved.call(()),
+                Err(e) => msg.set(clean_error_msg(e.to_string())),
+            },
+            _ => msg.set("Невірний формат".to_string()),
+        }
+    };
+
+    let days_left = item
+        .policy
+        .days_until_empty
+        .map(|d| format!("{:.1}", d))
+        .unwrap_or("-".to_string());
+
+    rsx! {
+        tr { style: if item.below_reorder_point { "background-color: #fee2e2;" } else { "" },
+            td { "{item.fuel_name}" }
+            td { "{item.stored} / {item.capacity}" }
+            td { "{item.on_order}" }
+            td { {item.policy.avg_daily_sales.map(|a| format!("{:.0}", a)).unwrap_or("-".to_string())} }
+            td { "{days_left}" }
+            td {
+                div { style: "display: flex; gap: 5px; align-items: center;",
+                    input {
+                        class: "price-input",
+                        style: "width: 70px;",
+                        value: "{point}",
+                        oninput: move |e| point.set(e.value())
+                    }
+                    input {
+                        class: "price-input",
+                        style: "width: 70px;",
+                        value: "{target}",
+                        oninput: move |e| target.set(e.value())
+                    }
+                    button { onclick: handle_save, "Зберегти" }
+                }
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+            }
+            td { style: "font-weight: bold;", "{item.suggested_qty}" }
+        }
+    }
+}
+
+#[component]
+fn TankStockTable() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let mut stock = use_resource(move || async move {
+        let token = admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default();
+        get_tank_stock(token).await
+    });
+    let fmt_opt = |v: Option<f64>, prec: usize| {
+        v.map(|x| format!("{:.*}", prec, x))
+            .unwrap_or("-".to_string())
+    };
+
+    rsx! {
+        h2 { style: "margin-top: 2rem;", "Залишки в резервуарах" }
+        match &*stock.read() {
+            Some(Ok(list)) => rsx! {
+                div { style: "overflow-x: auto;",
+                    table { class: "data-table",
+                        thead {
+                            tr {
+                                th { "Резервуар" }
+                                th { "Пальне" }
+                                th { "За обліком, л" }
+                                th { "Виміряно, л" }
+                                th { "При 15 °C, л" }
+                                th { "Різниця, л" }
+                                th { "Вода, мм" }
+                                th { "t, °C" }
+                                th { "Час заміру" }
+                            }
+                        }
+                        tbody {
+                            for t in list {
+                                tr { key: "{t.tank_id}",
+                                    td { "#{t.tank_id}" }
+                                    td { "{t.fuel_name}" }
+                                    td { "{t.book} / {t.capacity}" }
+                                    if let Some(m) = t.measured {
+                                        td { "{m:.0}" }
+                                        td { {fmt_opt(t.measured_net, 0)} }
+                                        td {
+                                            style: if (m - t.book as f64).abs() > t.capacity as f64 * 0.01 { "color: #dc2626; font-weight: bold;" } else { "" },
+                                            "{m - t.book as f64:+.0}"
+                                        }
+                                    } else {
+                                        td { "-" }
+                                        td { "-" }
+                                        td { "-" }
+                                    }
+                                    td { {fmt_opt(t.water_height, 0)} }
+                                    td { {fmt_opt(t.temperature, 1)} }
+                                    td { {t.measured_at.map(utils::format_timestamp).unwrap_or("-".to_string())} }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+            None => rsx! { div { class: "loading", "Завантаження" } }
+        }
+
+        if let Some(Ok(list)) = &*stock.read() {
+            DipEntryForm {
+                tanks: list.iter().map(|t| (t.tank_id, t.fuel_name.clone())).collect::<Vec<_>>(),
+                on_saved: move |_| stock.restart(),
+            }
+        }
+    }
+}
+
+#[component]
+fn DipEntryForm(tanks: Vec<(i32, String)>, on_saved: EventHandler<()>) -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let mut tank_id = use_signal(|| tanks.first().map(|t| t.0).unwrap_or(0));
+    let mut height = use_signal(|| "".to_string());
+    let mut water = use_signal(|| "0".to_string());
+    let mut temperature = use_signal(|| "15".to_string());
+    let mut csv = use_signal(|| "".to_string());
+    let mut msg = use_signal(|| "".to_string());
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+
+    let handle_dip = move |_| async move {
+        let parsed = (
+            height().replace(',', ".").parse::<f64>(),
+            water().replace(',', ".").parse::<f64>(),
+            temperature().replace(',', ".").parse::<f64>(),
+        );
+        match parsed {
+            (Ok(h), Ok(w), Ok(t)) => match record_dip(tank_id(), h, w, t, token()).await {
+                Ok(volume) => {
+                    msg.set(format!("Замір збережено: {:.0} л", volume));
+                    on_saved.call(());
+                }
+                Err(e) => msg.set(clean_error_msg(e.to_string())),
+            },
+            _ => msg.set("Невірний формат".to_string()),
+        }
+    };
+
+    let handle_import = move |_| async move {
+        match import_strapping_chart(tank_id(), csv(), token()).await {
+            Ok(rows) => {
+                msg.set(format!("Імпортовано точок: {}", rows));
+                csv.set("".to_string());
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    rsx! {
+        h2 { style: "margin-top: 2rem;", "Замір щупом" }
+        div { class: "admin-controls",
+            div { class: "control-group",
+                label { "Резервуар:" }
+                select {
+                    class: "modern-input",
+                    onchange: move |e| tank_id.set(e.value().parse().unwrap_or(0)),
+                    for (id, name) in tanks.iter() {
+                        option { key: "{id}", value: "{id}", "#{id} — {name}" }
+                    }
+                }
+            }
+            div { class: "control-group",
+                label { "Рівень продукту, мм:" }
+                input { class: "price-input", value: "{height}", oninput: move |e| height.set(e.value()) }
+                label { "Рівень води, мм:" }
+                input { class: "price-input", value: "{water}", oninput: move |e| water.set(e.value()) }
+                label { "Температура, °C:" }
+                input { class: "price-input", value: "{temperature}", oninput: move |e| temperature.set(e.value()) }
+                button { onclick: handle_dip, "Записати замір" }
+            }
+            div { class: "control-group", style: "flex-direction: column; align-items: stretch;",
+                label { "Градуювальна таблиця (CSV: висота_мм,об'єм_л):" }
+                textarea {
+                    class: "modern-input",
+                    rows: "5",
+                    value: "{csv}",
+                    oninput: move |e| csv.set(e.value())
+                }
+                button { onclick: handle_import, "Імпортувати таблицю" }
+            }
+            if !msg().is_empty() {
+                div { class: "status-msg", "{msg}" }
+            }
+        }
+    }
+}
+
+#[component]
+fn ReconciliationPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let mut days = use_signal(|| 7u32);
+    let mut msg = use_signal(|| "".to_string());
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+    let report = use_resource(move || async move { get_reconciliation(days(), token()).await });
+    let mut alerts = use_resource(move || async move { get_variance_alerts(token()).await });
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let handle_ack = move |alert_id: i32| async move {
+        match acknowledge_variance_alert(alert_id, token()).await {
+            Ok(_) => alerts.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Звірка залишків" }
+                p { class: "subtitle",
+                    "Початковий залишок + надходження − продажі у порівнянні з виміряним залишком на кінець дня"
+                }
+
+                div { style: "display: flex; gap: 10px; align-items: center; margin-bottom: 1rem;",
+                    label { "Період:" }
+                    select {
+                        class: "modern-input",
+                        style: "width: auto;",
+                        onchange: move |e| days.set(e.value().parse().unwrap_or(7)),
+                        option { value: "7", "7 днів" }
+                        option { value: "14", "14 днів" }
+                        option { value: "30", "30 днів" }
+                    }
+                }
+
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+
+                match &*alerts.read() {
+                    Some(Ok(list)) if !list.is_empty() => rsx! {
+                        div { class: "error-message", style: "text-align: left;",
+                            h3 { style: "margin-top: 0;", "Розбіжності понад поріг" }
+                            for a in list.clone() {
+                                div { key: "{a.id}", style: "display: flex; justify-content: space-between; align-items: center; gap: 10px; margin: 5px 0;",
+                                    span { "Резервуар #{a.tank_id}, {a.day}: {a.variance:+.0} л (поріг {a.threshold} л)" }
+                                    button { onclick: move |_| handle_ack(a.id), "Прийнято" }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    _ => rsx! {}
+                }
+
+                match &*report.read() {
+                    Some(Ok(tanks)) => rsx! {
+                        for t in tanks.clone() {
+                            TankReconciliationTable { key: "{t.tank_id}", item: t, report: report }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn MarginPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let mut days = use_signal(|| 7u32);
+
+    let report = use_resource(move || async move {
+        let token = admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default();
+        get_margin_report(days(), token).await
+    });
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Валовий прибуток" }
+                p { class: "subtitle",
+                    "Собівартість розраховано за партіями поставок (FIFO)"
+                }
+
+                div { style: "display: flex; gap: 10px; align-items: center; margin-bottom: 1rem;",
+                    label { "Період:" }
+                    select {
+                        class: "modern-input",
+                        style: "width: auto;",
+                        onchange: move |e| days.set(e.value().parse().unwrap_or(7)),
+                        option { value: "1", "Сьогодні" }
+                        option { value: "7", selected: true, "7 днів" }
+                        option { value: "30", "30 днів" }
+                        option { value: "90", "90 днів" }
+                    }
+                }
+
+                match &*report.read() {
+                    Some(Ok(list)) if list.is_empty() => rsx! { p { class: "loading", "Немає продажів за період" } },
+                    Some(Ok(list)) => {
+                        let revenue: i64 = list.iter().map(|m| m.revenue).sum();
+                        let cogs: i64 = list.iter().map(|m| m.cogs).sum();
+                        let pct = |p: Option<f64>| p.map(|x| format!("{:.1}%", x)).unwrap_or("-".to_string());
+                        rsx! {
+                            div { style: "overflow-x: auto;",
+                                table { class: "data-table",
+                                    thead {
+                                        tr {
+                                            th { "Пальне" }
+                                            th { "Продано, л" }
+                                            th { "Виручка" }
+                                            th { "Собівартість" }
+                                            th { "Маржа" }
+                                            th { "Маржа, %" }
+                                        }
+                                    }
+                                    tbody {
+                                        for m in list {
+                                            tr { key: "{m.fuel_id}",
+                                                td { "{m.fuel_name}" }
+                                                td { "{m.litres}" }
+                                                td { {utils::format_money(m.revenue)} }
+                                                td { {utils::format_money(m.cogs)} }
+                                                td { {utils::format_money(m.margin)} }
+                                                td { {pct(m.margin_pct)} }
+                                            }
+                                        }
+                                        tr { style: "font-weight: bold;",
+                                            td { "Разом" }
+                                            td { {list.iter().map(|m| m.litres).sum::<i64>().to_string()} }
+                                            td { {utils::format_money(revenue)} }
+                                            td { {utils::format_money(cogs)} }
+                                            td { {utils::format_money(revenue - cogs)} }
+                                            td { {pct(utils::margin_percent(revenue, cogs))} }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn JobsPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let mut msg = use_signal(|| "".to_string());
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+    let mut jobs = use_resource(move || async move { get_job_statuses(token()).await });
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let handle_run = move |name: String| async move {
+        match run_job_now(name.clone(), token()).await {
+            Ok(_) => {
+                msg.set(format!("Задачу {} запущено", name));
+                jobs.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let fmt_ts = |ts: Option<i64>| ts.map(utils::format_timestamp).unwrap_or("-".to_string());
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Фонові задачі" }
+                div { style: "display: flex; justify-content: flex-end; margin-bottom: 1rem;",
+                    button { onclick: move |_| jobs.restart(), "Оновити" }
+                }
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+                match &*jobs.read() {
+                    Some(Ok(list)) => rsx! {
+                        div { style: "overflow-x: auto;",
+                            table { class: "data-table",
+                                thead {
+                                    tr {
+                                        th { "Задача" }
+                                        th { "Розклад" }
+                                        th { "Статус" }
+                                        th { "Останній запуск" }
+                                        th { "Наступний" }
+                                        th { "Запусків" }
+                                        th { "" }
+                                    }
+                                }
+                                tbody {
+                                    for s in list.clone() {
+                                        tr { key: "{s.job.name}",
+                                            td { "{s.job.name}" }
+                                            td { code { "{s.job.schedule}" } }
+                                            td {
+                                                if s.running {
+                                                    "Виконується"
+                                                } else {
+                                                    {match s.job.last_status.as_deref() {
+                                                        Some("ok") => "Успішно",
+                                                        Some("error") => "Помилка",
+                                                        Some("skipped") => "Пропущено",
+                                                        _ => "-",
+                                                    }}
+                                                }
+                                                if let Some(m) = s.job.last_message.clone() {
+                                                    div { style: "font-size: 0.8rem; color: #6b7280;", "{m}" }
+                                                }
+                                                if let Some(e) = s.job.last_error.clone() {
+                                                    div { class: "mini-error",
+                                                        "{fmt_ts(s.job.last_error_at)}: {e}"
+                                                    }
+                                                }
+                                            }
+                                            td { {fmt_ts(s.job.last_started_at)} }
+                                            td { {fmt_ts(s.next_run)} }
+                                            td { "{s.job.run_count}" }
+                                            td {
+                                                button {
+                                                    disabled: s.running,
+                                                    onclick: move |_| handle_run(s.job.name.clone()),
+                                                    "Запустити"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn StaffEventsPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let mut msg = use_signal(|| "".to_string());
+    let mut channel_kind = use_signal(|| "email".to_string());
+    let mut channel_target = use_signal(String::new);
+    let mut channel_severity = use_signal(|| "warning".to_string());
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+    let mut events = use_resource(move || async move { get_staff_events(token()).await });
+    let mut channels = use_resource(move || async move { get_staff_channels(token()).await });
+    let thresholds = use_resource(move || async move { get_tank_thresholds(token()).await });
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let handle_ack = move |id: i32| async move {
+        match acknowledge_staff_event(id, token()).await {
+            Ok(_) => events.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_add_channel = move |_| async move {
+        match add_staff_channel(channel_kind(), channel_target(), channel_severity(), token()).await {
+            Ok(_) => {
+                msg.set("Канал додано".to_string());
+                channel_target.set(String::new());
+                channels.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_toggle_channel = move |(id, enabled): (i32, bool)| async move {
+        match set_staff_channel_enabled(id, enabled, token()).await {
+            Ok(_) => channels.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_delete_channel = move |id: i32| async move {
+        match delete_staff_channel(id, token()).await {
+            Ok(_) => channels.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let severity_style = |severity: &str| match severity {
+        "critical" => "background-color: #fee2e2;",
+        _ => "background-color: #fef3c7;",
+    };
+    let kind_label = |kind: &str| match kind {
+        "low_stock" => "Низький залишок",
+        "charger_offline" => "Зарядка офлайн",
+        "refill_failed" => "Невдале поповнення",
+        "variance" => "Розбіжність",
+        _ => "Інше",
+    };
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Події для персоналу" }
+                div { style: "display: flex; justify-content: flex-end; margin-bottom: 1rem;",
+                    button { onclick: move |_| events.restart(), "Оновити" }
+                }
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+                match &*events.read() {
+                    Some(Ok(list)) if list.is_empty() => rsx! {
+                        p { class: "subtitle", "Подій немає" }
+                    },
+                    Some(Ok(list)) => rsx! {
+                        div { style: "overflow-x: auto;",
+                            table { class: "data-table",
+                                thead {
+                                    tr {
+                                        th { "Тип" }
+                                        th { "Подія" }
+                                        th { "Повторів" }
+                                        th { "Вперше" }
+                                        th { "Востаннє" }
+                                        th { "Стан" }
+                                        th { "Підтвердження" }
+                                    }
+                                }
+                                tbody {
+                                    for e in list.clone() {
+                                        tr { key: "{e.id}",
+                                            style: if e.acknowledged_at.is_none() { severity_style(&e.severity) } else { "" },
+                                            td { {kind_label(&e.kind)} }
+                                            td { "{e.message}" }
+                                            td { "{e.occurrences}" }
+                                            td { {utils::format_timestamp(e.created_at)} }
+                                            td { {utils::format_timestamp(e.last_seen_at)} }
+                                            td {
+                                                if let Some(ts) = e.resolved_at {
+                                                    "Вирішено {utils::format_timestamp(ts)}"
+                                                } else {
+                                                    "Активна"
+                                                }
+                                            }
+                                            td {
+                                                match (e.acknowledged_by.clone(), e.acknowledged_at) {
+                                                    (Some(login), Some(ts)) => rsx! { "{login}, {utils::format_timestamp(ts)}" },
+                                                    _ => rsx! {
+                                                        button { onclick: move |_| handle_ack(e.id), "Підтвердити" }
+                                                    },
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+
+                h2 { style: "margin-top: 2rem;", "Пороги низького залишку" }
+                match &*thresholds.read() {
+                    Some(Ok(list)) => rsx! {
+                        div { style: "overflow-x: auto;",
+                            table { class: "data-table",
+                                thead {
+                                    tr {
+                                        th { "Резервуар" }
+                                        th { "Залишок" }
+                                        th { "Діючий поріг" }
+                                        th { "Поріг, л" }
+                                    }
+                                }
+                                tbody {
+                                    for t in list.clone() {
+                                        TankThresholdRow { key: "{t.tank_id}", item: t, thresholds }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+
+                h2 { style: "margin-top: 2rem;", "Канали доставки" }
+                div { style: "display: flex; gap: 10px; flex-wrap: wrap; align-items: center; margin-bottom: 1rem;",
+                    select {
+                        class: "modern-input",
+                        value: "{channel_kind}",
+                        onchange: move |e| channel_kind.set(e.value()),
+                        option { value: "email", "Пошта" }
+                        option { value: "webhook", "Вебхук" }
+                    }
+                    input {
+                        class: "modern-input",
+                        placeholder: if channel_kind() == "email" { "staff@example.com" } else { "https://example.com/hook" },
+                        value: "{channel_target}",
+                        oninput: move |e| channel_target.set(e.value()),
+                    }
+                    select {
+                        class: "modern-input",
+                        value: "{channel_severity}",
+                        onchange: move |e| channel_severity.set(e.value()),
+                        option { value: "warning", "Усі події" }
+                        option { value: "critical", "Лише критичні" }
+                    }
+                    button { onclick: handle_add_channel, "Додати" }
+                }
+                match &*channels.read() {
+                    Some(Ok(list)) if list.is_empty() => rsx! {
+                        p { class: "subtitle", "Канали не налаштовано — події видно лише тут" }
+                    },
+                    Some(Ok(list)) => rsx! {
+                        table { class: "data-table",
+                            thead {
+                                tr {
+                                    th { "Тип" }
+                                    th { "Адреса" }
+                                    th { "Рівень" }
+                                    th { "" }
+                                }
+                            }
+                            tbody {
+                                for c in list.clone() {
+                                    tr { key: "{c.id}",
+                                        td { if c.kind == "email" { "Пошта" } else { "Вебхук" } }
+                                        td { "{c.target}" }
+                                        td { if c.min_severity == "critical" { "Лише критичні" } else { "Усі події" } }
+                                        td { style: "display: flex; gap: 5px;",
+                                            button {
+                                                onclick: move |_| handle_toggle_channel((c.id, !c.enabled)),
+                                                if c.enabled { "Вимкнути" } else { "Увімкнути" }
+                                            }
+                                            button { onclick: move |_| handle_delete_channel(c.id), "Видалити" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TankThresholdRow(
+    item: models::TankThreshold,
+    thresholds: Resource<Result<Vec<models::TankThreshold>, ServerFnError>>,
+) -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let mut input = use_signal(|| item.threshold.map(|t| t.to_string()).unwrap_or_default());
+    let mut msg = use_signal(|| "".to_string());
+    let tank_id = item.tank_id;
+
+    // Порожнє поле — типовий поріг
+    let handle_save = move |_| async move {
+        let token = admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default();
+        let value = input();
+        let litres = if value.trim().is_empty() {
+            None
+        } else {
+            match value.trim().parse::<i32>() {
+                Ok(l) => Some(l),
+                Err(_) => {
+                    msg.set("Невірний формат".to_string());
+                    return;
+                }
+            }
+        };
+        match set_low_stock_threshold(tank_id, litres, token).await {
+            Ok(_) => {
+                msg.set("Збережено".to_string());
+                thresholds.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    rsx! {
+        tr {
+            style: if item.stored <= item.effective { "background-color: #fef3c7;" } else { "" },
+            td { "#{item.tank_id} — {item.fuel_name}" }
+            td { "{item.stored} / {item.capacity} л" }
+            td {
+                "{item.effective} л"
+                if item.threshold.is_none() {
+                    span { style: "color: #6b7280;", " (типовий)" }
+                }
+            }
+            td {
+                div { style: "display: flex; gap: 5px; align-items: center;",
+                    input {
+                        class: "price-input",
+                        placeholder: "20%",
+                        value: "{input}",
+                        oninput: move |e| input.set(e.value())
+                    }
+                    button { onclick: handle_save, "Зберегти" }
+                }
+                if !msg().is_empty() {
+                    div { style: "font-size: 0.8rem; color: #6b7280;", "{msg}" }
+                }
+            }
+        }
+    }
+}
+
+// Події вебхуків для форми підписки (сервер перевіряє за webhooks::EVENTS)
+const WEBHOOK_EVENTS: [(&str, &str); 4] = [
+    ("sale.completed", "Продаж"),
+    ("price.changed", "Зміна ціни"),
+    ("tank.refilled", "Поповнення резервуара"),
+    ("stock.low", "Низький залишок"),
+];
+
+#[component]
+fn WebhooksPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let mut msg = use_signal(|| "".to_string());
+    let mut url = use_signal(String::new);
+    let mut selected = use_signal(|| WEBHOOK_EVENTS.iter().map(|(e, _)| e.to_string()).collect::<Vec<_>>());
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+    let mut subscriptions = use_resource(move || async move { get_webhook_subscriptions(token()).await });
+    let mut deliveries = use_resource(move || async move { get_webhook_deliveries(token()).await });
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let handle_create = move |_| async move {
+        match create_webhook_subscription(url(), selected(), token()).await {
+            Ok(sub) => {
+                msg.set(format!("Підписку створено. Секрет для перевірки підпису: {}", sub.secret));
+                url.set(String::new());
+                subscriptions.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_toggle = move |(id, enabled): (i32, bool)| async move {
+        match set_webhook_enabled(id, enabled, token()).await {
+            Ok(_) => subscriptions.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_delete = move |id: i32| async move {
+        match delete_webhook_subscription(id, token()).await {
+            Ok(_) => {
+                subscriptions.restart();
+                deliveries.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_retry = move |id: i32| async move {
+        match retry_webhook_delivery(id, token()).await {
+            Ok(_) => {
+                msg.set(format!("Доставку #{} поставлено в чергу", id));
+                deliveries.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let events_label = |events: &str| {
+        if events == "*" {
+            return "Усі події".to_string();
+        }
+        events
+            .split(',')
+            .map(|e| WEBHOOK_EVENTS.iter().find(|(k, _)| *k == e).map(|(_, l)| *l).unwrap_or(e))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Вебхуки" }
+                p { class: "subtitle",
+                    "Події надсилаються POST-запитом з JSON. Підпис: заголовок X-GasStation-Signature = sha256=HMAC-SHA256(секрет, \"<X-GasStation-Timestamp>.<тіло>\")."
+                }
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+
+                h2 { "Підписки" }
+                div { style: "display: flex; gap: 10px; flex-wrap: wrap; align-items: center; margin-bottom: 1rem;",
+                    input {
+                        class: "modern-input",
+                        style: "min-width: 320px;",
+                        placeholder: "https://erp.example.com/hooks/gas",
+                        value: "{url}",
+                        oninput: move |e| url.set(e.value()),
+                    }
+                    for (event, label) in WEBHOOK_EVENTS {
+                        label { key: "{event}", style: "display: flex; gap: 4px; align-items: center;",
+                            input {
+                                r#type: "checkbox",
+                                checked: selected().iter().any(|e| e == event),
+                                onchange: move |e| {
+                                    let mut list = selected.write();
+                                    list.retain(|x| x != event);
+                                    if e.checked() {
+                                        list.push(event.to_string());
+                                    }
+                                }
+                            }
+                            "{label}"
+                        }
+                    }
+                    button { onclick: handle_create, "Додати" }
+                }
+                match &*subscriptions.read() {
+                    Some(Ok(list)) if list.is_empty() => rsx! {
+                        p { class: "subtitle", "Підписок немає" }
+                    },
+                    Some(Ok(list)) => rsx! {
+                        div { style: "overflow-x: auto;",
+                            table { class: "data-table",
+                                thead {
+                                    tr {
+                                        th { "#" }
+                                        th { "URL" }
+                                        th { "Події" }
+                                        th { "Секрет" }
+                                        th { "Створено" }
+                                        th { "" }
+                                    }
+                                }
+                                tbody {
+                                    for sub in list.clone() {
+                                        tr { key: "{sub.id}",
+                                            style: if sub.enabled { "" } else { "color: #9ca3af;" },
+                                            td { "{sub.id}" }
+                                            td { "{sub.url}" }
+                                            td { {events_label(&sub.events)} }
+                                            td { code { "{sub.secret}" } }
+                                            td { {utils::format_timestamp(sub.created_at)} }
+                                            td { style: "display: flex; gap: 5px;",
+                                                button {
+                                                    onclick: move |_| handle_toggle((sub.id, !sub.enabled)),
+                                                    if sub.enabled { "Вимкнути" } else { "Увімкнути" }
+                                                }
+                                                button { onclick: move |_| handle_delete(sub.id), "Видалити" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+
+                div { style: "display: flex; justify-content: space-between; align-items: center; margin-top: 2rem;",
+                    h2 { "Журнал доставок" }
+                    button { onclick: move |_| deliveries.restart(), "Оновити" }
+                }
+                match &*deliveries.read() {
+                    Some(Ok(list)) if list.is_empty() => rsx! {
+                        p { class: "subtitle", "Доставок ще не було" }
+                    },
+                    Some(Ok(list)) => rsx! {
+                        div { style: "overflow-x: auto;",
+                            table { class: "data-table",
+                                thead {
+                                    tr {
+                                        th { "#" }
+                                        th { "Підписка" }
+                                        th { "Подія" }
+                                        th { "Створено" }
+                                        th { "Статус" }
+                                        th { "Спроб" }
+                                        th { "Відповідь" }
+                                        th { "" }
+                                    }
+                                }
+                                tbody {
+                                    for d in list.clone() {
+                                        tr { key: "{d.id}",
+                                            style: if d.status == "failed" { "background-color: #fee2e2;" } else { "" },
+                                            td { "{d.id}" }
+                                            td { "#{d.subscription_id}" }
+                                            td { code { "{d.event}" } }
+                                            td { {utils::format_timestamp(d.created_at)} }
+                                            td {
+                                                {match d.status.as_str() {
+                                                    "delivered" => format!(
+                                                        "Доставлено {}",
+                                                        d.delivered_at.map(utils::format_timestamp).unwrap_or_default()
+                                                    ),
+                                                    "failed" => "Не доставлено".to_string(),
+                                                    _ if d.attempts > 0 => format!(
+                                                        "Повтор о {}",
+                                                        utils::format_timestamp(d.next_attempt_at)
+                                                    ),
+                                                    _ => "У черзі".to_string(),
+                                                }}
+                                            }
+                                            td { "{d.attempts}" }
+                                            td {
+                                                if let Some(code) = d.last_status_code {
+                                                    "{code} "
+                                                }
+                                                if let Some(e) = d.last_error.clone() {
+                                                    div { class: "mini-error", "{e}" }
+                                                }
+                                            }
+                                            td {
+                                                if d.status == "failed" {
+                                                    button { onclick: move |_| handle_retry(d.id), "Повторити" }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+            }
+        }
+    }
+}
+
+// Типи пального з підписами для форм водіїв і авто (сервер перевіряє за FUEL_TYPES)
+const FUEL_TYPE_LABELS: [(&str, &str); 4] = [
+    ("petrol", "Бензин"),
+    ("diesel", "Дизель"),
+    ("gas", "Газ"),
+    ("electricity", "Зарядка"),
+];
+
+// Ліміт у гривнях з поля форми: порожнє — без ліміту
+fn parse_limit(value: &str) -> Result<Option<i64>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .replace(',', ".")
+        .parse::<f64>()
+        .map(|uah| Some((uah * 100.0).round() as i64))
+        .map_err(|_| format!("Невірний ліміт: {}", value))
+}
+
+fn limit_input(limit: Option<i64>) -> String {
+    limit.map(|l| format!("{:.2}", l as f64 / 100.0)).unwrap_or_default()
+}
+
+fn fuel_types_of(allowed: Option<&str>) -> Vec<String> {
+    FUEL_TYPE_LABELS
+        .iter()
+        .map(|(t, _)| t.to_string())
+        .filter(|t| utils::fuel_type_allowed(allowed, t))
+        .collect()
+}
+
+#[component]
+fn FleetFuelTypes(selected: Signal<Vec<String>>) -> Element {
+    let mut selected = selected;
+    rsx! {
+        for (fuel_type, label) in FUEL_TYPE_LABELS {
+            label { key: "{fuel_type}", style: "display: flex; gap: 4px; align-items: center;",
+                input {
+                    r#type: "checkbox",
+                    checked: selected().iter().any(|t| t == fuel_type),
+                    onchange: move |e| {
+                        let mut list = selected.write();
+                        list.retain(|x| x != fuel_type);
+                        if e.checked() {
+                            list.push(fuel_type.to_string());
+                        }
+                    }
+                }
+                "{label}"
+            }
+        }
+    }
+}
+
+#[component]
+fn FleetPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let mut msg = use_signal(|| "".to_string());
+    let mut company_name = use_signal(String::new);
+    let mut selected_company = use_signal(|| None::<i32>);
+    let mut top_up_amount = use_signal(String::new);
+    let mut top_up_note = use_signal(String::new);
+    let mut driver_login = use_signal(String::new);
+    let mut card_number = use_signal(String::new);
+    let mut daily_limit = use_signal(String::new);
+    let mut monthly_limit = use_signal(String::new);
+    let new_driver_types = use_signal(|| fuel_types_of(None));
+    let mut month = use_signal(|| chrono::Local::now().format("%Y-%m").to_string());
+    let mut statement = use_signal(|| None::<models::FleetStatement>);
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+    let mut companies = use_resource(move || async move { get_fleet_companies(token()).await });
+    let mut drivers = use_resource(move || async move {
+        match selected_company() {
+            Some(id) => get_fleet_drivers(id, token()).await,
+            None => Ok(Vec::new()),
+        }
+    });
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let handle_create = move |_| async move {
+        match create_fleet_company(company_name(), token()).await {
+            Ok(company) => {
+                msg.set(format!("Компанію {} створено", company.name));
+                company_name.set(String::new());
+                selected_company.set(Some(company.id));
+                companies.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_top_up = move |_| async move {
+        let Some(id) = selected_company() else {
+            return;
+        };
+        let amount = match parse_limit(&top_up_amount()) {
+            Ok(Some(a)) => a,
+            Ok(None) => {
+                msg.set("Вкажіть суму поповнення".to_string());
+                return;
+            }
+            Err(e) => {
+                msg.set(e);
+                return;
+            }
+        };
+        match top_up_fleet_company(id, amount, top_up_note(), token()).await {
+            Ok(balance) => {
+                msg.set(format!("Рахунок поповнено, баланс {}", utils::format_money(balance)));
+                top_up_amount.set(String::new());
+                top_up_note.set(String::new());
+                companies.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_add_driver = move |_| async move {
+        let Some(id) = selected_company() else {
+            return;
+        };
+        let (daily, monthly) = match (parse_limit(&daily_limit()), parse_limit(&monthly_limit())) {
+            (Ok(d), Ok(m)) => (d, m),
+            (Err(e), _) | (_, Err(e)) => {
+                msg.set(e);
+                return;
+            }
+        };
+        match add_fleet_driver(id, driver_login(), card_number(), daily, monthly, new_driver_types(), token()).await {
+            Ok(_) => {
+                msg.set(format!("Водія {} додано", driver_login()));
+                driver_login.set(String::new());
+                card_number.set(String::new());
+                drivers.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_statement = move |_| async move {
+        let Some(id) = selected_company() else {
+            return;
+        };
+        match get_fleet_statement(id, month(), token()).await {
+            Ok(st) => statement.set(Some(st)),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let company_list = match &*companies.read() {
+        Some(Ok(list)) => list.clone(),
+        _ => Vec::new(),
+    };
+    let current = company_list.iter().find(|c| Some(c.id) == selected_company()).cloned();
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Автопарки" }
+                p { class: "subtitle",
+                    "Корпоративні рахунки: водії купують пальне зі своїх логінів, оплата — з балансу компанії в межах лімітів водія."
+                }
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+
+                div { style: "display: flex; gap: 10px; align-items: center; margin-bottom: 1rem;",
+                    input {
+                        class: "modern-input",
+                        placeholder: "Назва компанії",
+                        value: "{company_name}",
+                        oninput: move |e| company_name.set(e.value()),
+                    }
+                    button { onclick: handle_create, "Створити компанію" }
+                }
+
+                match &*companies.read() {
+                    Some(Ok(list)) if list.is_empty() => rsx! {
+                        p { class: "subtitle", "Компаній немає" }
+                    },
+                    Some(Ok(list)) => rsx! {
+                        table { class: "data-table",
+                            thead {
+                                tr {
+                                    th { "Компанія" }
+                                    th { "Баланс" }
+                                    th { "Створено" }
+                                    th { "" }
+                                }
+                            }
+                            tbody {
+                                for company in list.clone() {
+                                    tr { key: "{company.id}",
+                                        style: if Some(company.id) == selected_company() { "background-color: #eff6ff;" } else { "" },
+                                        td { "{company.name}" }
+                                        td { {utils::format_money(company.balance)} }
+                                        td { {utils::format_timestamp(company.created_at)} }
+                                        td {
+                                            button {
+                                                onclick: move |_| {
+                                                    selected_company.set(Some(company.id));
+                                                    statement.set(None);
+                                                },
+                                                "Відкрити"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+
+                if let Some(company) = current {
+                    h2 { style: "margin-top: 2rem;", "{company.name} — {utils::format_money(company.balance)}" }
+
+                    div { style: "display: flex; gap: 10px; align-items: center; margin-bottom: 1rem;",
+                        input {
+                            class: "modern-input",
+                            style: "width: 140px;",
+                            placeholder: "Сума, грн",
+                            value: "{top_up_amount}",
+                            oninput: move |e| top_up_amount.set(e.value()),
+                        }
+                        input {
+                            class: "modern-input",
+                            placeholder: "Примітка (рахунок №...)",
+                            value: "{top_up_note}",
+                            oninput: move |e| top_up_note.set(e.value()),
+                        }
+                        button { onclick: handle_top_up, "Поповнити" }
+                    }
+
+                    h3 { "Водії" }
+                    match &*drivers.read() {
+                        Some(Ok(list)) if list.is_empty() => rsx! {
+                            p { class: "subtitle", "Водіїв ще немає" }
+                        },
+                        Some(Ok(list)) => rsx! {
+                            div { style: "overflow-x: auto;",
+                                table { class: "data-table",
+                                    thead {
+                                        tr {
+                                            th { "Водій" }
+                                            th { "Сьогодні" }
+                                            th { "Місяць" }
+                                            th { "Ліміт доба / місяць, грн" }
+                                            th { "Пальне" }
+                                            th { "Активний" }
+                                            th { "" }
+                                        }
+                                    }
+                                    tbody {
+                                        for item in list.clone() {
+                                            FleetDriverRow { key: "{item.driver.id}", item, drivers }
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                        Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                        None => rsx! { div { class: "loading", "Завантаження" } }
+                    }
+
+                    div { style: "display: flex; gap: 10px; flex-wrap: wrap; align-items: center; margin: 1rem 0;",
+                        input {
+                            class: "modern-input",
+                            placeholder: "Логін водія",
+                            value: "{driver_login}",
+                            oninput: move |e| driver_login.set(e.value()),
+                        }
+                        input {
+                            class: "modern-input",
+                            style: "width: 150px;",
+                            placeholder: "№ картки (необов'язково)",
+                            value: "{card_number}",
+                            oninput: move |e| card_number.set(e.value()),
+                        }
+                        input {
+                            class: "modern-input",
+                            style: "width: 110px;",
+                            placeholder: "Доба, грн",
+                            value: "{daily_limit}",
+                            oninput: move |e| daily_limit.set(e.value()),
+                        }
+                        input {
+                            class: "modern-input",
+                            style: "width: 110px;",
+                            placeholder: "Місяць, грн",
+                            value: "{monthly_limit}",
+                            oninput: move |e| monthly_limit.set(e.value()),
+                        }
+                        FleetFuelTypes { selected: new_driver_types }
+                        button { onclick: handle_add_driver, "Додати водія" }
+                    }
+
+                    h3 { "Виписка" }
+                    div { style: "display: flex; gap: 10px; align-items: center; margin-bottom: 1rem;",
+                        input {
+                            class: "modern-input",
+                            r#type: "month",
+                            value: "{month}",
+                            oninput: move |e| month.set(e.value()),
+                        }
+                        button { onclick: handle_statement, "Сформувати" }
+                    }
+                    if let Some(st) = statement() {
+                        div { style: "display: flex; gap: 2rem; flex-wrap: wrap; margin-bottom: 1rem;",
+                            div { "Залишок на початок: " b { {utils::format_money(st.opening_balance)} } }
+                            div { "Поповнення: " b { {utils::format_money(st.top_ups)} } }
+                            div { "Покупки: " b { {utils::format_money(st.purchases)} } }
+                            div { "Залишок на кінець: " b { {utils::format_money(st.closing_balance)} } }
+                        }
+                        if !st.by_driver.is_empty() {
+                            table { class: "data-table", style: "margin-bottom: 1rem;",
+                                thead {
+                                    tr {
+                                        th { "Водій" }
+                                        th { "Покупки за {st.month}" }
+                                    }
+                                }
+                                tbody {
+                                    for (login, total) in st.by_driver.clone() {
+                                        tr { key: "{login}",
+                                            td { "{login}" }
+                                            td { {utils::format_money(total)} }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        if st.lines.is_empty() {
+                            p { class: "subtitle", "Операцій за {st.month} немає" }
+                        } else {
+                            table { class: "data-table",
+                                thead {
+                                    tr {
+                                        th { "Час" }
+                                        th { "Операція" }
+                                        th { "Водій" }
+                                        th { "Пальне" }
+                                        th { "Сума" }
+                                    }
+                                }
+                                tbody {
+                                    for (i, line) in st.lines.iter().cloned().enumerate() {
+                                        tr { key: "{i}",
+                                            td { {utils::format_timestamp(line.created_at)} }
+                                            td {
+                                                if line.kind == "topup" {
+                                                    {format!("Поповнення{}", line.note.map(|n| format!(": {}", n)).unwrap_or_default())}
+                                                } else {
+                                                    "Покупка"
+                                                }
+                                            }
+                                            td { {line.driver_login.unwrap_or_default()} }
+                                            td {
+                                                if let (Some(name), Some(volume)) = (line.fuel_name, line.volume) {
+                                                    "{name}, {volume}"
+                                                }
+                                            }
+                                            td {
+                                                style: if line.amount < 0 { "color: #b91c1c;" } else { "color: #047857;" },
+                                                {utils::format_money(line.amount)}
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
         }
+    }
+}
+
+#[component]
+fn FleetDriverRow(
+    item: models::FleetDriverView,
+    drivers: Resource<Result<Vec<models::FleetDriverView>, ServerFnError>>,
+) -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let mut daily = use_signal(|| limit_input(item.driver.daily_limit));
+    let mut monthly = use_signal(|| limit_input(item.driver.monthly_limit));
+    let types = use_signal(|| fuel_types_of(item.driver.allowed_fuel_types.as_deref()));
+    let mut active = use_signal(|| item.driver.active);
+    let mut msg = use_signal(|| "".to_string());
+    let driver_id = item.driver.id;
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
     };
 
-    let is_selected = cart().contains_key(&item.id);
-    let handle_toggle_cart = move |_| {
-        if cart().contains_key(&item.id) {
-            cart.write().remove(&item.id);
-        } else {
-            cart.write().insert(item.id, amount());
+    let handle_save = move |_| async move {
+        let (d, m) = match (parse_limit(&daily()), parse_limit(&monthly())) {
+            (Ok(d), Ok(m)) => (d, m),
+            (Err(e), _) | (_, Err(e)) => {
+                msg.set(e);
+                return;
+            }
+        };
+        match update_fleet_driver(driver_id, d, m, types(), active(), token()).await {
+            Ok(_) => {
+                msg.set("Збережено".to_string());
+                drivers.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_remove = move |_| async move {
+        match remove_fleet_driver(driver_id, token()).await {
+            Ok(_) => drivers.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
         }
     };
 
     rsx! {
-        div { class: "fuel-item",
-            div { class: "fuel-header",
-                div { class: "fuel-icon", "{icon}" }
-                div {
-                    span { class: "fuel-name", "{item.name}" }
-                    div {  }
-                    span { class: "fuel-price", "{fmt_money(price_val)} грн/{unit}" }
+        tr {
+            style: if item.driver.active { "" } else { "color: #9ca3af;" },
+            td {
+                "{item.login}"
+                if let Some(card) = item.driver.card_number.clone() {
+                    div { style: "font-size: 0.8rem; color: #6b7280;", "картка {card}" }
                 }
-                if user_state().is_some() && is_available {
-                    div { style: "margin-left: auto;",
-                        input {
-                            type: "checkbox",
-                            checked: "{is_selected}",
-                            onchange: handle_toggle_cart,
-                            style: "transform: scale(1.5); cursor: pointer;"
-                        }
+            }
+            td { {utils::format_money(item.spent_today)} }
+            td { {utils::format_money(item.spent_month)} }
+            td {
+                div { style: "display: flex; gap: 5px;",
+                    input {
+                        class: "price-input",
+                        placeholder: "без ліміту",
+                        value: "{daily}",
+                        oninput: move |e| daily.set(e.value())
+                    }
+                    input {
+                        class: "price-input",
+                        placeholder: "без ліміту",
+                        value: "{monthly}",
+                        oninput: move |e| monthly.set(e.value())
                     }
                 }
             }
+            td {
+                div { style: "display: flex; gap: 8px; flex-wrap: wrap;",
+                    FleetFuelTypes { selected: types }
+                }
+            }
+            td {
+                input {
+                    r#type: "checkbox",
+                    checked: active(),
+                    onchange: move |e| active.set(e.checked())
+                }
+            }
+            td {
+                div { style: "display: flex; gap: 5px;",
+                    button { onclick: handle_save, "Зберегти" }
+                    button { onclick: handle_remove, "Видалити" }
+                }
+                if !msg().is_empty() {
+                    div { style: "font-size: 0.8rem; color: #6b7280;", "{msg}" }
+                }
+            }
+        }
+    }
+}
 
-            if !is_electric {
-                div {
-                    style: "padding: 10px 20px 0 20px; display: flex; flex-direction: column; gap: 5px;",
-                    div {
-                        style: "width: 100%; height: 10px; background-color: #e5e7eb; border-radius: 5px; overflow: hidden;",
-                        div { style: "height: 100%; width: {percentage}%; background-color: {bar_color}; transition: width 0.5s ease;" }
+#[component]
+fn LoyaltyTiersPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let mut msg = use_signal(|| "".to_string());
+    let mut name = use_signal(String::new);
+    let mut min_spent = use_signal(String::new);
+    let mut per_litre = use_signal(|| "0".to_string());
+    let mut per_100_uah = use_signal(|| "1".to_string());
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+    let mut tiers = use_resource(move || async move { get_loyalty_tiers(token()).await });
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let handle_add = move |_| async move {
+        let (Ok(Some(min)), Ok(litre), Ok(uah)) = (
+            parse_limit(&min_spent()),
+            per_litre().trim().parse::<i32>(),
+            per_100_uah().trim().parse::<i32>(),
+        ) else {
+            msg.set("Вкажіть поріг у гривнях і бали цілими числами".to_string());
+            return;
+        };
+        match save_loyalty_tier(None, name(), min, litre, uah, token()).await {
+            Ok(_) => {
+                msg.set(format!("Рівень {} додано", name()));
+                name.set(String::new());
+                min_spent.set(String::new());
+                tiers.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Програма лояльності" }
+                p { class: "subtitle",
+                    "Рівень клієнта — за сумою покупок за останні 365 днів. Бали нараховуються за літр і за кожні сплачені 100 грн, 1 бал = 1 грн, діють рік."
+                }
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+
+                match &*tiers.read() {
+                    Some(Ok(list)) => rsx! {
+                        table { class: "data-table",
+                            thead {
+                                tr {
+                                    th { "Рівень" }
+                                    th { "Від, грн за рік" }
+                                    th { "Балів за літр" }
+                                    th { "Балів за 100 грн" }
+                                    th { "" }
+                                }
+                            }
+                            tbody {
+                                for tier in list.clone() {
+                                    LoyaltyTierRow { key: "{tier.id}", tier, tiers }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+
+                div { style: "display: flex; gap: 10px; align-items: center; margin-top: 1rem;",
+                    input {
+                        class: "modern-input",
+                        placeholder: "Назва рівня",
+                        value: "{name}",
+                        oninput: move |e| name.set(e.value()),
                     }
+                    input {
+                        class: "modern-input",
+                        style: "width: 140px;",
+                        placeholder: "Від, грн",
+                        value: "{min_spent}",
+                        oninput: move |e| min_spent.set(e.value()),
+                    }
+                    input {
+                        class: "modern-input",
+                        style: "width: 100px;",
+                        title: "Балів за літр",
+                        value: "{per_litre}",
+                        oninput: move |e| per_litre.set(e.value()),
+                    }
+                    input {
+                        class: "modern-input",
+                        style: "width: 100px;",
+                        title: "Балів за 100 грн",
+                        value: "{per_100_uah}",
+                        oninput: move |e| per_100_uah.set(e.value()),
+                    }
+                    button { onclick: handle_add, "Додати рівень" }
                 }
-            } else {
-                div {
-                    style: "padding: 10px 20px 0 20px; font-size: 0.9rem; font-weight: bold;",
-                    if is_available {
-                        span { style: "color: #059669;", "🟢 Зарядка доступна" }
-                    } else {
-                        span { style: "color: #dc2626;", "🔴 Тимчасово недоступно" }
+            }
+        }
+    }
+}
+
+#[component]
+fn LoyaltyTierRow(
+    tier: models::LoyaltyTier,
+    tiers: Resource<Result<Vec<models::LoyaltyTier>, ServerFnError>>,
+) -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let mut name = use_signal(|| tier.name.clone());
+    let mut min_spent = use_signal(|| limit_input(Some(tier.min_spent)));
+    let mut per_litre = use_signal(|| tier.points_per_litre.to_string());
+    let mut per_100_uah = use_signal(|| tier.points_per_100_uah.to_string());
+    let mut msg = use_signal(|| "".to_string());
+    let tier_id = tier.id;
+    let is_base = tier.min_spent == 0;
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+
+    let handle_save = move |_| async move {
+        let (Ok(Some(min)), Ok(litre), Ok(uah)) = (
+            parse_limit(&min_spent()),
+            per_litre().trim().parse::<i32>(),
+            per_100_uah().trim().parse::<i32>(),
+        ) else {
+            msg.set("Невірні значення".to_string());
+            return;
+        };
+        match save_loyalty_tier(Some(tier_id), name(), min, litre, uah, token()).await {
+            Ok(_) => {
+                msg.set("Збережено".to_string());
+                tiers.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_delete = move |_| async move {
+        match delete_loyalty_tier(tier_id, token()).await {
+            Ok(_) => tiers.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    rsx! {
+        tr {
+            td {
+                input { class: "price-input", value: "{name}", oninput: move |e| name.set(e.value()) }
+            }
+            td {
+                input {
+                    class: "price-input",
+                    disabled: is_base,
+                    value: "{min_spent}",
+                    oninput: move |e| min_spent.set(e.value())
+                }
+            }
+            td {
+                input { class: "price-input", value: "{per_litre}", oninput: move |e| per_litre.set(e.value()) }
+            }
+            td {
+                input { class: "price-input", value: "{per_100_uah}", oninput: move |e| per_100_uah.set(e.value()) }
+            }
+            td {
+                div { style: "display: flex; gap: 5px;",
+                    button { onclick: handle_save, "Зберегти" }
+                    if !is_base {
+                        button { onclick: handle_delete, "Видалити" }
                     }
                 }
+                if !msg().is_empty() {
+                    div { style: "font-size: 0.8rem; color: #6b7280;", "{msg}" }
+                }
             }
+        }
+    }
+}
 
-            div { class: "fuel-controls",
-                div { class: "slider-container",
-                    label { "Купити: {amount} {unit}" }
+// Умови акції одним рядком для таблиці
+fn promotion_terms(view: &models::PromotionView) -> String {
+    let p = &view.promotion;
+    let mut parts = vec![match p.kind.as_str() {
+        "percent" => format!("−{}%", p.value),
+        _ => format!("−{} за літр", utils::format_money(p.value as i64)),
+    }];
+    parts.push(view.fuel_name.clone().unwrap_or_else(|| "усе пальне".to_string()));
+    if let Some(min) = p.min_quantity {
+        parts.push(format!("від {} л", min));
+    }
+    if let (Some(start), Some(end)) = (p.happy_hour_start, p.happy_hour_end) {
+        parts.push(format!("{}–{}", utils::format_time_of_day(start), utils::format_time_of_day(end)));
+    }
+    if let Some(limit) = p.per_customer_limit {
+        parts.push(format!("{} раз(и) на клієнта", limit));
+    }
+    parts.join(", ")
+}
+
+#[component]
+fn PromotionsPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let mut msg = use_signal(|| "".to_string());
+    let mut name = use_signal(String::new);
+    let mut code = use_signal(String::new);
+    let mut kind = use_signal(|| "percent".to_string());
+    let mut value = use_signal(String::new);
+    let mut fuel_id = use_signal(String::new);
+    let mut min_quantity = use_signal(String::new);
+    let mut happy_start = use_signal(String::new);
+    let mut happy_end = use_signal(String::new);
+    let mut valid_from = use_signal(String::new);
+    let mut valid_until = use_signal(String::new);
+    let mut limit = use_signal(String::new);
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+    let mut promos = use_resource(move || async move { get_promotions(token()).await });
+    let fuels = use_resource(get_fuels);
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let handle_create = move |_| async move {
+        let optional_number = |raw: String| match raw.trim() {
+            "" => Ok(None),
+            v => v.parse::<i32>().map(Some),
+        };
+        // Відсотки — цілим числом, за літр — у гривнях
+        let parsed_value = match kind().as_str() {
+            "percent" => value().trim().parse::<i32>().ok(),
+            _ => parse_limit(&value()).ok().flatten().and_then(|v| i32::try_from(v).ok()),
+        };
+        let (Some(parsed_value), Ok(min_quantity_value), Ok(limit_value)) =
+            (parsed_value, optional_number(min_quantity()), optional_number(limit()))
+        else {
+            msg.set("Вкажіть знижку, мінімальний обсяг і ліміт числами".to_string());
+            return;
+        };
+        let happy_hour = match (happy_start().trim(), happy_end().trim()) {
+            ("", "") => None,
+            (start, end) => Some((start.to_string(), end.to_string())),
+        };
+        let form = models::PromotionForm {
+            name: name(),
+            code: Some(code()),
+            kind: kind(),
+            value: parsed_value,
+            fuel_id: fuel_id().parse().ok(),
+            min_quantity: min_quantity_value,
+            happy_hour,
+            valid_from: Some(valid_from()),
+            valid_until: Some(valid_until()),
+            per_customer_limit: limit_value,
+        };
+        match create_promotion(form, token()).await {
+            Ok(_) => {
+                msg.set(format!("Акцію {} створено", name()));
+                for mut field in [name, code, value, min_quantity, happy_start, happy_end, valid_from, valid_until, limit] {
+                    field.set(String::new());
+                }
+                promos.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Акції та купони" }
+                p { class: "subtitle",
+                    "Акція без купона діє автоматично. До покупки застосовується одна акція — найвигідніша для клієнта; бали лояльності списуються з суми після знижки."
+                }
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+
+                match &*promos.read() {
+                    Some(Ok(list)) if list.is_empty() => rsx! { p { "Акцій ще немає" } },
+                    Some(Ok(list)) => rsx! {
+                        table { class: "data-table",
+                            thead {
+                                tr {
+                                    th { "Акція" }
+                                    th { "Купон" }
+                                    th { "Умови" }
+                                    th { "Діє" }
+                                    th { "Використань" }
+                                    th { "Знижок надано" }
+                                    th { "" }
+                                }
+                            }
+                            tbody {
+                                for view in list.clone() {
+                                    PromotionRow { key: "{view.promotion.id}", view, promos }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+
+                h3 { style: "margin-top: 1.5rem;", "Нова акція" }
+                div { style: "display: flex; gap: 10px; align-items: center; flex-wrap: wrap;",
                     input {
-                        type: "range",
-                        min: "1",
-                        max: if is_electric { "100" } else { "{item.stored}" },
-                        value: "{amount}",
-                        oninput: move |e| {
-                            let val = e.value().parse().unwrap_or(1);
-                            amount.set(val);
-                            if cart().contains_key(&item.id) {
-                                cart.write().insert(item.id, val);
+                        class: "modern-input",
+                        placeholder: "Назва",
+                        value: "{name}",
+                        oninput: move |e| name.set(e.value()),
+                    }
+                    input {
+                        class: "modern-input",
+                        style: "width: 140px;",
+                        placeholder: "Купон (необов'язково)",
+                        value: "{code}",
+                        oninput: move |e| code.set(e.value()),
+                    }
+                    select {
+                        class: "modern-input",
+                        value: "{kind}",
+                        onchange: move |e| kind.set(e.value()),
+                        option { value: "percent", "Відсоток" }
+                        option { value: "per_litre", "Грн за літр" }
+                    }
+                    input {
+                        class: "modern-input",
+                        style: "width: 100px;",
+                        placeholder: if kind() == "percent" { "%" } else { "грн/л" },
+                        value: "{value}",
+                        oninput: move |e| value.set(e.value()),
+                    }
+                    select {
+                        class: "modern-input",
+                        value: "{fuel_id}",
+                        onchange: move |e| fuel_id.set(e.value()),
+                        option { value: "", "Усе пальне" }
+                        if let Some(Ok(list)) = &*fuels.read() {
+                            for f in list.clone() {
+                                option { value: "{f.id}", "{f.name}" }
                             }
                         }
                     }
                 }
-
-                div { class: "total-price",
-                    "До сплати: "
-                    span { class: "price-tag", "{fmt_money(total_cost)} грн" }
+                div { style: "display: flex; gap: 10px; align-items: center; flex-wrap: wrap; margin-top: 10px;",
+                    input {
+                        class: "modern-input",
+                        style: "width: 110px;",
+                        placeholder: "Від, л",
+                        value: "{min_quantity}",
+                        oninput: move |e| min_quantity.set(e.value()),
+                    }
+                    span { "Happy hour:" }
+                    input {
+                        class: "modern-input",
+                        r#type: "time",
+                        value: "{happy_start}",
+                        oninput: move |e| happy_start.set(e.value()),
+                    }
+                    input {
+                        class: "modern-input",
+                        r#type: "time",
+                        value: "{happy_end}",
+                        oninput: move |e| happy_end.set(e.value()),
+                    }
+                    span { "Діє з" }
+                    input {
+                        class: "modern-input",
+                        r#type: "date",
+                        value: "{valid_from}",
+                        oninput: move |e| valid_from.set(e.value()),
+                    }
+                    span { "по" }
+                    input {
+                        class: "modern-input",
+                        r#type: "date",
+                        value: "{valid_until}",
+                        oninput: move |e| valid_until.set(e.value()),
+                    }
+                    input {
+                        class: "modern-input",
+                        style: "width: 150px;",
+                        placeholder: "Разів на клієнта",
+                        value: "{limit}",
+                        oninput: move |e| limit.set(e.value()),
+                    }
+                    button { onclick: handle_create, "Створити акцію" }
                 }
+            }
+        }
+    }
+}
 
-                if !error_msg().is_empty() {
-                     div { class: "mini-error", "{error_msg}" }
-                }
+#[component]
+fn PromotionRow(
+    view: models::PromotionView,
+    promos: Resource<Result<Vec<models::PromotionView>, ServerFnError>>,
+) -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let mut msg = use_signal(|| "".to_string());
+    let promotion_id = view.promotion.id;
+    let active = view.promotion.active;
+    let terms = promotion_terms(&view);
+    let period = match (view.promotion.valid_from, view.promotion.valid_until) {
+        (None, None) => "безстроково".to_string(),
+        (from, until) => format!(
+            "{} — {}",
+            from.map_or("…".to_string(), utils::format_timestamp),
+            until.map_or("…".to_string(), utils::format_timestamp)
+        ),
+    };
 
-                button {
-                    class: "buy-button",
-                    disabled: !is_available,
-                    style: if !is_available { "background-color: gray; cursor: not-allowed;" } else { "" },
-                    onclick: handle_buy,
-                    if !is_available { "Недоступно" } else { "Купити" }
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+
+    let handle_toggle = move |_| async move {
+        match set_promotion_active(promotion_id, !active, token()).await {
+            Ok(_) => promos.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_delete = move |_| async move {
+        match delete_promotion(promotion_id, token()).await {
+            Ok(_) => promos.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    rsx! {
+        tr { style: if active { "" } else { "opacity: 0.5;" },
+            td { "{view.promotion.name}" }
+            td { {view.promotion.code.clone().unwrap_or_else(|| "—".to_string())} }
+            td { "{terms}" }
+            td { "{period}" }
+            td { "{view.uses}" }
+            td { {utils::format_money(view.total_discount)} }
+            td {
+                div { style: "display: flex; gap: 5px;",
+                    button { onclick: handle_toggle, if active { "Вимкнути" } else { "Увімкнути" } }
+                    button { onclick: handle_delete, "Видалити" }
+                }
+                if !msg().is_empty() {
+                    div { style: "font-size: 0.8rem; color: #6b7280;", "{msg}" }
                 }
             }
         }
     }
 }
 
+// Дозволи API-ключів для форми (сервер перевіряє за api_keys::SCOPES)
+const API_KEY_SCOPES: [(&str, &str); 3] = [
+    ("prices:read", "Ціни"),
+    ("stock:read", "Залишки"),
+    ("purchases:create", "Покупки з рахунку автопарку"),
+];
+
 #[component]
-fn About() -> Element {
-    const ABOUT_HTML: &str = include_str!("../assets/about.html");
-    rsx! {
-        div {
-            dangerous_inner_html: "{ABOUT_HTML}"
-        }
+fn ApiKeysPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let mut msg = use_signal(|| "".to_string());
+    // Повний ключ показується лише одразу після створення чи ротації
+    let mut issued = use_signal(|| None::<String>);
+    let mut name = use_signal(String::new);
+    let mut customer_login = use_signal(String::new);
+    let mut rate_limit = use_signal(|| "60".to_string());
+    let mut selected = use_signal(|| vec!["prices:read".to_string()]);
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+    let mut keys = use_resource(move || async move { get_api_keys(token()).await });
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
     }
-}
 
-#[component]
-fn PricesPage() -> Element {
-    let prices = use_resource(|| fetch_fuel_prices());
+    let handle_create = move |_| async move {
+        let Ok(limit) = rate_limit().trim().parse::<i32>() else {
+            msg.set("Ліміт має бути цілим числом".to_string());
+            return;
+        };
+        match create_api_key(name(), selected(), customer_login(), limit, token()).await {
+            Ok(key) => {
+                msg.set(format!("Ключ «{}» створено. Збережіть його зараз — повторно він не показується.", name()));
+                issued.set(Some(key));
+                name.set(String::new());
+                customer_login.set(String::new());
+                keys.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_rotate = move |id: i32| async move {
+        match rotate_api_key(id, token()).await {
+            Ok(key) => {
+                msg.set("Новий секрет видано. Попередній діє ще 24 години.".to_string());
+                issued.set(Some(key));
+                keys.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_revoke = move |id: i32| async move {
+        match revoke_api_key(id, token()).await {
+            Ok(_) => {
+                msg.set(format!("Ключ #{} відкликано", id));
+                keys.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let scopes_label = |scopes: &str| {
+        scopes
+            .split(',')
+            .map(|s| API_KEY_SCOPES.iter().find(|(k, _)| *k == s).map(|(_, l)| *l).unwrap_or(s))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
 
     rsx! {
         div { class: "page-container",
             div { class: "content-card",
-                h1 { "Середні ціни на пальне в Україні" }
-                p { class: "subtitle", style: "margin-bottom: 2rem;",
-                    "Дані оновлюються згідно з даними Мінфін"
+                h1 { "API-ключі" }
+                p { class: "subtitle",
+                    "Ключі для скриптів та інтеграцій автопарків. Передаються в заголовку X-Api-Key або Authorization: Bearer до /api/v1."
+                }
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+                if let Some(key) = issued() {
+                    div { style: "margin-bottom: 1rem;",
+                        code { style: "user-select: all; word-break: break-all;", "{key}" }
+                    }
                 }
 
-                match &*prices.read() {
+                div { style: "display: flex; gap: 10px; flex-wrap: wrap; align-items: center; margin-bottom: 1rem;",
+                    input {
+                        class: "modern-input",
+                        placeholder: "Назва (напр. ERP автопарку)",
+                        value: "{name}",
+                        oninput: move |e| name.set(e.value()),
+                    }
+                    input {
+                        class: "modern-input",
+                        placeholder: "Логін рахунку автопарку",
+                        value: "{customer_login}",
+                        oninput: move |e| customer_login.set(e.value()),
+                    }
+                    input {
+                        class: "modern-input",
+                        style: "width: 90px;",
+                        r#type: "number",
+                        title: "Запитів на хвилину",
+                        value: "{rate_limit}",
+                        oninput: move |e| rate_limit.set(e.value()),
+                    }
+                    for (scope, label) in API_KEY_SCOPES {
+                        label { key: "{scope}", style: "display: flex; gap: 4px; align-items: center;",
+                            input {
+                                r#type: "checkbox",
+                                checked: selected().iter().any(|s| s == scope),
+                                onchange: move |e| {
+                                    let mut list = selected.write();
+                                    list.retain(|x| x != scope);
+                                    if e.checked() {
+                                        list.push(scope.to_string());
+                                    }
+                                }
+                            }
+                            "{label}"
+                        }
+                    }
+                    button { onclick: handle_create, "Створити" }
+                }
+
+                match &*keys.read() {
+                    Some(Ok(list)) if list.is_empty() => rsx! {
+                        p { class: "subtitle", "Ключів немає" }
+                    },
                     Some(Ok(list)) => rsx! {
                         div { style: "overflow-x: auto;",
-                            table { style: "width: 100%; border-collapse: collapse;",
+                            table { class: "data-table",
                                 thead {
-                                    tr { style: "background-color: #f3f4f6;",
-                                        th { style: "padding: 12px; text-align: left; border-bottom: 2px solid #e5e7eb;", "Тип пального" }
-                                        th { style: "padding: 12px; text-align: right; border-bottom: 2px solid #e5e7eb;", "Середня ціна" }
-                                        th { style: "padding: 12px; text-align: right; border-bottom: 2px solid #e5e7eb;", "Мін" }
-                                        th { style: "padding: 12px; text-align: right; border-bottom: 2px solid #e5e7eb;", "Макс" }
+                                    tr {
+                                        th { "#" }
+                                        th { "Назва" }
+                                        th { "Префікс" }
+                                        th { "Дозволи" }
+                                        th { "Автопарк" }
+                                        th { "Ліміт/хв" }
+                                        th { "Створено" }
+                                        th { "Використано" }
+                                        th { "" }
                                     }
                                 }
                                 tbody {
-                                    for item in list {
-                                        tr { key: "{item.name}", style: "border-bottom: 1px solid #e5e7eb;",
-                                            td { style: "padding: 12px; font-weight: 600;", "{item.name}" }
-                                            td { style: "padding: 12px; text-align: right; color: #2563eb; font-weight: bold;",
-                                                "{item.average:.2} грн"
+                                    for item in list.clone() {
+                                        tr { key: "{item.key.id}",
+                                            style: if item.key.revoked_at.is_some() { "color: #9ca3af;" } else { "" },
+                                            td { "{item.key.id}" }
+                                            td { "{item.key.name}" }
+                                            td { code { "gs_{item.key.prefix}_…" } }
+                                            td { {scopes_label(&item.key.scopes)} }
+                                            td { {item.customer_login.clone().unwrap_or_else(|| "—".to_string())} }
+                                            td { "{item.key.rate_limit}" }
+                                            td {
+                                                {utils::format_timestamp(item.key.created_at)}
+                                                div { style: "font-size: 0.8rem; color: #6b7280;", "{item.key.created_by}" }
                                             }
-                                            td { style: "padding: 12px; text-align: right; color: #6b7280;",
-                                                "{item.min:.2}"
+                                            td {
+                                                {item.key.last_used_at.map(utils::format_timestamp).unwrap_or_else(|| "ще ні".to_string())}
                                             }
-                                            td { style: "padding: 12px; text-align: right; color: #6b7280;",
-                                                "{item.max:.2}"
+                                            td {
+                                                if let Some(revoked) = item.key.revoked_at {
+                                                    {format!("Відкликано {}", utils::format_timestamp(revoked))}
+                                                } else {
+                                                    div { style: "display: flex; gap: 5px;",
+                                                        button { onclick: move |_| handle_rotate(item.key.id), "Ротація" }
+                                                        button { onclick: move |_| handle_revoke(item.key.id), "Відкликати" }
+                                                    }
+                                                    if let Some(until) = item.key.previous_valid_until {
+                                                        div { style: "font-size: 0.8rem; color: #6b7280;",
+                                                            {format!("Старий секрет діє до {}", utils::format_timestamp(until))}
+                                                        }
+                                                    }
+                                                }
                                             }
                                         }
                                     }
@@ -1118,16 +7725,8 @@ fn PricesPage() -> Element {
                             }
                         }
                     },
-                    Some(Err(e)) => rsx! {
-                        div { class: "error-message",
-                            "Помилка завантаження даних: {e}"
-                        }
-                    },
-                    None => rsx! {
-                        div { class: "loading",
-                            "Завантаження цін"
-                        }
-                    }
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
                 }
             }
         }
@@ -1135,17 +7734,64 @@ fn PricesPage() -> Element {
 }
 
 #[component]
-fn Footer() -> Element {
+fn PricingPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let fuels = use_resource(get_fuels);
+    let market = use_resource(fetch_fuel_prices);
+    let mut fuel_id = use_signal(|| None::<i32>);
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    // За замовчуванням — перше пальне зі списку
+    let selected = fuel_id().or_else(|| match &*fuels.read() {
+        Some(Ok(list)) => list.first().map(|f| f.id),
+        _ => None,
+    });
+    let selected_name = match &*fuels.read() {
+        Some(Ok(list)) => list.iter().find(|f| Some(f.id) == selected).map(|f| f.name.clone()),
+        _ => None,
+    }
+    .unwrap_or_default();
+
     rsx! {
-        footer {
-            style: "background-color: #1f2937; color: white; padding: 2rem; margin-top: auto; width: 100%;",
-            div { style: "max-width: 1200px; margin: 0 auto; display: flex; justify-content: space-between; align-items: center; flex-wrap: wrap; gap: 20px;",
-                div {
-                    h3 { style: "margin: 0;", "GasStation" }
-                    p { style: "font-size: 0.9rem; color: #9ca3af; margin: 5px 0 0 0;", "© 2025 Всі права захищені" }
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Розклад цін" }
+                match &*fuels.read() {
+                    Some(Ok(list)) => rsx! {
+                        div { style: "display: flex; gap: 10px; align-items: center; margin-bottom: 1rem;",
+                            label { "Пальне:" }
+                            select {
+                                class: "modern-input",
+                                style: "width: auto;",
+                                onchange: move |e| fuel_id.set(e.value().parse().ok()),
+                                for f in list {
+                                    option { key: "{f.id}", value: "{f.id}", selected: Some(f.id) == selected, "{f.name}" }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
                 }
-                div { style: "display: flex; gap: 20px;",
-                    a { href: "/about", style: "color: white; text-decoration: none;", "Про компанію" }
+                if let Some(id) = selected {
+                    PriceChart {
+                        key: "chart-{id}",
+                        fuel_id: id,
+                        range_days: 30,
+                        market_average: market_average(&market, &selected_name),
+                    }
+                    PriceTimelineView { key: "{id}", fuel_id: id }
+                }
+
+                if let Some(Ok(list)) = &*fuels.read() {
+                    MarketPricingPanel {
+                        fuels: list.iter().filter(|f| utils::market_fuel_name(&f.name).is_some()).map(|f| (f.id, f.name.clone())).collect::<Vec<_>>()
+                    }
                 }
             }
         }
@@ -1153,150 +7799,209 @@ fn Footer() -> Element {
 }
 
 #[component]
-fn Home() -> Element {
-    let mut fuels = use_resource(|| get_fuels());
-    let mut user_state = use_context::<Signal<Option<Customer>>>();
-    let mut cart = use_signal(|| std::collections::HashMap::<i32, i32>::new());
-    let mut error_msg = use_signal(|| "".to_string());
-    let nav = use_navigator();
-
-    let handle_buy_batch = move |_| async move {
-        if let Some(user) = user_state() {
-            error_msg.set("Processing...".to_string());
-            let token = user.session_token.clone().unwrap_or_default();
-            let items: Vec<(i32, i32)> = cart().into_iter().collect();
+fn MarketPricingPanel(fuels: Vec<(i32, String)>) -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let mut preview = use_signal(|| None::<Vec<models::PricePreview>>);
+    let mut msg = use_signal(|| "".to_string());
 
-            if items.is_empty() {
-                error_msg.set("Кошик пустий".to_string());
-                return;
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+    let mut rules = use_resource(move || async move { get_market_rules(token()).await });
+    let mut proposals = use_resource(move || async move { get_price_proposals(token()).await });
+
+    let handle_preview = move |_| async move {
+        msg.set("Завантаження цін ринку...".to_string());
+        match preview_market_pricing(token()).await {
+            Ok(list) => {
+                msg.set("".to_string());
+                preview.set(Some(list));
             }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
 
-            match buy_fuel_batch(user.id, items, token).await {
-                Ok(new_balance) => {
-                    let mut updated_user = user.clone();
-                    updated_user.balance = new_balance;
-                    user_state.set(Some(updated_user));
-                    cart.write().clear();
-                    error_msg.set("Успішно куплено!".to_string());
-                    fuels.restart(); // Refresh fuel data
-                }
-                Err(e) => error_msg.set(clean_error_msg(e.to_string())),
+    let handle_run = move |_| async move {
+        match run_market_pricing(token()).await {
+            Ok(n) => {
+                msg.set(format!("Нових пропозицій: {}", n));
+                proposals.restart();
             }
-        } else {
-            nav.push(Route::LoginPage {});
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
         }
     };
 
-    let total_cart_cost = {
-        let current_cart = cart();
-        if let Some(Ok(list)) = &*fuels.read() {
-            list.iter()
-                .filter_map(|f| {
-                    current_cart
-                        .get(&f.id)
-                        .map(|&amount| f.price * amount as i64)
-                })
-                .sum::<i64>()
-        } else {
-            0
+    let handle_decide = move |(id, approve): (i32, bool)| async move {
+        match decide_price_proposal(id, approve, token()).await {
+            Ok(_) => proposals.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
         }
     };
-    let fmt_money = |cents: i64| format!("{:.2}", cents as f64 / 100.0);
+
+    let fuel_name = {
+        let fuels = fuels.clone();
+        move |id: i32| fuels.iter().find(|f| f.0 == id).map(|f| f.1.clone()).unwrap_or(format!("#{}", id))
+    };
+    let fmt_opt = |v: Option<i64>| v.map(utils::format_money).unwrap_or("-".to_string());
 
     rsx! {
-        div { style: "display: flex; flex-direction: column; min-height: 100vh;",
-            div { class: "page-container", style: "flex: 1; flex-direction: column; align-items: center; gap: 2rem;",
-                div { class: "content-card",
-                    if user_state().is_none() {
-                        h1 { "Асортимент" }
-                        p { class: "subtitle", "Увійдіть, щоб купити пальне" }
-                    } else {
-                        h1 { "Оберіть пальне" }
+        h2 { style: "margin-top: 2rem;", "Ринкові правила" }
+        p { class: "subtitle",
+            "Ціна = середня по ринку + зсув, але не нижче собівартості + мінімальної націнки"
+        }
+        match &*rules.read() {
+            Some(Ok(list)) => rsx! {
+                table { class: "data-table",
+                    thead {
+                        tr {
+                            th { "Пальне" }
+                            th { "Зсув, грн" }
+                            th { "Мін. націнка, грн" }
+                            th { "Автоматично" }
+                            th { "Увімкнено" }
+                            th { "" }
+                        }
+                    }
+                    tbody {
+                        for (id, name) in fuels.clone() {
+                            MarketRuleRow {
+                                key: "{id}",
+                                fuel_id: id,
+                                fuel_name: name,
+                                rule: list.iter().find(|r| r.fuel_id == id).cloned(),
+                                on_saved: move |_| rules.restart(),
+                            }
+                        }
                     }
+                }
+            },
+            Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+            None => rsx! { div { class: "loading", "Завантаження" } }
+        }
 
-                    match &*fuels.read() {
-                        Some(Ok(list)) => rsx! {
-                            div { class: "fuel-grid",
-                                for item in list {
-                                    FuelCard {
-                                        key: "{item.id}",
-                                        item: item.clone(),
-                                        cart: cart
-                                    }
+        div { style: "display: flex; gap: 10px; margin: 1rem 0;",
+            button { onclick: handle_preview, "Попередній перегляд" }
+            button { onclick: handle_run, "Сформувати пропозиції" }
+        }
+        if !msg().is_empty() {
+            div { class: "status-msg", "{msg}" }
+        }
+
+        if let Some(list) = preview() {
+            table { class: "data-table",
+                thead {
+                    tr {
+                        th { "Пальне" }
+                        th { "Поточна" }
+                        th { "Ринок" }
+                        th { "Собівартість" }
+                        th { "Пропозиція" }
+                        th { "Зміна" }
+                    }
+                }
+                tbody {
+                    for p in list {
+                        tr { key: "{p.fuel_id}",
+                            td { "{p.fuel_name}" }
+                            td { {utils::format_money(p.current_price)} }
+                            td { {fmt_opt(p.market_average)} }
+                            td { {fmt_opt(p.cost_basis)} }
+                            td {
+                                {fmt_opt(p.proposed_price)}
+                                if p.floor_applied {
+                                    span { class: "mini-error", " (мін. націнка)" }
                                 }
                             }
-                        },
-                        Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
-                        None => rsx! { div { class: "loading", "Завантаження" } }
+                            td { {p.proposed_price.map(|n| format!("{:+.2}", (n - p.current_price) as f64 / 100.0)).unwrap_or("-".to_string())} }
+                        }
                     }
+                }
+            }
+        }
 
-                    if !cart().is_empty() && user_state().is_some() {
-                        div { class: "batch-controls", style: "margin-top: 20px; padding: 20px; border-top: 1px solid #e5e7eb; display: flex; flex-direction: column; align-items: center; gap: 10px;",
-                            div { style: "font-size: 1.2rem; font-weight: bold;",
-                                "Разом до сплати: "
-                                span { style: "color: #2563eb;", "{fmt_money(total_cart_cost)} грн" }
-                            }
-                            if !error_msg().is_empty() {
-                                div { class: "mini-error", "{error_msg}" }
-                            }
-                            button {
-                                class: "modern-button",
-                                style: "width: 100%; max-width: 300px; background-color: #2563eb;",
-                                onclick: handle_buy_batch,
-                                "Купити вибране"
+        h3 { style: "margin-top: 1.5rem;", "Пропозиції на розгляді" }
+        match &*proposals.read() {
+            Some(Ok(list)) if list.is_empty() => rsx! { p { class: "loading", "Немає пропозицій" } },
+            Some(Ok(list)) => rsx! {
+                table { class: "data-table",
+                    thead {
+                        tr {
+                            th { "Створено" }
+                            th { "Пальне" }
+                            th { "Було" }
+                            th { "Пропозиція" }
+                            th { "Ринок" }
+                            th { "" }
+                        }
+                    }
+                    tbody {
+                        for p in list.clone() {
+                            tr { key: "{p.id}",
+                                td { {utils::format_timestamp(p.created_at)} }
+                                td { {fuel_name(p.fuel_id)} }
+                                td { {utils::format_money(p.current_price)} }
+                                td { style: "font-weight: bold;", {utils::format_money(p.proposed_price)} }
+                                td { {utils::format_money(p.market_average)} }
+                                td {
+                                    button { onclick: move |_| handle_decide((p.id, true)), "Застосувати" }
+                                    button { onclick: move |_| handle_decide((p.id, false)), "Відхилити" }
+                                }
                             }
                         }
                     }
                 }
-            }
-            Footer {}
+            },
+            Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+            None => rsx! { div { class: "loading", "Завантаження" } }
         }
     }
 }
 
 #[component]
-fn LoginPage() -> Element {
-    let mut login = use_signal(|| "".to_string());
-    let mut password = use_signal(|| "".to_string());
-    let mut error_msg = use_signal(|| "".to_string());
-
-    let mut user_state = use_context::<Signal<Option<Customer>>>();
-    let mut admin_state = use_context::<Signal<Option<Admin>>>();
-    let nav = use_navigator();
-
-    let handle_login = move |_| async move {
-        error_msg.set("".to_string());
-        let l = login();
-        let p = password();
+fn MarketRuleRow(
+    fuel_id: i32,
+    fuel_name: String,
+    rule: Option<models::MarketPricingRule>,
+    on_saved: EventHandler<()>,
+) -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let fmt = |cents: i64| format!("{:.2}", cents as f64 / 100.0);
+    let mut offset = use_signal(|| rule.as_ref().map(|r| fmt(r.market_offset)).unwrap_or("-0.50".to_string()));
+    let mut floor = use_signal(|| rule.as_ref().map(|r| fmt(r.margin_floor)).unwrap_or("3.00".to_string()));
+    let mut auto_apply = use_signal(|| rule.as_ref().is_some_and(|r| r.auto_apply));
+    let mut enabled = use_signal(|| rule.as_ref().is_none_or(|r| r.enabled));
+    let mut msg = use_signal(|| "".to_string());
 
-        // Try user login first
-        match login_user(l.clone(), p.clone()).await {
-            Ok(user) => {
-                user_state.set(Some(user));
-                nav.push(Route::Home {});
-            }
-            Err(_) => {
-                // Try admin login
-                match login_admin(l, p).await {
-                    Ok(admin) => {
-                        admin_state.set(Some(admin));
-                        nav.push(Route::ManagementPage {});
-                    }
-                    Err(_) => error_msg.set("Невірний логін або пароль".to_string()),
+    let handle_save = move |_| async move {
+        let token = admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default();
+        let parse = |v: String| v.replace(',', ".").parse::<f64>().map(|x| (x * 100.0).round() as i64);
+        match (parse(offset()), parse(floor())) {
+            (Ok(o), Ok(f)) => match set_market_rule(fuel_id, o, f, auto_apply(), enabled(), token).await {
+                Ok(_) => {
+                    msg.set("Збережено".to_string());
+                    on_saved.call(());
                 }
-            }
+                Err(e) => msg.set(clean_error_msg(e.to_string())),
+            },
+            _ => msg.set("Невірний формат".to_string()),
         }
     };
-
-    rsx! {
-        div { class: "page-container",
-            div { class: "auth-card",
-                h2 { "Вхід в кабінет" }
-                div { class: "form-content",
-                    input { class: "modern-input", placeholder: "Логін", value: "{login}", oninput: move |e| login.set(e.value()) }
-                    input { class: "modern-input", type: "password", placeholder: "Пароль", value: "{password}", oninput: move |e| password.set(e.value()) }
-                    if !error_msg().is_empty() { div { class: "error-message", "{error_msg}" } }
-                    button { class: "modern-button", onclick: handle_login, "Увійти" }
+
+    rsx! {
+        tr {
+            td { "{fuel_name}" }
+            td { input { class: "price-input", style: "width: 70px;", value: "{offset}", oninput: move |e| offset.set(e.value()) } }
+            td { input { class: "price-input", style: "width: 70px;", value: "{floor}", oninput: move |e| floor.set(e.value()) } }
+            td { input { r#type: "checkbox", checked: auto_apply(), onchange: move |e| auto_apply.set(e.checked()) } }
+            td { input { r#type: "checkbox", checked: enabled(), onchange: move |e| enabled.set(e.checked()) } }
+            td {
+                button { onclick: handle_save, if rule.is_some() { "Зберегти" } else { "Додати" } }
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
                 }
             }
         }
@@ -1304,110 +8009,241 @@ fn LoginPage() -> Element {
 }
 
 #[component]
-fn RegisterPage() -> Element {
-    let mut login = use_signal(|| "".to_string());
-    let mut password = use_signal(|| "".to_string());
-    let mut error_msg = use_signal(|| "".to_string());
-    let nav = use_navigator();
+fn PriceTimelineView(fuel_id: i32) -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let mut new_price = use_signal(|| "".to_string());
+    let mut effective_at = use_signal(|| "".to_string());
+    let mut rule_start = use_signal(|| "23:00".to_string());
+    let mut rule_end = use_signal(|| "07:00".to_string());
+    let mut rule_price = use_signal(|| "".to_string());
+    let mut msg = use_signal(|| "".to_string());
 
-    let handle_register = move |_| async move {
-        error_msg.set("".to_string());
-        match register_user(login(), password()).await {
-            Ok(_) => {
-                nav.push(Route::LoginPage {});
-            }
-            Err(e) => error_msg.set(e.to_string()),
-        }
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
     };
+    let mut timeline = use_resource(move || async move { get_price_timeline(fuel_id, token()).await });
 
-    rsx! {
-        div { class: "page-container",
-            div { class: "auth-card",
-                h2 { "Створити акаунт" }
-                div { class: "form-content",
-                    input { class: "modern-input", placeholder: "Логін", value: "{login}", oninput: move |e| login.set(e.value()) }
-                    input { class: "modern-input", type: "password", placeholder: "Пароль", value: "{password}", oninput: move |e| password.set(e.value()) }
-                    if !error_msg().is_empty() { div { class: "error-message", "{error_msg}" } }
-                    button { class: "modern-button", onclick: handle_register, "Зареєструватися" }
+    let parse_cents = |v: String| v.replace(',', ".").parse::<f64>().map(|x| (x * 100.0).round() as i64);
+
+    let handle_schedule = move |_| async move {
+        match parse_cents(new_price()) {
+            Ok(cents) => match schedule_price_change(fuel_id, cents, effective_at(), token()).await {
+                Ok(_) => {
+                    msg.set("Зміну заплановано".to_string());
+                    timeline.restart();
                 }
-            }
+                Err(e) => msg.set(clean_error_msg(e.to_string())),
+            },
+            Err(_) => msg.set("Невірний формат".to_string()),
         }
-    }
-}
+    };
 
-#[cfg(test)]
-mod tests;
+    let handle_cancel = move |schedule_id: i32| async move {
+        match cancel_price_change(schedule_id, token()).await {
+            Ok(_) => timeline.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
 
-#[component]
-fn AdminDashboard() -> Element {
-    let admin_state = use_context::<Signal<Option<Admin>>>();
-    let nav = use_navigator();
+    let handle_add_rule = move |_| async move {
+        match parse_cents(rule_price()) {
+            Ok(cents) => match create_price_rule(fuel_id, rule_start(), rule_end(), cents, token()).await {
+                Ok(_) => {
+                    msg.set("Правило додано".to_string());
+                    timeline.restart();
+                }
+                Err(e) => msg.set(clean_error_msg(e.to_string())),
+            },
+            Err(_) => msg.set("Невірний формат".to_string()),
+        }
+    };
 
-    if admin_state().is_none() {
-        nav.push(Route::LoginPage {});
-        return rsx! {};
-    }
+    let handle_delete_rule = move |rule_id: i32| async move {
+        match delete_price_rule(rule_id, token()).await {
+            Ok(_) => timeline.restart(),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let source_label = |source: &str| match source {
+        "initial" => "Початкова",
+        "manual" => "Вручну",
+        "schedule" => "За розкладом",
+        "time_of_day" => "За часом доби",
+        "market_rule" => "Ринкове правило",
+        _ => "-",
+    };
 
     rsx! {
-        div { class: "page-container",
-            h1 { "Панель адміністратора" }
-            div { class: "admin-header",
-                p { "Вітаємо, {admin_state().unwrap().login}!" }
-            }
-            div { class: "content-card",
-                p { "Тут поки що пусто. Перейдіть на вкладку 'Керування'." }
-            }
+        if !msg().is_empty() {
+            div { class: "status-msg", "{msg}" }
+        }
+        match &*timeline.read() {
+            Some(Ok(t)) => rsx! {
+                p { "Діюча ціна: " b { {utils::format_money(t.current_price)} } " (базова {utils::format_money(t.base_price)})" }
+
+                h2 { style: "margin-top: 2rem;", "Ціни за часом доби" }
+                table { class: "data-table",
+                    thead {
+                        tr {
+                            th { "Інтервал" }
+                            th { "Ціна" }
+                            th { "" }
+                        }
+                    }
+                    tbody {
+                        for r in t.rules.clone() {
+                            tr { key: "{r.id}",
+                                td { "{utils::format_time_of_day(r.start_minute)} – {utils::format_time_of_day(r.end_minute)}" }
+                                td { {utils::format_money(r.price)} }
+                                td { button { onclick: move |_| handle_delete_rule(r.id), "Видалити" } }
+                            }
+                        }
+                    }
+                }
+                div { class: "admin-controls",
+                    div { class: "control-group",
+                        label { "З:" }
+                        input { class: "price-input", value: "{rule_start}", oninput: move |e| rule_start.set(e.value()) }
+                        label { "До:" }
+                        input { class: "price-input", value: "{rule_end}", oninput: move |e| rule_end.set(e.value()) }
+                        label { "Ціна, грн:" }
+                        input { class: "price-input", value: "{rule_price}", oninput: move |e| rule_price.set(e.value()) }
+                        button { onclick: handle_add_rule, "Додати правило" }
+                    }
+                }
+
+                h2 { style: "margin-top: 2rem;", "Запланувати зміну" }
+                div { class: "admin-controls",
+                    div { class: "control-group",
+                        input {
+                            class: "modern-input",
+                            r#type: "datetime-local",
+                            value: "{effective_at}",
+                            oninput: move |e| effective_at.set(e.value())
+                        }
+                        label { "Ціна, грн:" }
+                        input { class: "price-input", value: "{new_price}", oninput: move |e| new_price.set(e.value()) }
+                        button { onclick: handle_schedule, "Запланувати" }
+                    }
+                }
+
+                h2 { style: "margin-top: 2rem;", "Хронологія" }
+                table { class: "data-table",
+                    thead {
+                        tr {
+                            th { "Час" }
+                            th { "Ціна" }
+                            th { "Джерело" }
+                            th { "" }
+                        }
+                    }
+                    tbody {
+                        for u in t.upcoming.iter().rev().cloned() {
+                            tr { key: "s{u.id}", style: "color: var(--primary);",
+                                td { {utils::format_timestamp(u.effective_at)} }
+                                td { {utils::format_money(u.price)} }
+                                td { "Заплановано ({u.created_by})" }
+                                td { button { onclick: move |_| handle_cancel(u.id), "Скасувати" } }
+                            }
+                        }
+                        for h in t.history.iter() {
+                            tr { key: "h{h.id}",
+                                td { {utils::format_timestamp(h.changed_at)} }
+                                td { {utils::format_money(h.price)} }
+                                td {
+                                    {source_label(&h.source)}
+                                    if let Some(by) = &h.changed_by {
+                                        " ({by})"
+                                    }
+                                }
+                                td {}
+                            }
+                        }
+                    }
+                }
+            },
+            Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+            None => rsx! { div { class: "loading", "Завантаження" } }
         }
     }
 }
 
 #[component]
-fn ManagementPage() -> Element {
+fn TankReconciliationTable(
+    item: models::TankReconciliation,
+    report: Resource<Result<Vec<models::TankReconciliation>, ServerFnError>>,
+) -> Element {
     let admin_state = use_context::<Signal<Option<Admin>>>();
-    let nav = use_navigator();
-    let fuels = use_resource(|| get_fuels());
-    let bank_info = use_resource(|| get_bank_info());
-
-    if admin_state().is_none() {
-        nav.push(Route::LoginPage {});
-        return rsx! {};
-    }
+    let mut threshold = use_signal(|| item.threshold.to_string());
+    let mut msg = use_signal(|| "".to_string());
+    let tank_id = item.tank_id;
+
+    let handle_save = move |_| async move {
+        let token = admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default();
+        match threshold().parse::<i32>() {
+            Ok(litres) => match set_variance_threshold(tank_id, litres, token).await {
+                Ok(_) => {
+                    msg.set("Поріг збережено".to_string());
+                    report.restart();
+                }
+                Err(e) => msg.set(clean_error_msg(e.to_string())),
+            },
+            Err(_) => msg.set("Невірний формат".to_string()),
+        }
+    };
 
-    let fmt_money = |cents: i64| format!("{:.2} грн", cents as f64 / 100.0);
+    let fmt_opt = |v: Option<f64>| v.map(|x| format!("{:.0}", x)).unwrap_or("-".to_string());
 
     rsx! {
-        div { class: "page-container",
-            div { class: "management-layout",
-                // Left Column: Header and Bank Info
-                div { class: "management-sidebar",
-                    h1 { style: "text-align: left;", "Керування заправкою" }
-                    div { class: "admin-header",
-                        match &*bank_info.read() {
-                            Some(Ok(bank)) => rsx! {
-                                div { class: "bank-info",
-                                    span { "Баланс банку: " }
-                                    span { class: "money-value", "{fmt_money(bank.total)}" }
-                                }
-                            },
-                            Some(Err(e)) => rsx! { div { class: "error", "Помилка банку: {e}" } },
-                            None => rsx! { "Завантаження..." }
-                        }
+        div { style: "margin-top: 2rem;",
+            div { style: "display: flex; justify-content: space-between; align-items: center; flex-wrap: wrap; gap: 10px;",
+                h3 { "Резервуар #{item.tank_id} — {item.fuel_name}" }
+                div { style: "display: flex; gap: 5px; align-items: center;",
+                    label { "Поріг, л:" }
+                    input {
+                        class: "price-input",
+                        value: "{threshold}",
+                        oninput: move |e| threshold.set(e.value())
                     }
+                    button { onclick: handle_save, "Зберегти" }
                 }
-
-                // Right Column: Fuel Management
-                div { class: "content-card management-content",
-                    h2 { "Керування пальним" }
-                    match &*fuels.read() {
-                        Some(Ok(list)) => rsx! {
-                            div { class: "fuel-grid",
-                                for item in list {
-                                    AdminFuelItem { key: "{item.id}", item: item.clone(), bank_info: bank_info, fuels: fuels }
-                                }
+            }
+            if !msg().is_empty() {
+                div { class: "status-msg", "{msg}" }
+            }
+            div { style: "overflow-x: auto;",
+                table { class: "data-table",
+                    thead {
+                        tr {
+                            th { "День" }
+                            th { "Початок, л" }
+                            th { "Надходження" }
+                            th { "Продажі" }
+                            th { "Очікувано" }
+                            th { "Виміряно" }
+                            th { "Розбіжність" }
+                            th { "Накопичено" }
+                        }
+                    }
+                    tbody {
+                        for d in item.days.iter() {
+                            tr { key: "{d.day}",
+                                style: if d.over_threshold { "background-color: #fee2e2;" } else { "" },
+                                td { "{d.day}" }
+                                td { "{d.opening:.0}" }
+                                td { "+{d.deliveries}" }
+                                td { "−{d.sales}" }
+                                td { "{d.expected_closing:.0}" }
+                                td { {fmt_opt(d.measured_closing)} }
+                                td { {d.variance.map(|v| format!("{:+.0}", v)).unwrap_or("-".to_string())} }
+                                td { "{d.cumulative_variance:+.0}" }
                             }
-                        },
-                        Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
-                        None => rsx! { div { class: "loading", "Завантаження" } }
+                        }
                     }
                 }
             }
@@ -1416,13 +8252,10 @@ fn ManagementPage() -> Element {
 }
 
 #[component]
-fn AdminFuelItem(
-    item: models::FuelWithTank,
-    bank_info: Resource<Result<models::Bank, ServerFnError>>,
-    fuels: Resource<Result<Vec<models::FuelWithTank>, ServerFnError>>,
-) -> Element {
+fn AdminFuelItem(item: models::FuelWithTank) -> Element {
+    let mut live_fuels = use_context::<Signal<LiveFuels>>();
+    let item = live_fuels.read().get(&item.id).cloned().unwrap_or(item);
     let mut price_input = use_signal(|| (item.price as f64 / 100.0).to_string());
-    let mut refill_amount = use_signal(|| 100);
     let mut msg = use_signal(|| "".to_string());
     let admin_state = use_context::<Signal<Option<Admin>>>();
 
@@ -1448,27 +8281,29 @@ fn AdminFuelItem(
         }
     };
 
-    let handle_refill = move |_| async move {
-        msg.set("Refilling".to_string());
-        if let Some(admin) = admin_state() {
+    let current_price = format!("{:.2} грн", item.price as f64 / 100.0);
+    let charger_on = item.stored > 0;
+    let charger = item.clone();
+    let handle_charger = move |_| {
+        let mut charger = charger.clone();
+        async move {
+            let Some(admin) = admin_state() else {
+                return;
+            };
             let token = admin.session_token.clone().unwrap_or_default();
-            match refill_fuel(item.id, refill_amount(), token).await {
+            let available = !charger_on;
+            match set_charger_available(charger.id, available, token).await {
                 Ok(_) => {
-                    msg.set("Заправлено!".to_string());
-                    bank_info.restart(); // Оновити баланс банку
-                    fuels.restart(); // Оновити дані про пальне
+                    // Одразу показуємо новий стан, не чекаючи оновлення з потоку
+                    charger.stored = if available { charger.capacity } else { 0 };
+                    live_fuels.write().insert(charger.id, charger);
+                    msg.set(if available { "Зарядку увімкнено" } else { "Зарядку вимкнено" }.to_string());
                 }
                 Err(e) => msg.set(clean_error_msg(e.to_string())),
-                // Err(e) => msg.set(e.to_string()),
             }
-        } else {
-            msg.set("Not logged in".to_string());
         }
     };
 
-    let refill_cost = (item.price / 2) * refill_amount() as i64;
-    let fmt_money = |cents: i64| format!("{:.2}", cents as f64 / 100.0);
-
     rsx! {
         div { class: "fuel-item admin-item",
             h3 { "{item.name}" }
@@ -1484,25 +8319,286 @@ fn AdminFuelItem(
                 }
 
                 div { class: "status-group",
-                    p { "Залишок: {item.stored} / {item.capacity} {unit}" }
+                    p { "Діюча ціна: {current_price}" }
+                    if is_electric {
+                        p { if charger_on { "Зарядка доступна" } else { "Зарядка недоступна" } }
+                        button { onclick: handle_charger, if charger_on { "Вимкнути" } else { "Увімкнути" } }
+                    } else {
+                        p { "Залишок: {item.stored} / {item.capacity} {unit}" }
+                        Link { to: Route::SupplyPage {}, class: "nav-item", "Замовити поставку" }
+                    }
+                }
 
-                    if !is_electric {
-                        div { class: "refill-control",
-                            label { "Поповнити: {refill_amount} {unit}" }
-                            input {
-                                type: "range",
-                                min: "10",
-                                max: "1000",
-                                step: "10",
-                                value: "{refill_amount}",
-                                oninput: move |e| refill_amount.set(e.value().parse().unwrap_or(10))
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SupplyPage() -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let nav = use_navigator();
+    let fuels = use_resource(get_fuels);
+    let mut bank_info = use_resource(get_bank_info);
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+    let mut suppliers = use_resource(move || async move { get_suppliers(token()).await });
+    let mut orders = use_resource(move || async move { get_purchase_orders(token()).await });
+
+    let mut supplier_name = use_signal(|| "".to_string());
+    let mut supplier_contact = use_signal(|| "".to_string());
+    let mut order_supplier = use_signal(|| 0);
+    let mut order_fuel = use_signal(|| 0);
+    let mut order_qty = use_signal(|| "1000".to_string());
+    let mut order_cost = use_signal(|| "".to_string());
+    let mut msg = use_signal(|| "".to_string());
+
+    if admin_state().is_none() {
+        nav.push(Route::LoginPage {});
+        return rsx! {};
+    }
+
+    let handle_add_supplier = move |_| async move {
+        let contact = supplier_contact();
+        match create_supplier(supplier_name(), Some(contact), token()).await {
+            Ok(_) => {
+                supplier_name.set("".to_string());
+                supplier_contact.set("".to_string());
+                msg.set("Постачальника додано".to_string());
+                suppliers.restart();
+            }
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    let handle_create_order = move |_| async move {
+        let parsed = (
+            order_qty().parse::<i32>(),
+            order_cost().replace(',', ".").parse::<f64>(),
+        );
+        match parsed {
+            (Ok(qty), Ok(cost)) => {
+                let unit_cost = (cost * 100.0).round() as i64;
+                match create_purchase_order(order_supplier(), order_fuel(), qty, unit_cost, token()).await {
+                    Ok(_) => {
+                        msg.set("Замовлення створено".to_string());
+                        orders.restart();
+                    }
+                    Err(e) => msg.set(clean_error_msg(e.to_string())),
+                }
+            }
+            _ => msg.set("Невірний формат".to_string()),
+        }
+    };
+
+    let fmt_money = |cents: i64| format!("{:.2} грн", cents as f64 / 100.0);
+
+    rsx! {
+        div { class: "page-container",
+            div { class: "content-card",
+                h1 { "Постачання пального" }
+                match &*bank_info.read() {
+                    Some(Ok(bank)) => rsx! { p { class: "subtitle", "Баланс банку: {fmt_money(bank.total)}" } },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка банку: {e}" } },
+                    None => rsx! {}
+                }
+
+                if !msg().is_empty() {
+                    div { class: "status-msg", "{msg}" }
+                }
+
+                h2 { "Постачальники" }
+                match &*suppliers.read() {
+                    Some(Ok(list)) => rsx! {
+                        table { class: "data-table",
+                            thead {
+                                tr {
+                                    th { "Назва" }
+                                    th { "Контакт" }
+                                }
+                            }
+                            tbody {
+                                for s in list {
+                                    tr { key: "{s.id}",
+                                        td { "{s.name}" }
+                                        td { {s.contact.clone().unwrap_or("-".to_string())} }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+                div { class: "admin-controls",
+                    div { class: "control-group",
+                        input {
+                            class: "modern-input",
+                            placeholder: "Назва",
+                            value: "{supplier_name}",
+                            oninput: move |e| supplier_name.set(e.value())
+                        }
+                        input {
+                            class: "modern-input",
+                            placeholder: "Контакт",
+                            value: "{supplier_contact}",
+                            oninput: move |e| supplier_contact.set(e.value())
+                        }
+                        button { onclick: handle_add_supplier, "Додати" }
+                    }
+                }
+
+                h2 { style: "margin-top: 2rem;", "Нове замовлення" }
+                div { class: "admin-controls",
+                    div { class: "control-group",
+                        select {
+                            class: "modern-input",
+                            onchange: move |e| order_supplier.set(e.value().parse().unwrap_or(0)),
+                            option { value: "0", "Постачальник" }
+                            if let Some(Ok(list)) = &*suppliers.read() {
+                                for s in list {
+                                    option { key: "{s.id}", value: "{s.id}", "{s.name}" }
+                                }
                             }
-                            p { class: "cost-preview", "Вартість: {fmt_money(refill_cost)} грн" }
-                            button { class: "refill-btn", onclick: handle_refill, "Поповнити" }
                         }
+                        select {
+                            class: "modern-input",
+                            onchange: move |e| order_fuel.set(e.value().parse().unwrap_or(0)),
+                            option { value: "0", "Пальне" }
+                            if let Some(Ok(list)) = &*fuels.read() {
+                                for f in list.iter().filter(|f| f.fuel_type != "electricity") {
+                                    option { key: "{f.id}", value: "{f.id}", "{f.name}" }
+                                }
+                            }
+                        }
+                    }
+                    div { class: "control-group",
+                        label { "Кількість, л:" }
+                        input { class: "price-input", value: "{order_qty}", oninput: move |e| order_qty.set(e.value()) }
+                        label { "Ціна закупівлі, грн/л:" }
+                        input { class: "price-input", value: "{order_cost}", oninput: move |e| order_cost.set(e.value()) }
+                        button { onclick: handle_create_order, "Створити" }
                     }
                 }
 
+                h2 { style: "margin-top: 2rem;", "Замовлення" }
+                match &*orders.read() {
+                    Some(Ok(list)) => rsx! {
+                        div { style: "overflow-x: auto;",
+                            table { class: "data-table",
+                                thead {
+                                    tr {
+                                        th { "№" }
+                                        th { "Постачальник" }
+                                        th { "Пальне" }
+                                        th { "Доставлено / замовлено, л" }
+                                        th { "Ціна, грн/л" }
+                                        th { "Статус" }
+                                        th { "Приймання" }
+                                    }
+                                }
+                                tbody {
+                                    for o in list.clone() {
+                                        PurchaseOrderRow {
+                                            key: "{o.order.id}",
+                                            item: o,
+                                            on_changed: move |_| {
+                                                orders.restart();
+                                                bank_info.restart();
+                                            },
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! { div { class: "error-message", "Помилка: {e}" } },
+                    None => rsx! { div { class: "loading", "Завантаження" } }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn PurchaseOrderRow(item: models::PurchaseOrderView, on_changed: EventHandler<()>) -> Element {
+    let admin_state = use_context::<Signal<Option<Admin>>>();
+    let order = item.order.clone();
+    let remaining = order.ordered_qty - order.delivered_qty;
+    let mut quantity = use_signal(move || remaining.to_string());
+    let mut temperature = use_signal(|| "".to_string());
+    let mut msg = use_signal(|| "".to_string());
+    let order_id = order.id;
+    let is_open = order.status == "open" || order.status == "partial";
+
+    let token = move || {
+        admin_state()
+            .and_then(|a| a.session_token)
+            .unwrap_or_default()
+    };
+
+    let handle_receive = move |_| async move {
+        let temp = temperature().replace(',', ".");
+        let temp = if temp.trim().is_empty() {
+            Ok(None)
+        } else {
+            temp.trim().parse::<f64>().map(Some)
+        };
+        match (quantity().parse::<i32>(), temp) {
+            (Ok(qty), Ok(t)) => match receive_delivery(order_id, qty, t, token()).await {
+                Ok(status) => {
+                    msg.set(format!("Прийнято. Статус: {}", utils::order_status_label(&status)));
+                    on_changed.call(());
+                }
+                Err(e) => msg.set(clean_error_msg(e.to_string())),
+            },
+            _ => msg.set("Невірний формат".to_string()),
+        }
+    };
+
+    let handle_close = move |_| async move {
+        match close_purchase_order(order_id, token()).await {
+            Ok(_) => on_changed.call(()),
+            Err(e) => msg.set(clean_error_msg(e.to_string())),
+        }
+    };
+
+    rsx! {
+        tr {
+            td { "{order.id}" }
+            td { "{item.supplier_name}" }
+            td { "{item.fuel_name}" }
+            td { "{order.delivered_qty} / {order.ordered_qty}" }
+            td { {format!("{:.2}", order.unit_cost as f64 / 100.0)} }
+            td { {utils::order_status_label(&order.status)} }
+            td {
+                if is_open {
+                    div { style: "display: flex; gap: 5px; align-items: center; flex-wrap: wrap;",
+                        input {
+                            class: "price-input",
+                            style: "width: 80px;",
+                            title: "Кількість, л",
+                            value: "{quantity}",
+                            oninput: move |e| quantity.set(e.value())
+                        }
+                        input {
+                            class: "price-input",
+                            style: "width: 60px;",
+                            placeholder: "t, °C",
+                            value: "{temperature}",
+                            oninput: move |e| temperature.set(e.value())
+                        }
+                        button { onclick: handle_receive, "Прийняти" }
+                        button { onclick: handle_close, "Закрити" }
+                    }
+                }
                 if !msg().is_empty() {
                     div { class: "status-msg", "{msg}" }
                 }
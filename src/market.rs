@@ -0,0 +1,313 @@
+// Ринкові ціни на пальне: джерела (Мінфін, CSV-файл), кеш зі знімками в БД.
+//
+// Джерело обирається змінною MARKET_PRICE_SOURCE: "minfin" (типово) або "file:<шлях>".
+// Знімок вважається свіжим протягом MARKET_PRICE_TTL_SECS секунд (типово 1800).
+
+use std::path::PathBuf;
+
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+use scraper::{ElementRef, Html, Selector};
+
+use crate::models::{FuelPriceStats, MarketQuote};
+use crate::schema::{market_quote, market_snapshot};
+
+pub const MINFIN_URL: &str = "https://index.minfin.com.ua/ua/markets/fuel/tm/";
+const DEFAULT_TTL_SECS: i64 = 1800;
+
+/// Категорії пального в порядку відображення та заголовки колонок таблиці Мінфіну
+pub const FUEL_CATEGORIES: [(&str, &str); 5] = [
+    ("А-95 Преміум", "А-95+"),
+    ("А-95", "А-95"),
+    ("А-92", "А-92"),
+    ("Дизель", "ДП"),
+    ("Газ", "Газ"),
+];
+
+pub trait PriceSource {
+    /// Ключ джерела для знімків у БД
+    fn name(&self) -> String;
+    async fn fetch_quotes(&self) -> Result<Vec<MarketQuote>, String>;
+}
+
+pub struct MinfinSource {
+    pub url: String,
+}
+
+pub struct FileSource {
+    pub path: PathBuf,
+}
+
+impl PriceSource for MinfinSource {
+    fn name(&self) -> String {
+        "minfin".to_string()
+    }
+
+    async fn fetch_quotes(&self) -> Result<Vec<MarketQuote>, String> {
+        let response = reqwest::get(&self.url)
+            .await
+            .map_err(|e| format!("Помилка завантаження: {}", e))?;
+        let html = response
+            .text()
+            .await
+            .map_err(|e| format!("Помилка читання: {}", e))?;
+        parse_minfin_html(&html)
+    }
+}
+
+impl PriceSource for FileSource {
+    fn name(&self) -> String {
+        format!("file:{}", self.path.display())
+    }
+
+    async fn fetch_quotes(&self) -> Result<Vec<MarketQuote>, String> {
+        let text = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|e| format!("Помилка читання файлу {}: {}", self.path.display(), e))?;
+        parse_quotes_csv(&text)
+    }
+}
+
+/// Джерело з конфігурації
+pub enum ConfiguredSource {
+    Minfin(MinfinSource),
+    File(FileSource),
+}
+
+impl ConfiguredSource {
+    pub fn from_env() -> Self {
+        match std::env::var("MARKET_PRICE_SOURCE") {
+            Ok(v) if v.starts_with("file:") => ConfiguredSource::File(FileSource {
+                path: PathBuf::from(&v["file:".len()..]),
+            }),
+            _ => ConfiguredSource::Minfin(MinfinSource {
+                url: MINFIN_URL.to_string(),
+            }),
+        }
+    }
+}
+
+impl PriceSource for ConfiguredSource {
+    fn name(&self) -> String {
+        match self {
+            ConfiguredSource::Minfin(s) => s.name(),
+            ConfiguredSource::File(s) => s.name(),
+        }
+    }
+
+    async fn fetch_quotes(&self) -> Result<Vec<MarketQuote>, String> {
+        match self {
+            ConfiguredSource::Minfin(s) => s.fetch_quotes().await,
+            ConfiguredSource::File(s) => s.fetch_quotes().await,
+        }
+    }
+}
+
+fn parse_price(raw: &str) -> Option<f64> {
+    if raw.is_empty() || raw == "-" {
+        return None;
+    }
+    raw.replace(",", ".").parse::<f64>().ok()
+}
+
+fn cell_text(cell: ElementRef) -> String {
+    cell.text().collect::<Vec<_>>().join("").trim().to_string()
+}
+
+/// Розбирає таблицю цін по мережах АЗС. Колонки визначаються за заголовками, тому зміна
+/// їх порядку не ламає розбір, а зникнення колонок чи рядків повертає помилку.
+pub fn parse_minfin_html(html: &str) -> Result<Vec<MarketQuote>, String> {
+    let document = Html::parse_document(html);
+    let table_selector = Selector::parse("table").map_err(|e| format!("Помилка селектора: {}", e))?;
+    let row_selector = Selector::parse("tr").map_err(|e| format!("Помилка селектора: {}", e))?;
+    let header_selector = Selector::parse("th").map_err(|e| format!("Помилка селектора: {}", e))?;
+    let cell_selector = Selector::parse("td").map_err(|e| format!("Помилка селектора: {}", e))?;
+
+    for table in document.select(&table_selector) {
+        let headers: Vec<String> = table.select(&header_selector).map(cell_text).collect();
+        let columns: Vec<(usize, &str)> = FUEL_CATEGORIES
+            .iter()
+            .filter_map(|(fuel, header)| headers.iter().position(|h| h == header).map(|i| (i, *fuel)))
+            .collect();
+        if columns.is_empty() {
+            continue;
+        }
+        if columns.len() < FUEL_CATEGORIES.len() {
+            return Err(format!(
+                "Змінилась структура таблиці Мінфіну: знайдено колонки {:?}",
+                headers
+            ));
+        }
+
+        let mut quotes = Vec::new();
+        for row in table.select(&row_selector) {
+            let cells: Vec<String> = row.select(&cell_selector).map(cell_text).collect();
+            // Підсумковий рядок із середніми цінами рахуємо самі
+            if cells.len() < headers.len() || cells[0].is_empty() || cells[0].starts_with("Середн") {
+                continue;
+            }
+            for (i, fuel) in &columns {
+                if let Some(price) = parse_price(&cells[*i]) {
+                    quotes.push(MarketQuote {
+                        brand: cells[0].clone(),
+                        fuel: fuel.to_string(),
+                        price,
+                    });
+                }
+            }
+        }
+
+        if quotes.is_empty() {
+            return Err("Таблиця Мінфіну не містить цін".to_string());
+        }
+        return Ok(quotes);
+    }
+
+    Err("Таблицю цін Мінфіну не знайдено".to_string())
+}
+
+/// CSV з рядками `мережа,пальне,ціна` (заголовок необов'язковий, ціна у грн)
+pub fn parse_quotes_csv(text: &str) -> Result<Vec<MarketQuote>, String> {
+    let mut quotes = Vec::new();
+    let mut first = true;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() != 3 {
+            return Err(format!("Рядок {}: очікується 3 поля", i + 1));
+        }
+        let is_first = std::mem::take(&mut first);
+        let Ok(price) = fields[2].parse::<f64>() else {
+            if is_first {
+                continue; // заголовок
+            }
+            return Err(format!("Рядок {}: некоректна ціна", i + 1));
+        };
+        if !FUEL_CATEGORIES.iter().any(|(fuel, _)| *fuel == fields[1]) {
+            return Err(format!("Рядок {}: невідоме пальне {}", i + 1, fields[1]));
+        }
+        quotes.push(MarketQuote {
+            brand: fields[0].to_string(),
+            fuel: fields[1].to_string(),
+            price,
+        });
+    }
+    if quotes.is_empty() {
+        return Err("Файл не містить цін".to_string());
+    }
+    Ok(quotes)
+}
+
+fn calculate_stats(name: &str, prices: &[f64]) -> FuelPriceStats {
+    let sum: f64 = prices.iter().sum();
+    let count = prices.len();
+    let avg = sum / count as f64;
+    let min = prices.iter().fold(f64::INFINITY, |a, &b| a.min(b));
+    let max = prices.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+
+    FuelPriceStats {
+        name: name.to_string(),
+        average: avg,
+        min,
+        max,
+        count,
+    }
+}
+
+/// Середня, мінімальна та максимальна ціна по кожній категорії пального
+pub fn summarize(quotes: &[MarketQuote]) -> Vec<FuelPriceStats> {
+    FUEL_CATEGORIES
+        .iter()
+        .filter_map(|(fuel, _)| {
+            let prices: Vec<f64> = quotes.iter().filter(|q| q.fuel == *fuel).map(|q| q.price).collect();
+            (!prices.is_empty()).then(|| calculate_stats(fuel, &prices))
+        })
+        .collect()
+}
+
+/// Останній знімок джерела: час завантаження та ціни
+pub fn latest_snapshot(conn: &mut SqliteConnection, source: &str) -> QueryResult<Option<(i64, Vec<MarketQuote>)>> {
+    let snapshot: Option<(i32, i64)> = market_snapshot::table
+        .filter(market_snapshot::source.eq(source))
+        .order(market_snapshot::fetched_at.desc())
+        .select((market_snapshot::id, market_snapshot::fetched_at))
+        .first(conn)
+        .optional()?;
+
+    let Some((id, fetched_at)) = snapshot else {
+        return Ok(None);
+    };
+    let quotes = market_quote::table
+        .filter(market_quote::snapshot_id.eq(id))
+        .order(market_quote::id)
+        .select(MarketQuote::as_select())
+        .load(conn)?;
+    Ok(Some((fetched_at, quotes)))
+}
+
+fn save_snapshot(conn: &mut SqliteConnection, source: &str, quotes: &[MarketQuote]) -> QueryResult<i32> {
+    conn.transaction(|conn| {
+        let id: i32 = diesel::insert_into(market_snapshot::table)
+            .values((
+                market_snapshot::source.eq(source),
+                market_snapshot::fetched_at.eq(chrono::Utc::now().timestamp()),
+            ))
+            .returning(market_snapshot::id)
+            .get_result(conn)?;
+        let rows: Vec<_> = quotes
+            .iter()
+            .map(|q| {
+                (
+                    market_quote::snapshot_id.eq(id),
+                    market_quote::brand.eq(&q.brand),
+                    market_quote::fuel.eq(&q.fuel),
+                    market_quote::price.eq(q.price),
+                )
+            })
+            .collect();
+        diesel::insert_into(market_quote::table).values(&rows).execute(conn)?;
+        Ok(id)
+    })
+}
+
+fn ttl_secs() -> i64 {
+    std::env::var("MARKET_PRICE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_TTL_SECS)
+}
+
+/// Ціни по мережах зі свіжого знімка або нового завантаження джерела.
+/// Якщо джерело недоступне, повертається останній (застарілий) знімок.
+pub async fn current_quotes(force_refresh: bool) -> Result<Vec<MarketQuote>, String> {
+    let source = ConfiguredSource::from_env();
+    let name = source.name();
+    let cached = latest_snapshot(&mut crate::db::connection(), &name).map_err(|e| e.to_string())?;
+
+    if let Some((fetched_at, quotes)) = &cached {
+        if !force_refresh && chrono::Utc::now().timestamp() - fetched_at < ttl_secs() {
+            return Ok(quotes.clone());
+        }
+    }
+
+    match source.fetch_quotes().await {
+        Ok(quotes) => {
+            save_snapshot(&mut crate::db::connection(), &name, &quotes).map_err(|e| e.to_string())?;
+            Ok(quotes)
+        }
+        Err(e) => match cached {
+            Some((_, quotes)) => {
+                println!("LOG: Market source {} failed, using cached snapshot: {}", name, e);
+                Ok(quotes)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+pub async fn current_prices(force_refresh: bool) -> Result<Vec<FuelPriceStats>, String> {
+    current_quotes(force_refresh).await.map(|q| summarize(&q))
+}
@@ -3,6 +3,7 @@ use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
 #[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 #[diesel(table_name = customer)]
 pub struct Customer {
     pub id: i32,
@@ -43,6 +44,9 @@ pub struct Fuel {
     pub name: String,
     pub price: i64,
     pub fuel_type: Option<String>, // Нове поле
+    pub density: Option<f64>,
+    pub expansion_coeff: Option<f64>,
+    pub base_price: i64,
 }
 
 #[derive(Queryable, Selectable, Debug, Clone)]
@@ -52,9 +56,545 @@ pub struct Tank {
     pub fuelid: i32,
     pub stored: i32,
     pub capacity: i32,
+    pub variance_threshold: i32,
 }
 
 #[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = tank_reading)]
+pub struct TankReading {
+    pub id: i32,
+    pub tank_id: i32,
+    pub recorded_at: i64,
+    pub height: f64,
+    pub volume: f64,
+    pub water_height: f64,
+    pub temperature: f64,
+    pub source: String,
+    pub net_volume: Option<f64>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = tank_reading)]
+pub struct NewTankReading<'a> {
+    pub tank_id: i32,
+    pub recorded_at: i64,
+    pub height: f64,
+    pub volume: f64,
+    pub water_height: f64,
+    pub temperature: f64,
+    pub source: &'a str,
+    pub net_volume: Option<f64>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = sale)]
+pub struct NewSale {
+    pub customer_id: i32,
+    pub fuel_id: i32,
+    pub amount: i32,
+    pub total: i64,
+    pub created_at: i64,
+    pub vehicle_id: Option<i32>,
+    pub discount: i64,
+    pub promotion_id: Option<i32>,
+    pub points_value: i64,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = stock_movement)]
+pub struct NewStockMovement<'a> {
+    pub tank_id: i32,
+    pub kind: &'a str,
+    pub quantity: i32,
+    pub sale_id: Option<i32>,
+    pub created_at: i64,
+    pub net_quantity: Option<f64>,
+    pub temperature: Option<f64>,
+    pub delivery_id: Option<i32>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = supplier)]
+pub struct Supplier {
+    pub id: i32,
+    pub name: String,
+    pub contact: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = purchase_order)]
+pub struct PurchaseOrder {
+    pub id: i32,
+    pub supplier_id: i32,
+    pub fuel_id: i32,
+    pub ordered_qty: i32,
+    pub delivered_qty: i32,
+    pub unit_cost: i64,
+    pub status: String,
+    pub created_at: i64,
+    pub closed_at: Option<i64>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = delivery)]
+pub struct NewDelivery {
+    pub order_id: i32,
+    pub fuel_id: i32,
+    pub quantity: i32,
+    pub unit_cost: i64,
+    pub total_cost: i64,
+    pub temperature: Option<f64>,
+    pub net_quantity: Option<f64>,
+    pub created_at: i64,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = inventory_lot)]
+pub struct InventoryLot {
+    pub id: i32,
+    pub tank_id: i32,
+    pub delivery_id: Option<i32>,
+    pub unit_cost: i64,
+    pub quantity: i32,
+    pub remaining: i32,
+    pub created_at: i64,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = inventory_lot)]
+pub struct NewInventoryLot {
+    pub tank_id: i32,
+    pub delivery_id: Option<i32>,
+    pub unit_cost: i64,
+    pub quantity: i32,
+    pub remaining: i32,
+    pub created_at: i64,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = price_history)]
+pub struct PriceHistory {
+    pub id: i32,
+    pub fuel_id: i32,
+    pub price: i64,
+    pub changed_at: i64,
+    pub source: String,
+    pub changed_by: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = price_schedule)]
+pub struct PriceSchedule {
+    pub id: i32,
+    pub fuel_id: i32,
+    pub price: i64,
+    pub effective_at: i64,
+    pub created_by: String,
+    pub created_at: i64,
+    pub applied_at: Option<i64>,
+    pub cancelled: bool,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = price_rule)]
+pub struct PriceRule {
+    pub id: i32,
+    pub fuel_id: i32,
+    pub start_minute: i32,
+    pub end_minute: i32,
+    pub price: i64,
+    pub enabled: bool,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = market_pricing_rule)]
+pub struct MarketPricingRule {
+    pub fuel_id: i32,
+    pub market_offset: i64,
+    pub margin_floor: i64,
+    pub auto_apply: bool,
+    pub enabled: bool,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = price_proposal)]
+pub struct PriceProposal {
+    pub id: i32,
+    pub fuel_id: i32,
+    pub current_price: i64,
+    pub proposed_price: i64,
+    pub market_average: i64,
+    pub cost_basis: Option<i64>,
+    pub floor_applied: bool,
+    pub status: String,
+    pub created_at: i64,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<i64>,
+}
+
+// Підписка клієнта: kind — 'price_below' (threshold у копійках) або 'available'
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = price_alert)]
+pub struct PriceAlert {
+    pub id: i32,
+    pub customer_id: i32,
+    pub fuel_id: i32,
+    pub kind: String,
+    pub threshold: Option<i64>,
+    pub active: bool,
+    pub created_at: i64,
+    pub triggered_at: Option<i64>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = notification)]
+pub struct Notification {
+    pub id: i32,
+    pub customer_id: i32,
+    pub alert_id: Option<i32>,
+    pub message: String,
+    pub created_at: i64,
+    pub read_at: Option<i64>,
+}
+
+// Подія для персоналу: низький залишок, зарядка офлайн, невдале поповнення, розбіжність
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = staff_event)]
+pub struct StaffEvent {
+    pub id: i32,
+    pub kind: String,
+    pub severity: String,
+    pub dedup_key: String,
+    pub message: String,
+    pub tank_id: Option<i32>,
+    pub fuel_id: Option<i32>,
+    pub occurrences: i32,
+    pub created_at: i64,
+    pub last_seen_at: i64,
+    pub dispatched_at: Option<i64>,
+    pub resolved_at: Option<i64>,
+    pub acknowledged_by: Option<String>,
+    pub acknowledged_at: Option<i64>,
+}
+
+// Канал доставки подій: kind — 'email' або 'webhook'
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = staff_channel)]
+pub struct StaffChannel {
+    pub id: i32,
+    pub kind: String,
+    pub target: String,
+    pub min_severity: String,
+    pub enabled: bool,
+}
+
+// Поріг низького залишку резервуара (threshold — налаштований, effective — діючий)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TankThreshold {
+    pub tank_id: i32,
+    pub fuel_name: String,
+    pub stored: i32,
+    pub capacity: i32,
+    pub threshold: Option<i32>,
+    pub effective: i32,
+}
+
+// Підписка на вебхуки: events — події через кому або '*'
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = webhook_subscription)]
+pub struct WebhookSubscription {
+    pub id: i32,
+    pub url: String,
+    pub secret: String,
+    pub events: String,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+// Доставка вебхука: status — 'pending', 'delivered' або 'failed'
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = webhook_delivery)]
+pub struct WebhookDelivery {
+    pub id: i32,
+    pub subscription_id: i32,
+    pub event: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: i64,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub delivered_at: Option<i64>,
+}
+
+// API-ключ без хешів секрету (їх читає лише перевірка ключа)
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = api_key)]
+pub struct ApiKey {
+    pub id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: String,
+    pub customer_id: Option<i32>,
+    pub rate_limit: i32,
+    pub created_by: String,
+    pub created_at: i64,
+    pub rotated_at: Option<i64>,
+    pub previous_valid_until: Option<i64>,
+    pub last_used_at: Option<i64>,
+    pub revoked_at: Option<i64>,
+}
+
+// Ключ із логіном рахунку автопарку для адмінки
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiKeyView {
+    pub key: ApiKey,
+    pub customer_login: Option<String>,
+}
+
+// Компанія-автопарк: баланс у копійках належить компанії
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = fleet_company)]
+pub struct FleetCompany {
+    pub id: i32,
+    pub name: String,
+    pub balance: i64,
+    pub created_at: i64,
+}
+
+// Водій компанії; ліміти в копійках (None — без ліміту),
+// allowed_fuel_types — типи пального через кому (None — усі)
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = fleet_driver)]
+pub struct FleetDriver {
+    pub id: i32,
+    pub company_id: i32,
+    pub customer_id: i32,
+    pub card_number: Option<String>,
+    pub daily_limit: Option<i64>,
+    pub monthly_limit: Option<i64>,
+    pub allowed_fuel_types: Option<String>,
+    pub active: bool,
+    pub created_at: i64,
+}
+
+// Водій з логіном і витратами за поточні день та місяць
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FleetDriverView {
+    pub driver: FleetDriver,
+    pub login: String,
+    pub spent_today: i64,
+    pub spent_month: i64,
+}
+
+// Корпоративний рахунок водія для головної сторінки
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DriverAccount {
+    pub company_name: String,
+    pub company_balance: i64,
+    pub driver: FleetDriver,
+    pub spent_today: i64,
+    pub spent_month: i64,
+}
+
+// Рядок виписки: поповнення або покупка водія
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FleetStatementLine {
+    pub created_at: i64,
+    pub kind: String,
+    pub driver_login: Option<String>,
+    pub fuel_name: Option<String>,
+    pub volume: Option<i32>,
+    pub amount: i64,
+    pub note: Option<String>,
+}
+
+// Місячна виписка компанії; суми в копійках
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FleetStatement {
+    pub company: FleetCompany,
+    pub month: String,
+    pub opening_balance: i64,
+    pub top_ups: i64,
+    pub purchases: i64,
+    pub closing_balance: i64,
+    pub lines: Vec<FleetStatementLine>,
+    // (логін водія, сума покупок)
+    pub by_driver: Vec<(String, i64)>,
+}
+
+// Авто клієнта: tank_capacity — літри бака або кВт·год батареї
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+#[diesel(table_name = vehicle)]
+pub struct Vehicle {
+    pub id: i32,
+    pub customer_id: i32,
+    pub plate: String,
+    pub fuel_type: String,
+    pub tank_capacity: i32,
+    pub created_at: i64,
+}
+
+// Авто з підсумками заправок
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VehicleView {
+    pub vehicle: Vehicle,
+    pub fills: i64,
+    pub total_volume: i64,
+    pub total_cost: i64,
+    pub last_fill_at: Option<i64>,
+}
+
+// Одна заправка авто
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VehicleFill {
+    pub sale_id: i32,
+    pub created_at: i64,
+    pub fuel_name: String,
+    pub amount: i32,
+    pub total: i64,
+}
+
+// Історія споживання авто: заправки (нові першими) та підсумки по місяцях
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VehicleHistory {
+    pub vehicle: Vehicle,
+    pub fills: Vec<VehicleFill>,
+    // (місяць "YYYY-MM", обсяг, сума)
+    pub by_month: Vec<(String, i64, i64)>,
+}
+
+// Рівень лояльності: від min_spent (копійки за 365 днів), бали за літр і за 100 грн
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = loyalty_tier)]
+pub struct LoyaltyTier {
+    pub id: i32,
+    pub name: String,
+    pub min_spent: i64,
+    pub points_per_litre: i32,
+    pub points_per_100_uah: i32,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = loyalty_entry)]
+pub struct LoyaltyEntry {
+    pub id: i32,
+    pub customer_id: i32,
+    pub kind: String,
+    pub points: i64,
+    pub remaining: i64,
+    pub expires_at: Option<i64>,
+    pub sale_id: Option<i32>,
+    pub created_at: i64,
+}
+
+// Бонусний рахунок клієнта: рівень, доступні бали, найближче згоряння та історія
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LoyaltyAccount {
+    pub tier: LoyaltyTier,
+    pub next_tier: Option<LoyaltyTier>,
+    pub spent_year: i64,
+    pub points: i64,
+    // (бали, коли згорять)
+    pub expiring: Option<(i64, i64)>,
+    pub history: Vec<LoyaltyEntry>,
+}
+
+// Акція або купон (code). Час happy hour — хвилини від початку доби.
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = promotion)]
+pub struct Promotion {
+    pub id: i32,
+    pub name: String,
+    pub code: Option<String>,
+    pub kind: String,
+    pub value: i32,
+    pub fuel_id: Option<i32>,
+    pub min_quantity: Option<i32>,
+    pub happy_hour_start: Option<i32>,
+    pub happy_hour_end: Option<i32>,
+    pub valid_from: Option<i64>,
+    pub valid_until: Option<i64>,
+    pub per_customer_limit: Option<i32>,
+    pub active: bool,
+    pub created_at: i64,
+}
+
+// Акція з назвою пального та статистикою використань
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PromotionView {
+    pub promotion: Promotion,
+    pub fuel_name: Option<String>,
+    pub uses: i64,
+    pub total_discount: i64,
+}
+
+// Нова акція з адмінки: час "ГГ:ХХ", дати "РРРР-ММ-ДД" (до valid_until включно)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PromotionForm {
+    pub name: String,
+    pub code: Option<String>,
+    pub kind: String,
+    pub value: i32,
+    pub fuel_id: Option<i32>,
+    pub min_quantity: Option<i32>,
+    pub happy_hour: Option<(String, String)>,
+    pub valid_from: Option<String>,
+    pub valid_until: Option<String>,
+    pub per_customer_limit: Option<i32>,
+}
+
+// Розрахунок кошика: сума без знижки, знижка найвигіднішої акції, до сплати
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CheckoutQuote {
+    pub subtotal: i64,
+    pub discount: i64,
+    pub promotion_name: Option<String>,
+    pub total: i64,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = reorder_policy)]
+pub struct ReorderPolicy {
+    pub fuel_id: i32,
+    pub reorder_point: i32,
+    pub target_level: i32,
+    pub avg_daily_sales: Option<f64>,
+    pub days_until_empty: Option<f64>,
+    pub forecast_at: Option<i64>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = scheduled_job)]
+pub struct ScheduledJob {
+    pub name: String,
+    pub schedule: String,
+    pub last_started_at: Option<i64>,
+    pub last_finished_at: Option<i64>,
+    pub last_status: Option<String>,
+    pub last_message: Option<String>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<i64>,
+    pub run_count: i32,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = variance_alert)]
+pub struct VarianceAlert {
+    pub id: i32,
+    pub tank_id: i32,
+    pub day: String,
+    pub variance: f64,
+    pub threshold: i32,
+    pub created_at: i64,
+    pub acknowledged: bool,
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 #[diesel(table_name = bank)]
 pub struct Bank {
     pub id: i32,
@@ -62,6 +602,7 @@ pub struct Bank {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct FuelWithTank {
     pub id: i32,
     pub name: String,
@@ -71,7 +612,131 @@ pub struct FuelWithTank {
     pub capacity: i32,
 }
 
+// Замовлення з назвами постачальника та пального для відображення
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PurchaseOrderView {
+    pub order: PurchaseOrder,
+    pub supplier_name: String,
+    pub fuel_name: String,
+}
+
+// Прогноз і рекомендоване замовлення по пальному
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReorderSuggestion {
+    pub fuel_id: i32,
+    pub fuel_name: String,
+    pub stored: i32,
+    pub capacity: i32,
+    pub on_order: i32,
+    pub policy: ReorderPolicy,
+    pub suggested_qty: i32,
+    pub below_reorder_point: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct PricePoint {
+    pub at: i64,
+    pub price: i64,
+}
+
+// Результат розрахунку ринкового правила (попередній перегляд без змін у БД)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PricePreview {
+    pub fuel_id: i32,
+    pub fuel_name: String,
+    pub current_price: i64,
+    pub market_average: Option<i64>,
+    pub cost_basis: Option<i64>,
+    pub proposed_price: Option<i64>,
+    pub floor_applied: bool,
+}
+
+// Минулі та заплановані ціни пального
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PriceTimeline {
+    pub base_price: i64,
+    pub current_price: i64,
+    pub history: Vec<PriceHistory>,
+    pub upcoming: Vec<PriceSchedule>,
+    pub rules: Vec<PriceRule>,
+}
+
+// Стан задачі планувальника для сторінки адміністратора
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JobStatus {
+    pub job: ScheduledJob,
+    pub running: bool,
+    pub next_run: Option<i64>,
+}
+
+// Облікові (book) та виміряні залишки резервуару
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct TankStock {
+    pub tank_id: i32,
+    pub fuel_name: String,
+    pub capacity: i32,
+    pub book: i32,
+    pub measured: Option<f64>,
+    pub measured_net: Option<f64>,
+    pub water_height: Option<f64>,
+    pub temperature: Option<f64>,
+    pub measured_at: Option<i64>,
+}
+
+// Добова звірка резервуару: початковий залишок + надходження − продажі vs виміряний
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DailyReconciliation {
+    pub day: String,
+    pub opening: f64,
+    pub deliveries: i64,
+    pub sales: i64,
+    pub expected_closing: f64,
+    pub measured_closing: Option<f64>,
+    pub variance: Option<f64>,
+    pub cumulative_variance: f64,
+    pub over_threshold: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TankReconciliation {
+    pub tank_id: i32,
+    pub fuel_name: String,
+    pub threshold: i32,
+    pub days: Vec<DailyReconciliation>,
+}
+
+// Валовий прибуток по пальному за період (суми в копійках)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FuelMargin {
+    pub fuel_id: i32,
+    pub fuel_name: String,
+    pub litres: i64,
+    pub revenue: i64,
+    pub cogs: i64,
+    pub margin: i64,
+    pub margin_pct: Option<f64>,
+}
+
+// Підписка з назвою пального для відображення
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PriceAlertView {
+    pub alert: PriceAlert,
+    pub fuel_name: String,
+}
+
+// Ціна мережі АЗС на пальне з ринкового джерела, грн/л
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = market_quote)]
+pub struct MarketQuote {
+    pub brand: String,
+    pub fuel: String,
+    pub price: f64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct FuelPriceStats {
     pub name: String,
     pub average: f64,
@@ -86,4 +751,5 @@ use dioxus::prelude::*;
 pub struct FuelCardProps {
     pub item: FuelWithTank,
     pub cart: Signal<std::collections::HashMap<i32, i32>>,
+    pub vehicle: Signal<Option<i32>>,
 }
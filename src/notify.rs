@@ -0,0 +1,290 @@
+// Вихідні сповіщення клієнтам: трейт `Notifier`, SMTP-клієнт і локальний поштовий
+// приймач (mail sink) для розробки та тестів.
+//
+// Канал обирається змінними оточення: SMTP_ADDR ("host:port") вмикає надсилання поштою,
+// SMTP_FROM задає адресу відправника. Без SMTP_ADDR сповіщення лише пишуться в лог.
+//
+// SMTP-клієнт мінімальний: EHLO, MAIL FROM, RCPT TO, DATA, QUIT без TLS та автентифікації —
+// розрахований на локальний поштовий релей.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use base64::Engine;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+const DEFAULT_FROM: &str = "noreply@gasstation.local";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug)]
+pub enum NotifyError {
+    Io(std::io::Error),
+    Smtp(u16, String),
+    Protocol(String),
+}
+
+impl std::fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotifyError::Io(e) => write!(f, "Помилка зв'язку з поштовим сервером: {}", e),
+            NotifyError::Smtp(code, text) => write!(f, "Поштовий сервер відхилив команду ({}): {}", code, text),
+            NotifyError::Protocol(msg) => write!(f, "Помилка протоколу SMTP: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for NotifyError {}
+
+impl From<std::io::Error> for NotifyError {
+    fn from(e: std::io::Error) -> Self {
+        NotifyError::Io(e)
+    }
+}
+
+pub trait Notifier {
+    async fn send(&self, message: &Message) -> Result<(), NotifyError>;
+}
+
+/// Сповіщення лише в лог (коли пошту не налаштовано)
+pub struct LogNotifier;
+
+impl Notifier for LogNotifier {
+    async fn send(&self, message: &Message) -> Result<(), NotifyError> {
+        println!("LOG: Notification to {}: {}", message.to, message.subject);
+        Ok(())
+    }
+}
+
+pub struct SmtpNotifier {
+    pub addr: String,
+    pub from: String,
+}
+
+// ---------- SMTP ----------
+
+// Заголовок з не-ASCII текстом кодується як encoded-word (RFC 2047)
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", base64::engine::general_purpose::STANDARD.encode(value))
+    }
+}
+
+/// Текст листа для команди DATA: заголовки, CRLF і дублювання крапки на початку рядка
+pub fn format_message(from: &str, message: &Message) -> String {
+    let mut data = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\n\
+         Content-Type: text/plain; charset=UTF-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+        from,
+        message.to,
+        encode_header(&message.subject),
+        chrono::Utc::now().to_rfc2822(),
+    );
+    for line in message.body.lines() {
+        if line.starts_with('.') {
+            data.push('.');
+        }
+        data.push_str(line);
+        data.push_str("\r\n");
+    }
+    data
+}
+
+struct SmtpSession<S> {
+    stream: BufReader<S>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> SmtpSession<S> {
+    // Читає відповідь (можливо багаторядкову "250-...") і перевіряє код
+    async fn reply(&mut self, expected: u16) -> Result<String, NotifyError> {
+        let mut text = String::new();
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await? == 0 {
+                return Err(NotifyError::Protocol("з'єднання закрито".to_string()));
+            }
+            let line = line.trim_end();
+            let code: u16 = line
+                .get(..3)
+                .and_then(|c| c.parse().ok())
+                .ok_or_else(|| NotifyError::Protocol(format!("некоректна відповідь '{}'", line)))?;
+            text.push_str(line.get(4..).unwrap_or(""));
+            if line.as_bytes().get(3) != Some(&b'-') {
+                if code != expected {
+                    return Err(NotifyError::Smtp(code, text));
+                }
+                return Ok(text);
+            }
+            text.push('\n');
+        }
+    }
+
+    async fn command(&mut self, line: &str, expected: u16) -> Result<String, NotifyError> {
+        self.stream.get_mut().write_all(format!("{}\r\n", line).as_bytes()).await?;
+        self.reply(expected).await
+    }
+}
+
+// Адреса з "Ім'я <addr>" або просто "addr"
+fn envelope_address(value: &str) -> &str {
+    match (value.find('<'), value.rfind('>')) {
+        (Some(a), Some(b)) if a < b => &value[a + 1..b],
+        _ => value.trim(),
+    }
+}
+
+impl Notifier for SmtpNotifier {
+    async fn send(&self, message: &Message) -> Result<(), NotifyError> {
+        let stream = TcpStream::connect(&self.addr).await?;
+        let mut session = SmtpSession { stream: BufReader::new(stream) };
+
+        session.reply(220).await?;
+        session.command("EHLO gasstation.local", 250).await?;
+        session
+            .command(&format!("MAIL FROM:<{}>", envelope_address(&self.from)), 250)
+            .await?;
+        session
+            .command(&format!("RCPT TO:<{}>", envelope_address(&message.to)), 250)
+            .await?;
+        session.command("DATA", 354).await?;
+        let data = format_message(&self.from, message);
+        session.stream.get_mut().write_all(data.as_bytes()).await?;
+        session.command(".", 250).await?;
+        session.command("QUIT", 221).await?;
+        Ok(())
+    }
+}
+
+/// Канал з конфігурації
+pub enum ConfiguredNotifier {
+    Smtp(SmtpNotifier),
+    Log(LogNotifier),
+}
+
+impl ConfiguredNotifier {
+    pub fn from_env() -> Self {
+        match std::env::var("SMTP_ADDR") {
+            Ok(addr) if !addr.is_empty() => ConfiguredNotifier::Smtp(SmtpNotifier {
+                addr,
+                from: std::env::var("SMTP_FROM").unwrap_or_else(|_| DEFAULT_FROM.to_string()),
+            }),
+            _ => ConfiguredNotifier::Log(LogNotifier),
+        }
+    }
+}
+
+impl Notifier for ConfiguredNotifier {
+    async fn send(&self, message: &Message) -> Result<(), NotifyError> {
+        match self {
+            ConfiguredNotifier::Smtp(n) => n.send(message).await,
+            ConfiguredNotifier::Log(n) => n.send(message).await,
+        }
+    }
+}
+
+/// Надсилає листи через налаштований канал; помилки лише логуються
+pub async fn deliver(messages: Vec<Message>) {
+    let notifier = ConfiguredNotifier::from_env();
+    for m in &messages {
+        if let Err(e) = notifier.send(m).await {
+            println!("LOG: Failed to notify {}: {}", m.to, e);
+        }
+    }
+}
+
+// ---------- Поштовий приймач ----------
+
+/// Лист, отриманий приймачем (DATA без завершальної крапки, з прибраним дублюванням крапок)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceivedMail {
+    pub from: String,
+    pub to: Vec<String>,
+    pub data: String,
+}
+
+pub type MailSink = Arc<Mutex<Vec<ReceivedMail>>>;
+
+async fn serve_sink_client(stream: TcpStream, sink: MailSink) -> std::io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    stream.get_mut().write_all(b"220 mail sink ready\r\n").await?;
+
+    let mut from = String::new();
+    let mut to = Vec::new();
+    loop {
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 {
+            return Ok(());
+        }
+        let line = line.trim_end();
+        let upper = line.to_ascii_uppercase();
+        let reply: &[u8] = if upper.starts_with("EHLO") || upper.starts_with("HELO") {
+            b"250-mail sink\r\n250 8BITMIME\r\n"
+        } else if let Some(addr) = upper.strip_prefix("MAIL FROM:").map(|_| &line[10..]) {
+            from = addr.trim_matches(['<', '>', ' ']).to_string();
+            to.clear();
+            b"250 OK\r\n"
+        } else if let Some(addr) = upper.strip_prefix("RCPT TO:").map(|_| &line[8..]) {
+            let addr = addr.trim_matches(['<', '>', ' ']);
+            if addr.contains('@') {
+                to.push(addr.to_string());
+                b"250 OK\r\n"
+            } else {
+                b"550 No such user\r\n"
+            }
+        } else if upper == "DATA" {
+            if to.is_empty() {
+                b"503 No recipients\r\n"
+            } else {
+                stream.get_mut().write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n").await?;
+                let mut data = String::new();
+                loop {
+                    let mut l = String::new();
+                    if stream.read_line(&mut l).await? == 0 {
+                        return Ok(());
+                    }
+                    if l.trim_end_matches(['\r', '\n']) == "." {
+                        break;
+                    }
+                    data.push_str(l.strip_prefix('.').unwrap_or(&l));
+                }
+                println!("LOG: Mail sink received message for {}", to.join(", "));
+                sink.lock().unwrap().push(ReceivedMail {
+                    from: from.clone(),
+                    to: std::mem::take(&mut to),
+                    data,
+                });
+                b"250 Queued\r\n"
+            }
+        } else if upper == "QUIT" {
+            stream.get_mut().write_all(b"221 Bye\r\n").await?;
+            return Ok(());
+        } else {
+            b"502 Command not implemented\r\n"
+        };
+        stream.get_mut().write_all(reply).await?;
+    }
+}
+
+/// Запускає поштовий приймач. Повертає фактичну адресу та сховище отриманих листів.
+pub async fn spawn_mail_sink(addr: &str) -> std::io::Result<(SocketAddr, MailSink)> {
+    let listener = TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+    let sink: MailSink = Arc::new(Mutex::new(Vec::new()));
+
+    let received = sink.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve_sink_client(stream, received.clone()));
+        }
+    });
+
+    Ok((local_addr, sink))
+}
@@ -0,0 +1,269 @@
+// Зміна цін: базова ціна (вручну, за розкладом або за ринковим правилом), правила за
+// часом доби та історія. Усі зміни fuel.price проходять через `refresh_price`, щоб
+// історія була повною.
+
+use chrono::{Local, Timelike};
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+
+use crate::models::{FuelPriceStats, MarketPricingRule, PricePreview, PriceProposal, PriceRule, PriceSchedule};
+use crate::schema::{
+    fuel, inventory_lot, market_pricing_rule, price_history, price_proposal, price_rule, price_schedule,
+    purchase_order, tank,
+};
+use crate::utils::{effective_price, market_fuel_name, market_linked_price, weighted_average_cost};
+
+fn current_minute() -> i32 {
+    let now = Local::now();
+    (now.hour() * 60 + now.minute()) as i32
+}
+
+/// Перераховує діючу ціну з базової та правил; записує історію, якщо ціна змінилась.
+/// Повертає true, якщо fuel.price оновлено.
+pub fn refresh_price(
+    conn: &mut SqliteConnection,
+    fuel_id: i32,
+    source: &str,
+    changed_by: Option<&str>,
+) -> QueryResult<bool> {
+    let (price, base_price): (i64, i64) = fuel::table
+        .find(fuel_id)
+        .select((fuel::price, fuel::base_price))
+        .first(conn)?;
+    let rules: Vec<(i32, i32, i64)> = price_rule::table
+        .filter(price_rule::fuel_id.eq(fuel_id))
+        .filter(price_rule::enabled.eq(true))
+        .order(price_rule::id)
+        .select((price_rule::start_minute, price_rule::end_minute, price_rule::price))
+        .load(conn)?;
+
+    let new_price = effective_price(base_price, &rules, current_minute());
+    if new_price == price {
+        return Ok(false);
+    }
+
+    diesel::update(fuel::table.find(fuel_id))
+        .set(fuel::price.eq(new_price))
+        .execute(conn)?;
+    diesel::insert_into(price_history::table)
+        .values((
+            price_history::fuel_id.eq(fuel_id),
+            price_history::price.eq(new_price),
+            price_history::changed_at.eq(chrono::Utc::now().timestamp()),
+            price_history::source.eq(source),
+            price_history::changed_by.eq(changed_by),
+        ))
+        .execute(conn)?;
+    crate::webhooks::enqueue(
+        conn,
+        crate::webhooks::PRICE_CHANGED,
+        serde_json::json!({
+            "fuel_id": fuel_id,
+            "old_price": price,
+            "new_price": new_price,
+            "source": source,
+            "changed_by": changed_by,
+        }),
+    )?;
+    Ok(true)
+}
+
+/// Встановлює базову ціну та одразу застосовує її з урахуванням правил за часом доби
+pub fn set_base_price(
+    conn: &mut SqliteConnection,
+    fuel_id: i32,
+    base_price: i64,
+    source: &str,
+    changed_by: Option<&str>,
+) -> QueryResult<bool> {
+    conn.transaction(|conn| {
+        diesel::update(fuel::table.find(fuel_id))
+            .set(fuel::base_price.eq(base_price))
+            .execute(conn)?;
+        refresh_price(conn, fuel_id, source, changed_by)
+    })
+}
+
+/// Застосовує заплановані зміни, час яких настав
+pub fn apply_due_schedules(conn: &mut SqliteConnection) -> QueryResult<usize> {
+    let now = chrono::Utc::now().timestamp();
+    let due: Vec<PriceSchedule> = price_schedule::table
+        .filter(price_schedule::effective_at.le(now))
+        .filter(price_schedule::applied_at.is_null())
+        .filter(price_schedule::cancelled.eq(false))
+        .order(price_schedule::effective_at)
+        .select(PriceSchedule::as_select())
+        .load(conn)?;
+
+    // Позначка applied_at і зміна ціни — в одній транзакції: інакше збій між ними
+    // застосував би зміну ще раз на наступному запуску
+    let mut applied = 0;
+    for s in &due {
+        conn.transaction(|conn| {
+            let pending = price_schedule::table
+                .find(s.id)
+                .filter(price_schedule::applied_at.is_null());
+            let claimed = diesel::update(pending)
+                .set(price_schedule::applied_at.eq(Some(now)))
+                .execute(conn)?;
+            if claimed == 1 {
+                set_base_price(conn, s.fuel_id, s.price, "schedule", Some(&s.created_by))?;
+                applied += 1;
+            }
+            QueryResult::Ok(())
+        })?;
+    }
+    Ok(applied)
+}
+
+/// Перемикає ціни відповідно до правил за часом доби
+pub fn apply_time_of_day(conn: &mut SqliteConnection) -> QueryResult<usize> {
+    let fuel_ids: Vec<i32> = fuel::table.select(fuel::id).load(conn)?;
+    let mut changed = 0;
+    for id in fuel_ids {
+        if refresh_price(conn, id, "time_of_day", None)? {
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+pub fn rules_for(conn: &mut SqliteConnection, fuel_id: i32) -> QueryResult<Vec<PriceRule>> {
+    price_rule::table
+        .filter(price_rule::fuel_id.eq(fuel_id))
+        .order(price_rule::start_minute)
+        .select(PriceRule::as_select())
+        .load(conn)
+}
+
+/// Собівартість літра: середньозважена за залишками партій, інакше ціна останнього замовлення
+pub fn cost_basis(conn: &mut SqliteConnection, fuel_id: i32) -> QueryResult<Option<i64>> {
+    let lots: Vec<(i32, i64)> = inventory_lot::table
+        .inner_join(tank::table)
+        .filter(tank::fuelid.eq(fuel_id))
+        .filter(inventory_lot::remaining.gt(0))
+        .select((inventory_lot::remaining, inventory_lot::unit_cost))
+        .load(conn)?;
+    if let Some(cost) = weighted_average_cost(&lots) {
+        return Ok(Some(cost));
+    }
+
+    purchase_order::table
+        .filter(purchase_order::fuel_id.eq(fuel_id))
+        .order(purchase_order::created_at.desc())
+        .select(purchase_order::unit_cost)
+        .first(conn)
+        .optional()
+}
+
+/// Розрахунок ринкових правил без змін у БД
+pub fn preview(conn: &mut SqliteConnection, market: &[FuelPriceStats]) -> QueryResult<Vec<PricePreview>> {
+    let rules: Vec<(MarketPricingRule, String, i64)> = market_pricing_rule::table
+        .inner_join(fuel::table)
+        .filter(market_pricing_rule::enabled.eq(true))
+        .order(market_pricing_rule::fuel_id)
+        .select((MarketPricingRule::as_select(), fuel::name, fuel::price))
+        .load(conn)?;
+
+    let mut result = Vec::new();
+    for (rule, fuel_name, current_price) in rules {
+        let market_average = market_fuel_name(&fuel_name)
+            .and_then(|name| market.iter().find(|m| m.name == name))
+            .map(|m| (m.average * 100.0).round() as i64);
+        let cost = cost_basis(conn, rule.fuel_id)?;
+        let proposal = market_average.map(|avg| market_linked_price(avg, rule.market_offset, cost, rule.margin_floor));
+
+        result.push(PricePreview {
+            fuel_id: rule.fuel_id,
+            fuel_name,
+            current_price,
+            market_average,
+            cost_basis: cost,
+            proposed_price: proposal.map(|p| p.0),
+            floor_applied: proposal.is_some_and(|p| p.1),
+        });
+    }
+    Ok(result)
+}
+
+/// Створює пропозиції цін за ринковими правилами. Попередні неприйняті пропозиції по
+/// пальному замінюються; для правил з auto_apply ціна застосовується одразу.
+pub fn propose(conn: &mut SqliteConnection, market: &[FuelPriceStats]) -> QueryResult<usize> {
+    let now = chrono::Utc::now().timestamp();
+    let mut created = 0;
+
+    for p in preview(conn, market)? {
+        let (Some(proposed), Some(market_average)) = (p.proposed_price, p.market_average) else {
+            continue;
+        };
+        let (base_price, auto_apply): (i64, bool) = fuel::table
+            .inner_join(market_pricing_rule::table)
+            .filter(fuel::id.eq(p.fuel_id))
+            .select((fuel::base_price, market_pricing_rule::auto_apply))
+            .first(conn)?;
+        let pending: Option<i64> = price_proposal::table
+            .filter(price_proposal::fuel_id.eq(p.fuel_id))
+            .filter(price_proposal::status.eq("pending"))
+            .select(price_proposal::proposed_price)
+            .first(conn)
+            .optional()?;
+        if proposed == base_price || pending == Some(proposed) {
+            continue;
+        }
+
+        conn.transaction(|conn| {
+            diesel::update(
+                price_proposal::table
+                    .filter(price_proposal::fuel_id.eq(p.fuel_id))
+                    .filter(price_proposal::status.eq("pending")),
+            )
+            .set(price_proposal::status.eq("superseded"))
+            .execute(conn)?;
+
+            let id: i32 = diesel::insert_into(price_proposal::table)
+                .values((
+                    price_proposal::fuel_id.eq(p.fuel_id),
+                    price_proposal::current_price.eq(p.current_price),
+                    price_proposal::proposed_price.eq(proposed),
+                    price_proposal::market_average.eq(market_average),
+                    price_proposal::cost_basis.eq(p.cost_basis),
+                    price_proposal::floor_applied.eq(p.floor_applied),
+                    price_proposal::created_at.eq(now),
+                ))
+                .returning(price_proposal::id)
+                .get_result(conn)?;
+
+            if auto_apply {
+                decide_proposal(conn, id, true, "auto")?;
+            }
+            QueryResult::Ok(())
+        })?;
+        created += 1;
+    }
+    Ok(created)
+}
+
+/// Приймає або відхиляє пропозицію; false — якщо вона вже не очікує рішення
+pub fn decide_proposal(conn: &mut SqliteConnection, proposal_id: i32, approve: bool, decided_by: &str) -> QueryResult<bool> {
+    conn.transaction(|conn| {
+        let proposal: PriceProposal = price_proposal::table
+            .find(proposal_id)
+            .select(PriceProposal::as_select())
+            .first(conn)?;
+        if proposal.status != "pending" {
+            return Ok(false);
+        }
+
+        if approve {
+            set_base_price(conn, proposal.fuel_id, proposal.proposed_price, "market_rule", Some(decided_by))?;
+        }
+        diesel::update(price_proposal::table.find(proposal_id))
+            .set((
+                price_proposal::status.eq(if approve { "applied" } else { "rejected" }),
+                price_proposal::decided_by.eq(Some(decided_by)),
+                price_proposal::decided_at.eq(Some(chrono::Utc::now().timestamp())),
+            ))
+            .execute(conn)?;
+        Ok(true)
+    })
+}
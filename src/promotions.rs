@@ -0,0 +1,163 @@
+// Акції та купони.
+//
+// Акція без коду діє автоматично, з кодом — лише коли клієнт ввів купон. Знижка у відсотках
+// або в копійках за літр, на одне пальне чи на все; умови — мінімальний обсяг, термін дії,
+// вікно happy hour (місцевий час) і ліміт покупок на клієнта. До покупки застосовується одна,
+// найвигідніша для клієнта акція; знижка записується на рядки продажу (sale.discount) та в
+// promotion_use, за яким рахуються ліміти.
+
+use chrono::{DateTime, Local, Timelike};
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+
+use crate::models::Promotion;
+use crate::schema::{promotion, promotion_use};
+use crate::utils::{format_time_of_day, in_happy_hour, promotion_discounts};
+
+pub const KIND_PERCENT: &str = "percent";
+pub const KIND_PER_LITRE: &str = "per_litre";
+
+/// Застосована акція: знижка по кожному рядку кошика
+pub struct Applied {
+    pub promotion: Promotion,
+    pub discounts: Vec<i64>,
+}
+
+impl Applied {
+    pub fn total(&self) -> i64 {
+        self.discounts.iter().sum()
+    }
+}
+
+/// Купон у тому вигляді, як зберігається: без пробілів по краях, великими літерами
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+/// Скільки покупок клієнт уже зробив за акцією
+pub fn uses(conn: &mut SqliteConnection, promotion_id: i32, customer_id: i32) -> QueryResult<i64> {
+    promotion_use::table
+        .filter(promotion_use::promotion_id.eq(promotion_id))
+        .filter(promotion_use::customer_id.eq(customer_id))
+        .count()
+        .get_result(conn)
+}
+
+/// Чому акція зараз не діє для клієнта (None — діє)
+fn unavailable_reason(
+    conn: &mut SqliteConnection,
+    promo: &Promotion,
+    customer_id: i32,
+    now: DateTime<Local>,
+) -> QueryResult<Option<String>> {
+    let ts = now.timestamp();
+    if !promo.active {
+        return Ok(Some("Акція не діє".to_string()));
+    }
+    if promo.valid_from.is_some_and(|from| ts < from) {
+        return Ok(Some("Акція ще не почалася".to_string()));
+    }
+    if promo.valid_until.is_some_and(|until| ts >= until) {
+        return Ok(Some("Термін дії акції минув".to_string()));
+    }
+    let minute = (now.hour() * 60 + now.minute()) as i32;
+    if !in_happy_hour(promo.happy_hour_start, promo.happy_hour_end, minute) {
+        let (start, end) = (promo.happy_hour_start.unwrap_or(0), promo.happy_hour_end.unwrap_or(0));
+        return Ok(Some(format!("Акція діє з {} до {}", format_time_of_day(start), format_time_of_day(end))));
+    }
+    if let Some(limit) = promo.per_customer_limit {
+        if uses(conn, promo.id, customer_id)? >= limit as i64 {
+            return Ok(Some("Ви вже використали цю акцію".to_string()));
+        }
+    }
+    Ok(None)
+}
+
+fn apply(promo: &Promotion, lines: &[(i32, i32, i64)]) -> Option<Applied> {
+    promotion_discounts(&promo.kind, promo.value, promo.fuel_id, promo.min_quantity, lines).map(|discounts| Applied {
+        promotion: promo.clone(),
+        discounts,
+    })
+}
+
+/// Найвигідніша акція для кошика (пальне, обсяг, вартість) серед автоматичних і купона.
+/// Err — купон не знайдено або він не діє для цього кошика (з причиною).
+pub fn best_for(
+    conn: &mut SqliteConnection,
+    customer_id: i32,
+    lines: &[(i32, i32, i64)],
+    coupon: Option<&str>,
+    now: DateTime<Local>,
+) -> Result<Option<Applied>, String> {
+    let db = |e: diesel::result::Error| e.to_string();
+    let mut best: Option<Applied> = None;
+
+    let code = coupon.map(normalize_code).filter(|c| !c.is_empty());
+    if let Some(code) = code {
+        let promo: Promotion = promotion::table
+            .filter(promotion::code.eq(&code))
+            .select(Promotion::as_select())
+            .first(conn)
+            .optional()
+            .map_err(db)?
+            .ok_or_else(|| format!("Купон {} не знайдено", code))?;
+        if let Some(reason) = unavailable_reason(conn, &promo, customer_id, now).map_err(db)? {
+            return Err(format!("Купон {}: {}", code, reason.to_lowercase()));
+        }
+        let applied = apply(&promo, lines).ok_or_else(|| match promo.min_quantity {
+            Some(min) => format!("Купон {} діє від {} л акційного пального", code, min),
+            None => format!("Купон {} не діє для цього пального", code),
+        })?;
+        best = Some(applied);
+    }
+
+    let automatic: Vec<Promotion> = promotion::table
+        .filter(promotion::code.is_null())
+        .filter(promotion::active.eq(true))
+        .order(promotion::id)
+        .select(Promotion::as_select())
+        .load(conn)
+        .map_err(db)?;
+    for promo in automatic {
+        if unavailable_reason(conn, &promo, customer_id, now).map_err(db)?.is_some() {
+            continue;
+        }
+        if let Some(applied) = apply(&promo, lines) {
+            if best.as_ref().is_none_or(|b| applied.total() > b.total()) {
+                best = Some(applied);
+            }
+        }
+    }
+
+    Ok(best.filter(|b| b.total() > 0))
+}
+
+/// Фіксує використання акції (викликати в транзакції продажу). Ліміт на клієнта
+/// перевіряється ще раз: якщо паралельна покупка вже вичерпала його, повертає Err,
+/// і продаж відкочується.
+pub fn record_use(
+    conn: &mut SqliteConnection,
+    promo: &Promotion,
+    customer_id: i32,
+    sale_id: i32,
+    discount: i64,
+    now: i64,
+) -> Result<(), String> {
+    let db = |e: diesel::result::Error| e.to_string();
+    if let Some(limit) = promo.per_customer_limit {
+        if uses(conn, promo.id, customer_id).map_err(db)? >= limit as i64 {
+            return Err(format!("Акцію «{}» вже використано максимальну кількість разів", promo.name));
+        }
+    }
+    diesel::insert_into(promotion_use::table)
+        .values((
+            promotion_use::promotion_id.eq(promo.id),
+            promotion_use::customer_id.eq(customer_id),
+            promotion_use::sale_id.eq(Some(sale_id)),
+            promotion_use::discount.eq(discount),
+            promotion_use::created_at.eq(now),
+        ))
+        .execute(conn)
+        .map_err(db)?;
+    Ok(())
+}
@@ -47,10 +47,12 @@ pub fn check_rate_limit(ip: &str, max_requests: u32, window: Duration) -> bool {
     }
 }
 
-// Очищення старих записів (викликати періодично)
-pub fn cleanup_old_entries(max_age: Duration) {
+// Очищення старих записів (викликається планувальником), повертає кількість видалених
+pub fn cleanup_old_entries(max_age: Duration) -> usize {
     let mut limiter = RATE_LIMITER.lock().unwrap();
     let now = Instant::now();
+    let before = limiter.len();
 
     limiter.retain(|_, entry| now.duration_since(entry.window_start) < max_age);
+    before - limiter.len()
 }
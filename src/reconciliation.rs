@@ -0,0 +1,166 @@
+// Добова звірка залишків (wet-stock reconciliation) та сигнали про розбіжності
+
+use chrono::{Days, Local, NaiveDate, TimeZone};
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+
+use crate::models::{DailyReconciliation, Tank, TankReconciliation};
+use crate::schema::{fuel, stock_movement, tank, tank_reading, variance_alert};
+use crate::utils::{cumulative_variances, expected_closing_stock, stock_variance, variance_exceeds};
+
+pub fn day_start(day: NaiveDate) -> i64 {
+    let midnight = day.and_hms_opt(0, 0, 0).unwrap();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .unwrap_or_else(|| midnight.and_utc().timestamp())
+}
+
+fn movement_sum(
+    conn: &mut SqliteConnection,
+    tank_id: i32,
+    kind: Option<&str>,
+    from: i64,
+    to: i64,
+) -> QueryResult<i64> {
+    let mut query = stock_movement::table
+        .filter(stock_movement::tank_id.eq(tank_id))
+        .filter(stock_movement::created_at.ge(from))
+        .filter(stock_movement::created_at.lt(to))
+        .select(stock_movement::quantity)
+        .into_boxed();
+    if let Some(k) = kind {
+        query = query.filter(stock_movement::kind.eq(k));
+    }
+    let quantities: Vec<i32> = query.load(conn)?;
+    Ok(quantities.iter().map(|q| *q as i64).sum())
+}
+
+/// Обліковий залишок на момент `ts`: поточний tank.stored мінус рух після `ts`
+fn book_stock_at(conn: &mut SqliteConnection, t: &Tank, ts: i64) -> QueryResult<f64> {
+    let after = movement_sum(conn, t.id, None, ts, i64::MAX)?;
+    Ok(t.stored as f64 - after as f64)
+}
+
+/// Останній вимір у проміжку [from, to)
+fn last_measurement(
+    conn: &mut SqliteConnection,
+    tank_id: i32,
+    from: i64,
+    to: i64,
+) -> QueryResult<Option<f64>> {
+    tank_reading::table
+        .filter(tank_reading::tank_id.eq(tank_id))
+        .filter(tank_reading::recorded_at.ge(from))
+        .filter(tank_reading::recorded_at.lt(to))
+        .order(tank_reading::recorded_at.desc())
+        .select(tank_reading::volume)
+        .first(conn)
+        .optional()
+}
+
+/// Звірка по всіх резервуарах за останні `days` днів (включно з сьогоднішнім)
+pub fn build_report(conn: &mut SqliteConnection, days: u64) -> QueryResult<Vec<TankReconciliation>> {
+    let today = Local::now().date_naive();
+    let first_day = today - Days::new(days.saturating_sub(1));
+
+    let tanks: Vec<(Tank, String)> = tank::table
+        .inner_join(fuel::table)
+        .select((Tank::as_select(), fuel::name))
+        .order(tank::id)
+        .load(conn)?;
+
+    let mut report = Vec::new();
+    for (t, fuel_name) in tanks {
+        let mut rows = Vec::new();
+        let mut prev_measured = {
+            let start = day_start(first_day);
+            last_measurement(conn, t.id, start - 86_400, start)?
+        };
+
+        let mut day = first_day;
+        while day <= today {
+            let start = day_start(day);
+            let end = day_start(day + Days::new(1));
+
+            let opening = match prev_measured {
+                Some(m) => m,
+                None => book_stock_at(conn, &t, start)?,
+            };
+            let deliveries = movement_sum(conn, t.id, Some("delivery"), start, end)?;
+            let sales = -movement_sum(conn, t.id, Some("sale"), start, end)?;
+            let expected = expected_closing_stock(opening, deliveries, sales);
+            let measured = last_measurement(conn, t.id, start, end)?;
+            let variance = measured.map(|m| stock_variance(expected, m));
+
+            rows.push(DailyReconciliation {
+                day: day.format("%Y-%m-%d").to_string(),
+                opening,
+                deliveries,
+                sales,
+                expected_closing: expected,
+                measured_closing: measured,
+                variance,
+                cumulative_variance: 0.0,
+                over_threshold: variance.is_some_and(|v| variance_exceeds(v, t.variance_threshold)),
+            });
+
+            prev_measured = measured;
+            day = day + Days::new(1);
+        }
+
+        let cumulative = cumulative_variances(&rows.iter().map(|r| r.variance).collect::<Vec<_>>());
+        for (row, c) in rows.iter_mut().zip(cumulative) {
+            row.cumulative_variance = c;
+        }
+
+        report.push(TankReconciliation {
+            tank_id: t.id,
+            fuel_name,
+            threshold: t.variance_threshold,
+            days: rows,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Створює сигнали для днів із розбіжністю понад поріг (один сигнал на резервуар і день)
+pub fn raise_alerts(conn: &mut SqliteConnection, report: &[TankReconciliation]) -> QueryResult<usize> {
+    let now = chrono::Utc::now().timestamp();
+    let mut created = 0;
+
+    for t in report {
+        for d in t.days.iter().filter(|d| d.over_threshold) {
+            let variance = d.variance.unwrap_or(0.0);
+            let inserted = diesel::insert_or_ignore_into(variance_alert::table)
+                .values((
+                    variance_alert::tank_id.eq(t.tank_id),
+                    variance_alert::day.eq(&d.day),
+                    variance_alert::variance.eq(variance),
+                    variance_alert::threshold.eq(t.threshold),
+                    variance_alert::created_at.eq(now),
+                ))
+                .execute(conn)?;
+            if inserted > 0 {
+                // Новий сигнал — також подія для персоналу
+                crate::staff::raise(
+                    conn,
+                    crate::staff::KIND_VARIANCE,
+                    "warning",
+                    &format!("variance:tank:{}:{}", t.tank_id, d.day),
+                    &format!(
+                        "Резервуар №{} ({}): розбіжність {:+.1} л за {} (поріг {} л)",
+                        t.tank_id, t.fuel_name, variance, d.day, t.threshold
+                    ),
+                    Some(t.tank_id),
+                    None,
+                )?;
+            }
+            created += inserted;
+        }
+    }
+
+    Ok(created)
+}
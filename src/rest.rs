@@ -0,0 +1,554 @@
+// Стабільний REST API /api/v1 для зовнішніх скриптів та інтеграцій.
+//
+// URL серверних функцій Dioxus містять хеш збірки і змінюються з кожною збіркою, тому
+// зовнішнім клієнтам потрібні сталі шляхи. Обробники лише розбирають запит і викликають
+// ті самі #[server]-функції, що й інтерфейс, — бізнес-логіка одна.
+//
+// Автентифікація: заголовок `Authorization: Bearer <токен>`, токен видають
+// POST /api/v1/auth/login (клієнт) та POST /api/v1/auth/admin/login (адмін).
+// Машинні клієнти замість сесії передають API-ключ (див. api_keys.rs) у заголовку
+// `X-Api-Key` або `Authorization: Bearer gs_...`; ключ діє лише в межах своїх дозволів.
+// Відповіді — JSON; помилки — `{"error": "<текст>"}` з відповідним HTTP-статусом.
+//
+//   POST /api/v1/auth/login                 {login, password} → {token, customer_id, balance}
+//   POST /api/v1/auth/admin/login           {login, password} → {token}
+//   GET  /api/v1/fuels                      пальне з ціною та залишком (ключ: prices:read)
+//   GET  /api/v1/fuels/{id}                 одне пальне (ключ: prices:read)
+//   GET  /api/v1/fuels/{id}/price-history   ?days=30 — історія ціни (ключ: prices:read)
+//   PUT  /api/v1/fuels/{id}/price           {price} у копійках (адмін)
+//   GET  /api/v1/prices/market              ринкові ціни по категоріях (ключ: prices:read)
+//   POST /api/v1/purchases                  {items: [{fuel_id, amount}]} → {balance}
+//                                           (клієнт або ключ purchases:create з рахунку автопарку)
+//   GET  /api/v1/me                         поточний клієнт (клієнт)
+//   GET  /api/v1/tanks                      залишки резервуарів (адмін або ключ stock:read)
+//   GET  /api/v1/bank                       баланс станції (адмін)
+//
+// Специфікація OpenAPI 3 генерується з анотацій обробників і типів відповідей та
+// віддається за GET /api/v1/openapi.json. Маршрути і специфікація будуються з одного
+// переліку (`routes!`), тож недокументованого маршруту бути не може.
+
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use dioxus::prelude::ServerFnError;
+use serde::{Deserialize, Serialize};
+use utoipa::openapi::security::{ApiKey as ApiKeyScheme, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+use crate::api_keys::{self, KeyError};
+use crate::models::{ApiKey, Bank, Customer, FuelPriceStats, FuelWithTank, PricePoint, TankStock, Vehicle};
+
+pub const SPEC_PATH: &str = "/api/v1/openapi.json";
+
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Тіло відповіді з помилкою
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+// Серверні функції повертають лише текст помилки — статус визначаємо за ним
+fn status_for(message: &str) -> StatusCode {
+    if message.contains("Unauthorized")
+        || message.contains("Неавторизований")
+        || message.contains("User not found")
+        || message.starts_with("Хибні дані")
+    {
+        StatusCode::UNAUTHORIZED
+    } else if message.contains("Занадто багато запитів") {
+        StatusCode::TOO_MANY_REQUESTS
+    } else if message.contains("не знайдено") {
+        StatusCode::NOT_FOUND
+    } else {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+impl From<ServerFnError> for ApiError {
+    fn from(e: ServerFnError) -> Self {
+        let message = crate::clean_error_msg(e.to_string());
+        ApiError::new(status_for(&message), message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(ErrorBody { error: self.message })).into_response()
+    }
+}
+
+impl From<KeyError> for ApiError {
+    fn from(e: KeyError) -> Self {
+        let status = match e {
+            KeyError::Invalid | KeyError::Revoked => StatusCode::UNAUTHORIZED,
+            KeyError::MissingScope(_) => StatusCode::FORBIDDEN,
+            KeyError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            KeyError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, e.to_string())
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+// API-ключ із X-Api-Key або з Authorization: Bearer gs_...
+fn api_key(headers: &HeaderMap) -> Option<&str> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+    header("X-Api-Key").or_else(|| {
+        header("Authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|t| t.starts_with("gs_"))
+    })
+}
+
+// Перевірка ключа з потрібним дозволом
+fn authorize_key(raw: &str, scope: &str) -> Result<ApiKey, ApiError> {
+    let now = chrono::Utc::now().timestamp();
+    Ok(api_keys::authenticate(&mut crate::db::connection(), raw, scope, now)?)
+}
+
+fn bearer_token(headers: &HeaderMap) -> Result<String, ApiError> {
+    headers
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| ApiError::new(StatusCode::UNAUTHORIZED, "Потрібен заголовок Authorization: Bearer <токен>"))
+}
+
+// Клієнт за токеном сесії
+fn current_customer(token: &str) -> Result<Customer, ApiError> {
+    use crate::schema::customer;
+    use diesel::prelude::*;
+
+    customer::table
+        .filter(customer::session_token.eq(token))
+        .select(Customer::as_select())
+        .first(&mut crate::db::connection())
+        .optional()
+        .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| ApiError::new(StatusCode::UNAUTHORIZED, "Неавторизований доступ (Invalid Token)"))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct LoginRequest {
+    pub login: String,
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, ToSchema)]
+pub struct CustomerSession {
+    pub token: String,
+    pub customer_id: i32,
+    /// Баланс у копійках
+    pub balance: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, ToSchema)]
+pub struct AdminSession {
+    pub token: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PriceUpdate {
+    /// Нова базова ціна в копійках
+    pub price: i64,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQuery {
+    /// Період у днях (типово 30)
+    pub days: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct PurchaseItem {
+    pub fuel_id: i32,
+    /// Літри (або кВт·год для зарядки)
+    pub amount: i32,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PurchaseRequest {
+    pub items: Vec<PurchaseItem>,
+    /// Авто клієнта: пальне має підходити йому і вміщатися в бак
+    #[serde(default)]
+    pub vehicle_id: Option<i32>,
+    /// Бали лояльності в оплату (1 бал = 1 грн); лише з токеном клієнта, не з API-ключем
+    #[serde(default)]
+    pub points: i64,
+    /// Код купона акції (необов'язково)
+    #[serde(default)]
+    pub coupon: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, ToSchema)]
+pub struct PurchaseResult {
+    /// Залишок на рахунку клієнта в копійках
+    pub balance: i64,
+}
+
+/// Вхід клієнта
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Токен сесії", body = CustomerSession),
+        (status = 401, description = "Хибний логін чи пароль", body = ErrorBody),
+        (status = 422, description = "Некоректне тіло запиту"),
+    )
+)]
+async fn login(Json(req): Json<LoginRequest>) -> ApiResult<CustomerSession> {
+    let customer = crate::login_user(req.login, req.password).await?;
+    Ok(Json(CustomerSession {
+        token: customer.session_token.unwrap_or_default(),
+        customer_id: customer.id,
+        balance: customer.balance,
+    }))
+}
+
+/// Вхід адміністратора
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/admin/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Токен сесії адміна", body = AdminSession),
+        (status = 401, description = "Хибний логін чи пароль", body = ErrorBody),
+        (status = 422, description = "Некоректне тіло запиту"),
+    )
+)]
+async fn admin_login(Json(req): Json<LoginRequest>) -> ApiResult<AdminSession> {
+    let admin = crate::login_admin(req.login, req.password).await?;
+    Ok(Json(AdminSession {
+        token: admin.session_token.unwrap_or_default(),
+    }))
+}
+
+// Публічні дані цін: з ключем діє його власний ліміт замість загального
+async fn load_fuels(headers: &HeaderMap) -> Result<Vec<FuelWithTank>, ApiError> {
+    match api_key(headers) {
+        Some(raw) => {
+            authorize_key(raw, api_keys::SCOPE_PRICES_READ)?;
+            Ok(crate::get_fuels_unblocked().await?)
+        }
+        None => Ok(crate::get_fuels().await?),
+    }
+}
+
+/// Пальне з поточною ціною (копійки) та сумарним залишком
+#[utoipa::path(
+    get,
+    path = "/api/v1/fuels",
+    tag = "fuels",
+    security((), ("api_key" = ["prices:read"])),
+    responses(
+        (status = 200, description = "Перелік пального", body = Vec<FuelWithTank>),
+        (status = 401, description = "Недійсний API-ключ", body = ErrorBody),
+        (status = 403, description = "Ключ без дозволу prices:read", body = ErrorBody),
+        (status = 429, description = "Перевищено ліміт запитів", body = ErrorBody),
+    )
+)]
+async fn list_fuels(headers: HeaderMap) -> ApiResult<Vec<FuelWithTank>> {
+    Ok(Json(load_fuels(&headers).await?))
+}
+
+/// Одне пальне
+#[utoipa::path(
+    get,
+    path = "/api/v1/fuels/{id}",
+    tag = "fuels",
+    params(("id" = i32, Path, description = "id пального")),
+    security((), ("api_key" = ["prices:read"])),
+    responses(
+        (status = 200, description = "Пальне", body = FuelWithTank),
+        (status = 401, description = "Недійсний API-ключ", body = ErrorBody),
+        (status = 403, description = "Ключ без дозволу prices:read", body = ErrorBody),
+        (status = 404, description = "Пальне не знайдено", body = ErrorBody),
+        (status = 429, description = "Перевищено ліміт запитів", body = ErrorBody),
+    )
+)]
+async fn get_fuel(headers: HeaderMap, Path(id): Path<i32>) -> ApiResult<FuelWithTank> {
+    load_fuels(&headers)
+        .await?
+        .into_iter()
+        .find(|f| f.id == id)
+        .map(Json)
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "Паливо не знайдено"))
+}
+
+/// Історія ціни пального
+#[utoipa::path(
+    get,
+    path = "/api/v1/fuels/{id}/price-history",
+    tag = "prices",
+    params(("id" = i32, Path, description = "id пального"), HistoryQuery),
+    security((), ("api_key" = ["prices:read"])),
+    responses(
+        (status = 200, description = "Зміни ціни за період", body = Vec<PricePoint>),
+        (status = 400, description = "Помилка запиту", body = ErrorBody),
+        (status = 401, description = "Недійсний API-ключ", body = ErrorBody),
+        (status = 403, description = "Ключ без дозволу prices:read", body = ErrorBody),
+        (status = 429, description = "Перевищено ліміт запитів ключа", body = ErrorBody),
+    )
+)]
+async fn price_history(
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+    Query(q): Query<HistoryQuery>,
+) -> ApiResult<Vec<PricePoint>> {
+    if let Some(raw) = api_key(&headers) {
+        authorize_key(raw, api_keys::SCOPE_PRICES_READ)?;
+    }
+    Ok(Json(crate::get_price_history(id, q.days.unwrap_or(30)).await?))
+}
+
+/// Нова базова ціна пального (адмін)
+#[utoipa::path(
+    put,
+    path = "/api/v1/fuels/{id}/price",
+    tag = "prices",
+    params(("id" = i32, Path, description = "id пального")),
+    request_body = PriceUpdate,
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Ціну змінено"),
+        (status = 400, description = "Некоректна ціна", body = ErrorBody),
+        (status = 401, description = "Потрібен токен адміна", body = ErrorBody),
+        (status = 422, description = "Некоректне тіло запиту"),
+    )
+)]
+async fn set_price(
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+    Json(req): Json<PriceUpdate>,
+) -> Result<StatusCode, ApiError> {
+    let token = bearer_token(&headers)?;
+    crate::update_fuel_price(id, req.price, token).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Середні, мінімальні та максимальні ринкові ціни по категоріях (грн)
+#[utoipa::path(
+    get,
+    path = "/api/v1/prices/market",
+    tag = "prices",
+    security((), ("api_key" = ["prices:read"])),
+    responses(
+        (status = 200, description = "Ринкові ціни", body = Vec<FuelPriceStats>),
+        (status = 401, description = "Недійсний API-ключ", body = ErrorBody),
+        (status = 403, description = "Ключ без дозволу prices:read", body = ErrorBody),
+        (status = 429, description = "Перевищено ліміт запитів ключа", body = ErrorBody),
+        (status = 502, description = "Джерело ринкових цін недоступне", body = ErrorBody),
+    )
+)]
+async fn market_prices(headers: HeaderMap) -> ApiResult<Vec<FuelPriceStats>> {
+    if let Some(raw) = api_key(&headers) {
+        authorize_key(raw, api_keys::SCOPE_PRICES_READ)?;
+    }
+    crate::fetch_fuel_prices().await.map(Json).map_err(|e| ApiError {
+        status: StatusCode::BAD_GATEWAY,
+        ..e.into()
+    })
+}
+
+/// Покупка пального з рахунку клієнта або, для API-ключа, з рахунку автопарку
+#[utoipa::path(
+    post,
+    path = "/api/v1/purchases",
+    tag = "purchases",
+    request_body = PurchaseRequest,
+    security(("bearer" = []), ("api_key" = ["purchases:create"])),
+    responses(
+        (status = 200, description = "Покупку проведено", body = PurchaseResult),
+        (status = 400, description = "Недостатньо коштів чи пального, пальне не підходить авто, бали з API-ключем", body = ErrorBody),
+        (status = 401, description = "Потрібен токен клієнта або API-ключ", body = ErrorBody),
+        (status = 403, description = "Ключ без дозволу purchases:create", body = ErrorBody),
+        (status = 404, description = "Пальне чи авто не знайдено", body = ErrorBody),
+        (status = 422, description = "Некоректне тіло запиту"),
+        (status = 429, description = "Перевищено ліміт запитів ключа", body = ErrorBody),
+    )
+)]
+async fn purchase(headers: HeaderMap, Json(req): Json<PurchaseRequest>) -> ApiResult<PurchaseResult> {
+    let items: Vec<(i32, i32)> = req.items.iter().map(|i| (i.fuel_id, i.amount)).collect();
+
+    if let Some(raw) = api_key(&headers) {
+        let key = authorize_key(raw, api_keys::SCOPE_PURCHASES_CREATE)?;
+        // Ключ діє від імені автопарку, а не клієнта, тож не може витрачати його бали
+        if req.points > 0 {
+            return Err(ApiError::new(StatusCode::BAD_REQUEST, "Бали лояльності не списуються за API-ключем"));
+        }
+        let customer_id = key.customer_id.ok_or_else(|| {
+            ApiError::new(StatusCode::FORBIDDEN, "Ключ не прив'язаний до рахунку автопарку")
+        })?;
+        if items.is_empty() {
+            return Err(ApiError::new(StatusCode::BAD_REQUEST, "Кошик порожній"));
+        }
+        let balance = crate::complete_purchase(&mut crate::db::connection(), customer_id, items, req.vehicle_id, req.points, req.coupon)?;
+        return Ok(Json(PurchaseResult { balance }));
+    }
+
+    let token = bearer_token(&headers)?;
+    let customer = current_customer(&token)?;
+    if items.is_empty() {
+        return Err(ApiError::new(StatusCode::BAD_REQUEST, "Кошик порожній"));
+    }
+    let balance = crate::buy_fuel_batch(customer.id, items, req.vehicle_id, req.points, req.coupon, token).await?;
+    Ok(Json(PurchaseResult { balance }))
+}
+
+/// Поточний клієнт і його баланс (копійки)
+#[utoipa::path(
+    get,
+    path = "/api/v1/me",
+    tag = "purchases",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Клієнт", body = Customer),
+        (status = 401, description = "Потрібен токен клієнта", body = ErrorBody),
+    )
+)]
+async fn me(headers: HeaderMap) -> ApiResult<Customer> {
+    let token = bearer_token(&headers)?;
+    Ok(Json(current_customer(&token)?))
+}
+
+/// Авто клієнта (для `vehicle_id` у покупці)
+#[utoipa::path(
+    get,
+    path = "/api/v1/vehicles",
+    tag = "purchases",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Авто клієнта", body = Vec<Vehicle>),
+        (status = 401, description = "Потрібен токен клієнта", body = ErrorBody),
+    )
+)]
+async fn vehicles(headers: HeaderMap) -> ApiResult<Vec<Vehicle>> {
+    let token = bearer_token(&headers)?;
+    let customer = current_customer(&token)?;
+    let list = crate::get_vehicles(customer.id, token).await?;
+    Ok(Json(list.into_iter().map(|v| v.vehicle).collect()))
+}
+
+/// Облікові та виміряні залишки резервуарів (адмін або API-ключ)
+#[utoipa::path(
+    get,
+    path = "/api/v1/tanks",
+    tag = "admin",
+    security(("bearer" = []), ("api_key" = ["stock:read"])),
+    responses(
+        (status = 200, description = "Резервуари", body = Vec<TankStock>),
+        (status = 401, description = "Потрібен токен адміна або API-ключ", body = ErrorBody),
+        (status = 403, description = "Ключ без дозволу stock:read", body = ErrorBody),
+        (status = 429, description = "Перевищено ліміт запитів ключа", body = ErrorBody),
+    )
+)]
+async fn tanks(headers: HeaderMap) -> ApiResult<Vec<TankStock>> {
+    if let Some(raw) = api_key(&headers) {
+        authorize_key(raw, api_keys::SCOPE_STOCK_READ)?;
+        return Ok(Json(crate::load_tank_stock(&mut crate::db::connection())?));
+    }
+    let token = bearer_token(&headers)?;
+    Ok(Json(crate::get_tank_stock(token).await?))
+}
+
+/// Баланс станції в копійках (адмін)
+#[utoipa::path(
+    get,
+    path = "/api/v1/bank",
+    tag = "admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Банк станції", body = Bank),
+        (status = 401, description = "Потрібен токен адміна", body = ErrorBody),
+    )
+)]
+async fn bank(headers: HeaderMap) -> ApiResult<Bank> {
+    let token = bearer_token(&headers)?;
+    crate::verify_admin(&mut crate::db::connection(), &token)?;
+    Ok(Json(crate::get_bank_info().await?))
+}
+
+struct SecuritySchemes;
+
+impl utoipa::Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKeyScheme::Header(ApiKeyValue::with_description(
+                "X-Api-Key",
+                "Ключ gs_<prefix>_<secret> від адміністратора; також приймається як Authorization: Bearer",
+            ))),
+        );
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "GasStation API",
+        version = "1.0.0",
+        description = "Стабільний REST API автозаправної станції. Суми — у копійках, час — unix-секунди."
+    ),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "auth", description = "Вхід і токени"),
+        (name = "fuels", description = "Пальне та залишки"),
+        (name = "prices", description = "Ціни"),
+        (name = "purchases", description = "Покупки клієнта"),
+        (name = "admin", description = "Адміністрування"),
+    )
+)]
+struct ApiDoc;
+
+/// Маршрути API та специфікація, зібрані з одного переліку обробників
+pub fn router_with_spec() -> (Router, utoipa::openapi::OpenApi) {
+    OpenApiRouter::with_openapi(ApiDoc::openapi())
+        .routes(routes!(login))
+        .routes(routes!(admin_login))
+        .routes(routes!(list_fuels))
+        .routes(routes!(get_fuel))
+        .routes(routes!(price_history))
+        .routes(routes!(set_price))
+        .routes(routes!(market_prices))
+        .routes(routes!(purchase))
+        .routes(routes!(me))
+        .routes(routes!(vehicles))
+        .routes(routes!(tanks))
+        .routes(routes!(bank))
+        .split_for_parts()
+}
+
+pub fn router() -> Router {
+    let (router, spec) = router_with_spec();
+    router.route(SPEC_PATH, get(move || async move { Json(spec) }))
+}
@@ -0,0 +1,321 @@
+// Планувальник фонових задач: іменовані задачі з cron-розкладом, захистом від
+// накладання запусків і станом останнього запуску в таблиці scheduled_job.
+//
+// Розклад — 5 полів cron (хвилина, година, день місяця, місяць, день тижня) за
+// місцевим часом. Підтримуються `*`, `a-b`, `a,b`, кроки `*/n` та `a-b/n`,
+// а також скорочення @hourly, @daily, @weekly, @monthly.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use chrono::{Datelike, Local, NaiveDateTime, TimeZone, Timelike};
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+
+use crate::models::{JobStatus, ScheduledJob};
+use crate::schema::scheduled_job;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (
+                r,
+                s.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("Некоректний крок: {}", part))?,
+            ),
+            None => (part, 1),
+        };
+        let (from, to) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            let a = a.parse::<u32>().map_err(|_| format!("Некоректне значення: {}", part))?;
+            let b = b.parse::<u32>().map_err(|_| format!("Некоректне значення: {}", part))?;
+            (a, b)
+        } else {
+            let v = range.parse::<u32>().map_err(|_| format!("Некоректне значення: {}", part))?;
+            // `5/15` означає «з 5 до кінця з кроком 15»
+            (v, if part.contains('/') { max } else { v })
+        };
+        if from < min || to > max || from > to {
+            return Err(format!("Значення поза межами {}-{}: {}", min, max, part));
+        }
+        for v in (from..=to).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("Очікується 5 полів розкладу, отримано {}", fields.len()));
+        }
+
+        let mut weekdays = parse_field(fields[4], 0, 7)?;
+        // 7 — також неділя
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(CronSchedule {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)? as u32,
+            days: parse_field(fields[2], 1, 31)? as u32,
+            months: parse_field(fields[3], 1, 12)? as u16,
+            weekdays: weekdays as u8,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        })
+    }
+
+    fn day_matches(&self, dt: &NaiveDateTime) -> bool {
+        let dom = self.days & (1 << dt.day()) != 0;
+        let dow = self.weekdays & (1 << dt.weekday().num_days_from_sunday()) != 0;
+        // Як у cron: якщо обмежено і день місяця, і день тижня — достатньо одного збігу
+        match (self.any_day, self.any_weekday) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    pub fn matches(&self, dt: &NaiveDateTime) -> bool {
+        self.minutes & (1 << dt.minute()) != 0
+            && self.hours & (1 << dt.hour()) != 0
+            && self.months & (1 << dt.month()) != 0
+            && self.day_matches(dt)
+    }
+
+    /// Наступна хвилина після `after`, що відповідає розкладу (пошук у межах ~5 років)
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut dt = after.with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        let limit = after + chrono::Duration::days(5 * 366);
+
+        while dt <= limit {
+            if self.months & (1 << dt.month()) == 0 || !self.day_matches(&dt) {
+                dt = (dt.date() + chrono::Days::new(1)).and_hms_opt(0, 0, 0)?;
+            } else if self.hours & (1 << dt.hour()) == 0 {
+                dt = dt.with_minute(0)? + chrono::Duration::hours(1);
+            } else if self.minutes & (1 << dt.minute()) == 0 {
+                dt += chrono::Duration::minutes(1);
+            } else {
+                return Some(dt);
+            }
+        }
+        None
+    }
+}
+
+type JobFn = Arc<dyn Fn() -> Result<String, String> + Send + Sync>;
+
+struct Job {
+    name: String,
+    expr: String,
+    schedule: CronSchedule,
+    run: JobFn,
+    running: AtomicBool,
+}
+
+#[derive(Default)]
+pub struct Scheduler {
+    jobs: Vec<Arc<Job>>,
+}
+
+static SCHEDULER: OnceLock<Scheduler> = OnceLock::new();
+
+fn local_timestamp(dt: NaiveDateTime) -> i64 {
+    Local
+        .from_local_datetime(&dt)
+        .earliest()
+        .map(|d| d.timestamp())
+        .unwrap_or_else(|| dt.and_utc().timestamp())
+}
+
+fn record_start(conn: &mut SqliteConnection, name: &str, now: i64) -> QueryResult<usize> {
+    diesel::update(scheduled_job::table.find(name))
+        .set(scheduled_job::last_started_at.eq(Some(now)))
+        .execute(conn)
+}
+
+fn record_result(conn: &mut SqliteConnection, name: &str, result: &Result<String, String>) -> QueryResult<usize> {
+    let now = chrono::Utc::now().timestamp();
+    let target = scheduled_job::table.find(name);
+    match result {
+        Ok(message) => diesel::update(target)
+            .set((
+                scheduled_job::last_finished_at.eq(Some(now)),
+                scheduled_job::last_status.eq(Some("ok")),
+                scheduled_job::last_message.eq(Some(message)),
+                scheduled_job::run_count.eq(scheduled_job::run_count + 1),
+            ))
+            .execute(conn),
+        Err(error) => diesel::update(target)
+            .set((
+                scheduled_job::last_finished_at.eq(Some(now)),
+                scheduled_job::last_status.eq(Some("error")),
+                scheduled_job::last_message.eq(None::<String>),
+                scheduled_job::last_error.eq(Some(error)),
+                scheduled_job::last_error_at.eq(Some(now)),
+                scheduled_job::run_count.eq(scheduled_job::run_count + 1),
+            ))
+            .execute(conn),
+    }
+}
+
+fn record_skipped(conn: &mut SqliteConnection, name: &str) -> QueryResult<usize> {
+    diesel::update(scheduled_job::table.find(name))
+        .set((
+            scheduled_job::last_status.eq(Some("skipped")),
+            scheduled_job::last_message.eq(Some("Попередній запуск ще триває")),
+        ))
+        .execute(conn)
+}
+
+// Скидає прапорець виконання навіть якщо задача впала з панікою
+struct RunningGuard(Arc<Job>);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.0.running.store(false, Ordering::SeqCst);
+    }
+}
+
+fn execute(job: Arc<Job>) {
+    tokio::task::spawn_blocking(move || {
+        let mut conn = crate::db::connection();
+        if job.running.swap(true, Ordering::SeqCst) {
+            println!("LOG: Job {} is still running, skipping", job.name);
+            record_skipped(&mut conn, &job.name).ok();
+            return;
+        }
+        let _guard = RunningGuard(job.clone());
+
+        record_start(&mut conn, &job.name, chrono::Utc::now().timestamp()).ok();
+        let result = (job.run)();
+        if let Err(e) = &result {
+            println!("LOG: Job {} failed: {}", job.name, e);
+        }
+        if let Err(e) = record_result(&mut conn, &job.name, &result) {
+            println!("LOG: Failed to record job {} result: {}", job.name, e);
+        }
+    });
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Реєструє задачу. Некоректний розклад — помилка конфігурації, тому панікуємо.
+    pub fn job<F>(mut self, name: &str, expr: &str, run: F) -> Self
+    where
+        F: Fn() -> Result<String, String> + Send + Sync + 'static,
+    {
+        let schedule = CronSchedule::parse(expr)
+            .unwrap_or_else(|e| panic!("Некоректний розклад задачі {}: {}", name, e));
+        self.jobs.push(Arc::new(Job {
+            name: name.to_string(),
+            expr: expr.to_string(),
+            schedule,
+            run: Arc::new(run),
+            running: AtomicBool::new(false),
+        }));
+        self
+    }
+
+    /// Зберігає задачі в БД і запускає цикл перевірки розкладу на початку кожної хвилини
+    pub fn start(self) {
+        let mut conn = crate::db::connection();
+        for job in &self.jobs {
+            let registered = diesel::insert_into(scheduled_job::table)
+                .values((
+                    scheduled_job::name.eq(&job.name),
+                    scheduled_job::schedule.eq(&job.expr),
+                ))
+                .on_conflict(scheduled_job::name)
+                .do_update()
+                .set(scheduled_job::schedule.eq(&job.expr))
+                .execute(&mut conn);
+            if let Err(e) = registered {
+                println!("LOG: Failed to register job {}: {}", job.name, e);
+            }
+        }
+
+        if SCHEDULER.set(self).is_err() {
+            println!("LOG: Scheduler is already running");
+            return;
+        }
+
+        tokio::spawn(async {
+            loop {
+                let now = Local::now().naive_local();
+                let wait = 60 - now.second() as u64;
+                tokio::time::sleep(Duration::from_secs(wait)).await;
+
+                let tick = Local::now().naive_local();
+                for job in SCHEDULER.get().into_iter().flat_map(|s| s.jobs.iter()) {
+                    if job.schedule.matches(&tick) {
+                        execute(job.clone());
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Позаплановий запуск задачі (із тим самим захистом від накладання)
+pub fn run_now(name: &str) -> Result<(), String> {
+    let job = SCHEDULER
+        .get()
+        .and_then(|s| s.jobs.iter().find(|j| j.name == name))
+        .ok_or_else(|| format!("Задачу {} не знайдено", name))?;
+    execute(job.clone());
+    Ok(())
+}
+
+pub fn statuses(conn: &mut SqliteConnection) -> QueryResult<Vec<JobStatus>> {
+    let rows: Vec<ScheduledJob> = scheduled_job::table
+        .order(scheduled_job::name)
+        .select(ScheduledJob::as_select())
+        .load(conn)?;
+    let now = Local::now().naive_local();
+
+    Ok(rows
+        .into_iter()
+        .map(|job| {
+            let registered = SCHEDULER
+                .get()
+                .and_then(|s| s.jobs.iter().find(|j| j.name == job.name));
+            JobStatus {
+                running: registered.is_some_and(|j| j.running.load(Ordering::SeqCst)),
+                next_run: registered
+                    .and_then(|j| j.schedule.next_after(now))
+                    .map(local_timestamp),
+                job,
+            }
+        })
+        .collect())
+}
@@ -10,6 +10,25 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    api_key (id) {
+        id -> Integer,
+        name -> Text,
+        prefix -> Text,
+        key_hash -> Text,
+        previous_hash -> Nullable<Text>,
+        previous_valid_until -> Nullable<BigInt>,
+        scopes -> Text,
+        customer_id -> Nullable<Integer>,
+        rate_limit -> Integer,
+        created_by -> Text,
+        created_at -> BigInt,
+        rotated_at -> Nullable<BigInt>,
+        last_used_at -> Nullable<BigInt>,
+        revoked_at -> Nullable<BigInt>,
+    }
+}
+
 diesel::table! {
     bank (id) {
         id -> Integer,
@@ -25,6 +44,57 @@ diesel::table! {
         salt -> Text,
         balance -> BigInt,
         session_token -> Nullable<Text>,
+        email -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    delivery (id) {
+        id -> Integer,
+        order_id -> Integer,
+        fuel_id -> Integer,
+        quantity -> Integer,
+        unit_cost -> BigInt,
+        total_cost -> BigInt,
+        temperature -> Nullable<Double>,
+        net_quantity -> Nullable<Double>,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    fleet_company (id) {
+        id -> Integer,
+        name -> Text,
+        balance -> BigInt,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    fleet_driver (id) {
+        id -> Integer,
+        company_id -> Integer,
+        customer_id -> Integer,
+        card_number -> Nullable<Text>,
+        daily_limit -> Nullable<BigInt>,
+        monthly_limit -> Nullable<BigInt>,
+        allowed_fuel_types -> Nullable<Text>,
+        active -> Bool,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    fleet_ledger (id) {
+        id -> Integer,
+        company_id -> Integer,
+        driver_id -> Nullable<Integer>,
+        sale_id -> Nullable<Integer>,
+        kind -> Text,
+        amount -> BigInt,
+        note -> Nullable<Text>,
+        created_at -> BigInt,
     }
 }
 
@@ -34,6 +104,285 @@ diesel::table! {
         name -> Text,
         price -> BigInt,
         fuel_type -> Nullable<Text>,
+        density -> Nullable<Double>,
+        expansion_coeff -> Nullable<Double>,
+        base_price -> BigInt,
+    }
+}
+
+diesel::table! {
+    inventory_lot (id) {
+        id -> Integer,
+        tank_id -> Integer,
+        delivery_id -> Nullable<Integer>,
+        unit_cost -> BigInt,
+        quantity -> Integer,
+        remaining -> Integer,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    market_quote (id) {
+        id -> Integer,
+        snapshot_id -> Integer,
+        fuel -> Text,
+        price -> Double,
+        brand -> Text,
+    }
+}
+
+diesel::table! {
+    market_snapshot (id) {
+        id -> Integer,
+        source -> Text,
+        fetched_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    loyalty_entry (id) {
+        id -> Integer,
+        customer_id -> Integer,
+        kind -> Text,
+        points -> BigInt,
+        remaining -> BigInt,
+        expires_at -> Nullable<BigInt>,
+        sale_id -> Nullable<Integer>,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    loyalty_tier (id) {
+        id -> Integer,
+        name -> Text,
+        min_spent -> BigInt,
+        points_per_litre -> Integer,
+        points_per_100_uah -> Integer,
+    }
+}
+
+diesel::table! {
+    market_pricing_rule (fuel_id) {
+        fuel_id -> Integer,
+        market_offset -> BigInt,
+        margin_floor -> BigInt,
+        auto_apply -> Bool,
+        enabled -> Bool,
+    }
+}
+
+diesel::table! {
+    notification (id) {
+        id -> Integer,
+        customer_id -> Integer,
+        alert_id -> Nullable<Integer>,
+        message -> Text,
+        created_at -> BigInt,
+        read_at -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    price_alert (id) {
+        id -> Integer,
+        customer_id -> Integer,
+        fuel_id -> Integer,
+        kind -> Text,
+        threshold -> Nullable<BigInt>,
+        active -> Bool,
+        created_at -> BigInt,
+        triggered_at -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    price_history (id) {
+        id -> Integer,
+        fuel_id -> Integer,
+        price -> BigInt,
+        changed_at -> BigInt,
+        source -> Text,
+        changed_by -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    price_proposal (id) {
+        id -> Integer,
+        fuel_id -> Integer,
+        current_price -> BigInt,
+        proposed_price -> BigInt,
+        market_average -> BigInt,
+        cost_basis -> Nullable<BigInt>,
+        floor_applied -> Bool,
+        status -> Text,
+        created_at -> BigInt,
+        decided_by -> Nullable<Text>,
+        decided_at -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    price_rule (id) {
+        id -> Integer,
+        fuel_id -> Integer,
+        start_minute -> Integer,
+        end_minute -> Integer,
+        price -> BigInt,
+        enabled -> Bool,
+    }
+}
+
+diesel::table! {
+    price_schedule (id) {
+        id -> Integer,
+        fuel_id -> Integer,
+        price -> BigInt,
+        effective_at -> BigInt,
+        created_by -> Text,
+        created_at -> BigInt,
+        applied_at -> Nullable<BigInt>,
+        cancelled -> Bool,
+    }
+}
+
+diesel::table! {
+    promotion (id) {
+        id -> Integer,
+        name -> Text,
+        code -> Nullable<Text>,
+        kind -> Text,
+        value -> Integer,
+        fuel_id -> Nullable<Integer>,
+        min_quantity -> Nullable<Integer>,
+        happy_hour_start -> Nullable<Integer>,
+        happy_hour_end -> Nullable<Integer>,
+        valid_from -> Nullable<BigInt>,
+        valid_until -> Nullable<BigInt>,
+        per_customer_limit -> Nullable<Integer>,
+        active -> Bool,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    promotion_use (id) {
+        id -> Integer,
+        promotion_id -> Integer,
+        customer_id -> Integer,
+        sale_id -> Nullable<Integer>,
+        discount -> BigInt,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    purchase_order (id) {
+        id -> Integer,
+        supplier_id -> Integer,
+        fuel_id -> Integer,
+        ordered_qty -> Integer,
+        delivered_qty -> Integer,
+        unit_cost -> BigInt,
+        status -> Text,
+        created_at -> BigInt,
+        closed_at -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    reorder_policy (fuel_id) {
+        fuel_id -> Integer,
+        reorder_point -> Integer,
+        target_level -> Integer,
+        avg_daily_sales -> Nullable<Double>,
+        days_until_empty -> Nullable<Double>,
+        forecast_at -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    sale (id) {
+        id -> Integer,
+        customer_id -> Integer,
+        fuel_id -> Integer,
+        amount -> Integer,
+        total -> BigInt,
+        created_at -> BigInt,
+        net_amount -> Nullable<Double>,
+        cogs -> Nullable<BigInt>,
+        vehicle_id -> Nullable<Integer>,
+        discount -> BigInt,
+        promotion_id -> Nullable<Integer>,
+        points_value -> BigInt,
+    }
+}
+
+diesel::table! {
+    scheduled_job (name) {
+        name -> Text,
+        schedule -> Text,
+        last_started_at -> Nullable<BigInt>,
+        last_finished_at -> Nullable<BigInt>,
+        last_status -> Nullable<Text>,
+        last_message -> Nullable<Text>,
+        last_error -> Nullable<Text>,
+        last_error_at -> Nullable<BigInt>,
+        run_count -> Integer,
+    }
+}
+
+diesel::table! {
+    staff_channel (id) {
+        id -> Integer,
+        kind -> Text,
+        target -> Text,
+        min_severity -> Text,
+        enabled -> Bool,
+    }
+}
+
+diesel::table! {
+    staff_event (id) {
+        id -> Integer,
+        kind -> Text,
+        severity -> Text,
+        dedup_key -> Text,
+        message -> Text,
+        tank_id -> Nullable<Integer>,
+        fuel_id -> Nullable<Integer>,
+        occurrences -> Integer,
+        created_at -> BigInt,
+        last_seen_at -> BigInt,
+        dispatched_at -> Nullable<BigInt>,
+        resolved_at -> Nullable<BigInt>,
+        acknowledged_by -> Nullable<Text>,
+        acknowledged_at -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    stock_movement (id) {
+        id -> Integer,
+        tank_id -> Integer,
+        kind -> Text,
+        quantity -> Integer,
+        sale_id -> Nullable<Integer>,
+        created_at -> BigInt,
+        net_quantity -> Nullable<Double>,
+        temperature -> Nullable<Double>,
+        delivery_id -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    supplier (id) {
+        id -> Integer,
+        name -> Text,
+        contact -> Nullable<Text>,
+        created_at -> BigInt,
     }
 }
 
@@ -43,9 +392,167 @@ diesel::table! {
         fuelid -> Integer,
         stored -> Integer,
         capacity -> Integer,
+        atg_number -> Nullable<Integer>,
+        variance_threshold -> Integer,
+        low_stock_threshold -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    tank_reading (id) {
+        id -> Integer,
+        tank_id -> Integer,
+        recorded_at -> BigInt,
+        height -> Double,
+        volume -> Double,
+        water_height -> Double,
+        temperature -> Double,
+        source -> Text,
+        net_volume -> Nullable<Double>,
+    }
+}
+
+diesel::table! {
+    tank_strapping (id) {
+        id -> Integer,
+        tank_id -> Integer,
+        height_mm -> Integer,
+        volume -> Double,
+    }
+}
+
+diesel::table! {
+    variance_alert (id) {
+        id -> Integer,
+        tank_id -> Integer,
+        day -> Text,
+        variance -> Double,
+        threshold -> Integer,
+        created_at -> BigInt,
+        acknowledged -> Bool,
+    }
+}
+
+diesel::table! {
+    vehicle (id) {
+        id -> Integer,
+        customer_id -> Integer,
+        plate -> Text,
+        fuel_type -> Text,
+        tank_capacity -> Integer,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    webhook_delivery (id) {
+        id -> Integer,
+        subscription_id -> Integer,
+        event -> Text,
+        payload -> Text,
+        status -> Text,
+        attempts -> Integer,
+        next_attempt_at -> BigInt,
+        last_status_code -> Nullable<Integer>,
+        last_error -> Nullable<Text>,
+        created_at -> BigInt,
+        delivered_at -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    webhook_subscription (id) {
+        id -> Integer,
+        url -> Text,
+        secret -> Text,
+        events -> Text,
+        enabled -> Bool,
+        created_at -> BigInt,
     }
 }
 
+diesel::joinable!(api_key -> customer (customer_id));
+diesel::joinable!(delivery -> fuel (fuel_id));
+diesel::joinable!(delivery -> purchase_order (order_id));
+diesel::joinable!(fleet_driver -> customer (customer_id));
+diesel::joinable!(fleet_driver -> fleet_company (company_id));
+diesel::joinable!(fleet_ledger -> fleet_company (company_id));
+diesel::joinable!(fleet_ledger -> fleet_driver (driver_id));
+diesel::joinable!(fleet_ledger -> sale (sale_id));
+diesel::joinable!(inventory_lot -> delivery (delivery_id));
+diesel::joinable!(inventory_lot -> tank (tank_id));
+diesel::joinable!(loyalty_entry -> customer (customer_id));
+diesel::joinable!(loyalty_entry -> sale (sale_id));
+diesel::joinable!(market_pricing_rule -> fuel (fuel_id));
+diesel::joinable!(market_quote -> market_snapshot (snapshot_id));
+diesel::joinable!(notification -> customer (customer_id));
+diesel::joinable!(notification -> price_alert (alert_id));
+diesel::joinable!(price_alert -> customer (customer_id));
+diesel::joinable!(price_alert -> fuel (fuel_id));
+diesel::joinable!(price_history -> fuel (fuel_id));
+diesel::joinable!(price_proposal -> fuel (fuel_id));
+diesel::joinable!(price_rule -> fuel (fuel_id));
+diesel::joinable!(price_schedule -> fuel (fuel_id));
+diesel::joinable!(promotion -> fuel (fuel_id));
+diesel::joinable!(promotion_use -> customer (customer_id));
+diesel::joinable!(promotion_use -> promotion (promotion_id));
+diesel::joinable!(promotion_use -> sale (sale_id));
+diesel::joinable!(purchase_order -> fuel (fuel_id));
+diesel::joinable!(purchase_order -> supplier (supplier_id));
+diesel::joinable!(reorder_policy -> fuel (fuel_id));
+diesel::joinable!(sale -> customer (customer_id));
+diesel::joinable!(sale -> fuel (fuel_id));
+diesel::joinable!(sale -> promotion (promotion_id));
+diesel::joinable!(sale -> vehicle (vehicle_id));
+diesel::joinable!(staff_event -> fuel (fuel_id));
+diesel::joinable!(staff_event -> tank (tank_id));
+diesel::joinable!(stock_movement -> delivery (delivery_id));
+diesel::joinable!(stock_movement -> sale (sale_id));
+diesel::joinable!(stock_movement -> tank (tank_id));
 diesel::joinable!(tank -> fuel (fuelid));
+diesel::joinable!(tank_reading -> tank (tank_id));
+diesel::joinable!(tank_strapping -> tank (tank_id));
+diesel::joinable!(variance_alert -> tank (tank_id));
+diesel::joinable!(vehicle -> customer (customer_id));
+diesel::joinable!(webhook_delivery -> webhook_subscription (subscription_id));
 
-diesel::allow_tables_to_appear_in_same_query!(admin, bank, customer, fuel, tank,);
+diesel::allow_tables_to_appear_in_same_query!(
+    admin,
+    api_key,
+    bank,
+    customer,
+    delivery,
+    fleet_company,
+    fleet_driver,
+    fleet_ledger,
+    fuel,
+    inventory_lot,
+    loyalty_entry,
+    loyalty_tier,
+    market_pricing_rule,
+    market_quote,
+    market_snapshot,
+    notification,
+    price_alert,
+    price_history,
+    price_proposal,
+    price_rule,
+    price_schedule,
+    promotion,
+    promotion_use,
+    purchase_order,
+    reorder_policy,
+    sale,
+    scheduled_job,
+    staff_channel,
+    staff_event,
+    stock_movement,
+    supplier,
+    tank,
+    tank_reading,
+    tank_strapping,
+    variance_alert,
+    vehicle,
+    webhook_delivery,
+    webhook_subscription,
+);
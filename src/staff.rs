@@ -0,0 +1,326 @@
+// Події для персоналу станції: низький залишок, зарядка офлайн, невдале поповнення,
+// велика розбіжність. Подія записується в staff_event і показується в адмінці; окремо
+// фонова розсилка передає нові події в налаштовані канали (пошта, вебхук).
+//
+// Дедуплікація: поки подія з тим самим ключем не вирішена, повтор лише оновлює лічильник
+// і час. Події-стани (низький залишок, зарядка офлайн) вирішуються самі, коли стан
+// минає; підтвердження адміном означає «побачено» і не закриває подію.
+
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+
+use crate::models::{StaffChannel, StaffEvent};
+use crate::notify::{ConfiguredNotifier, Message, Notifier};
+use crate::schema::{fuel, staff_channel, staff_event, tank};
+use crate::utils::{low_stock_severity, low_stock_threshold, severity_at_least};
+
+pub const KIND_LOW_STOCK: &str = "low_stock";
+pub const KIND_CHARGER_OFFLINE: &str = "charger_offline";
+pub const KIND_REFILL_FAILED: &str = "refill_failed";
+pub const KIND_VARIANCE: &str = "variance";
+
+/// Записує подію. Повертає true, якщо подія нова або підвищився її рівень
+/// (тобто її треба розіслати).
+pub fn raise(
+    conn: &mut SqliteConnection,
+    kind: &str,
+    severity: &str,
+    dedup_key: &str,
+    message: &str,
+    tank_id: Option<i32>,
+    fuel_id: Option<i32>,
+) -> QueryResult<bool> {
+    let now = chrono::Utc::now().timestamp();
+    conn.transaction(|conn| {
+        let open: Option<(i32, String)> = staff_event::table
+            .filter(staff_event::dedup_key.eq(dedup_key))
+            .filter(staff_event::resolved_at.is_null())
+            .select((staff_event::id, staff_event::severity))
+            .first(conn)
+            .optional()?;
+
+        match open {
+            Some((id, current)) => {
+                let escalated = !severity_at_least(&current, severity);
+                diesel::update(staff_event::table.find(id))
+                    .set((
+                        staff_event::occurrences.eq(staff_event::occurrences + 1),
+                        staff_event::last_seen_at.eq(now),
+                        staff_event::message.eq(message),
+                    ))
+                    .execute(conn)?;
+                if escalated {
+                    // Вищий рівень — нове повідомлення для каналів і для адміна
+                    diesel::update(staff_event::table.find(id))
+                        .set((
+                            staff_event::severity.eq(severity),
+                            staff_event::dispatched_at.eq(None::<i64>),
+                            staff_event::acknowledged_by.eq(None::<String>),
+                            staff_event::acknowledged_at.eq(None::<i64>),
+                        ))
+                        .execute(conn)?;
+                }
+                Ok(escalated)
+            }
+            None => {
+                diesel::insert_into(staff_event::table)
+                    .values((
+                        staff_event::kind.eq(kind),
+                        staff_event::severity.eq(severity),
+                        staff_event::dedup_key.eq(dedup_key),
+                        staff_event::message.eq(message),
+                        staff_event::tank_id.eq(tank_id),
+                        staff_event::fuel_id.eq(fuel_id),
+                        staff_event::created_at.eq(now),
+                        staff_event::last_seen_at.eq(now),
+                    ))
+                    .execute(conn)?;
+                Ok(true)
+            }
+        }
+    })
+}
+
+/// Закриває відкриту подію, коли її стан минув
+pub fn resolve(conn: &mut SqliteConnection, dedup_key: &str) -> QueryResult<usize> {
+    diesel::update(
+        staff_event::table
+            .filter(staff_event::dedup_key.eq(dedup_key))
+            .filter(staff_event::resolved_at.is_null()),
+    )
+    .set(staff_event::resolved_at.eq(Some(chrono::Utc::now().timestamp())))
+    .execute(conn)
+}
+
+/// Позначка «побачено» від адміна
+pub fn acknowledge(conn: &mut SqliteConnection, event_id: i32, login: &str) -> QueryResult<usize> {
+    diesel::update(
+        staff_event::table
+            .find(event_id)
+            .filter(staff_event::acknowledged_at.is_null()),
+    )
+    .set((
+        staff_event::acknowledged_by.eq(Some(login)),
+        staff_event::acknowledged_at.eq(Some(chrono::Utc::now().timestamp())),
+    ))
+    .execute(conn)
+}
+
+// id, fuelid, stored, capacity, low_stock_threshold, назва і тип пального
+type TankRow = (i32, i32, i32, i32, Option<i32>, String, Option<String>);
+
+/// Перевіряє залишки резервуарів (крім зарядки). Повертає кількість нових подій.
+pub fn check_low_stock(conn: &mut SqliteConnection) -> QueryResult<usize> {
+    let tanks: Vec<TankRow> = tank::table
+        .inner_join(fuel::table)
+        .order(tank::id)
+        .select((
+            tank::id,
+            tank::fuelid,
+            tank::stored,
+            tank::capacity,
+            tank::low_stock_threshold,
+            fuel::name,
+            fuel::fuel_type,
+        ))
+        .load(conn)?;
+
+    let mut created = 0;
+    for (tank_id, fuel_id, stored, capacity, configured, fuel_name, fuel_type) in tanks {
+        if fuel_type.as_deref() == Some("electricity") {
+            continue;
+        }
+        let key = format!("low_stock:tank:{}", tank_id);
+        let threshold = low_stock_threshold(capacity, configured);
+        match low_stock_severity(stored, threshold) {
+            Some(severity) => {
+                let message = format!(
+                    "Резервуар №{} ({}): залишок {} л із {} л, поріг {} л",
+                    tank_id, fuel_name, stored, capacity, threshold
+                );
+                if raise(conn, KIND_LOW_STOCK, severity, &key, &message, Some(tank_id), Some(fuel_id))? {
+                    crate::webhooks::enqueue(
+                        conn,
+                        crate::webhooks::STOCK_LOW,
+                        serde_json::json!({
+                            "tank_id": tank_id,
+                            "fuel_id": fuel_id,
+                            "fuel_name": fuel_name,
+                            "stored": stored,
+                            "capacity": capacity,
+                            "threshold": threshold,
+                            "severity": severity,
+                        }),
+                    )?;
+                    created += 1;
+                }
+            }
+            None => {
+                resolve(conn, &key)?;
+            }
+        }
+    }
+    Ok(created)
+}
+
+/// Зарядна станція вимкнена (критично) або знову доступна (подія вирішується)
+pub fn charger_status(conn: &mut SqliteConnection, fuel_id: i32, available: bool) -> QueryResult<bool> {
+    let key = format!("charger_offline:fuel:{}", fuel_id);
+    if available {
+        resolve(conn, &key)?;
+        return Ok(false);
+    }
+    let name: String = fuel::table.find(fuel_id).select(fuel::name).first(conn)?;
+    raise(
+        conn,
+        KIND_CHARGER_OFFLINE,
+        "critical",
+        &key,
+        &format!("Зарядна станція {} недоступна", name),
+        None,
+        Some(fuel_id),
+    )
+}
+
+/// Забирає нерозіслані події разом із каналами, що мають їх отримати.
+/// Подія позначається розісланою одразу, тому паралельні розсилки її не дублюють.
+pub fn claim_pending(conn: &mut SqliteConnection) -> QueryResult<Vec<(StaffEvent, Vec<StaffChannel>)>> {
+    let events: Vec<StaffEvent> = staff_event::table
+        .filter(staff_event::dispatched_at.is_null())
+        .order(staff_event::id)
+        .select(StaffEvent::as_select())
+        .load(conn)?;
+    let channels: Vec<StaffChannel> = staff_channel::table
+        .filter(staff_channel::enabled.eq(true))
+        .order(staff_channel::id)
+        .select(StaffChannel::as_select())
+        .load(conn)?;
+
+    let now = chrono::Utc::now().timestamp();
+    let mut claimed = Vec::new();
+    for event in events {
+        let taken = diesel::update(
+            staff_event::table
+                .find(event.id)
+                .filter(staff_event::dispatched_at.is_null()),
+        )
+        .set(staff_event::dispatched_at.eq(Some(now)))
+        .execute(conn)?;
+        if taken == 0 {
+            continue;
+        }
+        let targets = channels
+            .iter()
+            .filter(|c| severity_at_least(&event.severity, &c.min_severity))
+            .cloned()
+            .collect();
+        claimed.push((event, targets));
+    }
+    Ok(claimed)
+}
+
+// ---------- Канали ----------
+
+pub trait EventChannel {
+    async fn deliver(&self, event: &StaffEvent) -> Result<(), String>;
+}
+
+/// Лист на адресу персоналу
+pub struct EmailChannel<N: Notifier> {
+    pub notifier: N,
+    pub to: String,
+}
+
+/// POST із подією в JSON на довільний URL
+pub struct WebhookChannel {
+    pub url: String,
+}
+
+fn severity_label(severity: &str) -> &'static str {
+    match severity {
+        "critical" => "КРИТИЧНО",
+        _ => "Увага",
+    }
+}
+
+impl<N: Notifier> EventChannel for EmailChannel<N> {
+    async fn deliver(&self, event: &StaffEvent) -> Result<(), String> {
+        let message = Message {
+            to: self.to.clone(),
+            subject: format!("GasStation [{}]: {}", severity_label(&event.severity), event.message),
+            body: format!(
+                "{}\n\nПодія №{} ({}), зафіксовано {}.\nПідтвердіть її в розділі «Події» адмінки.",
+                event.message,
+                event.id,
+                event.kind,
+                crate::utils::format_timestamp(event.created_at),
+            ),
+        };
+        self.notifier.send(&message).await.map_err(|e| e.to_string())
+    }
+}
+
+impl EventChannel for WebhookChannel {
+    async fn deliver(&self, event: &StaffEvent) -> Result<(), String> {
+        let body = serde_json::to_string(event).map_err(|e| e.to_string())?;
+        let response = reqwest::Client::new()
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("Помилка з'єднання: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Відповідь {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Канал за записом staff_channel
+pub enum ConfiguredChannel {
+    Email(EmailChannel<ConfiguredNotifier>),
+    Webhook(WebhookChannel),
+}
+
+impl ConfiguredChannel {
+    pub fn from_row(channel: &StaffChannel) -> Option<Self> {
+        match channel.kind.as_str() {
+            "email" => Some(ConfiguredChannel::Email(EmailChannel {
+                notifier: ConfiguredNotifier::from_env(),
+                to: channel.target.clone(),
+            })),
+            "webhook" => Some(ConfiguredChannel::Webhook(WebhookChannel {
+                url: channel.target.clone(),
+            })),
+            _ => None,
+        }
+    }
+}
+
+impl EventChannel for ConfiguredChannel {
+    async fn deliver(&self, event: &StaffEvent) -> Result<(), String> {
+        match self {
+            ConfiguredChannel::Email(c) => c.deliver(event).await,
+            ConfiguredChannel::Webhook(c) => c.deliver(event).await,
+        }
+    }
+}
+
+/// Розсилає нові події в канали; помилки доставки лише логуються.
+/// Повертає кількість розісланих подій.
+pub async fn dispatch_pending() -> Result<usize, String> {
+    let claimed = claim_pending(&mut crate::db::connection()).map_err(|e| e.to_string())?;
+    for (event, channels) in &claimed {
+        for channel in channels {
+            let Some(sender) = ConfiguredChannel::from_row(channel) else {
+                println!("LOG: Unknown staff channel kind {}", channel.kind);
+                continue;
+            };
+            if let Err(e) = sender.deliver(event).await {
+                println!("LOG: Failed to deliver staff event {} to {}: {}", event.id, channel.target, e);
+            }
+        }
+    }
+    Ok(claimed.len())
+}
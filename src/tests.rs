@@ -44,13 +44,240 @@ mod unit_tests {
     }
 
     #[test]
-    fn test_refill_cost_calculation() {
-        let price_per_liter = 10000;
+    fn test_delivery_cost_calculation() {
+        let unit_cost = 5000;
         let liters = 100;
-        let cost = calculate_refill_cost(price_per_liter, liters);
+        let cost = calculate_delivery_cost(unit_cost, liters);
         assert_eq!(cost, 500000);
     }
 
+    #[test]
+    fn test_order_status_transitions() {
+        assert_eq!(order_status_after_delivery(1000, 0), "open");
+        assert_eq!(order_status_after_delivery(1000, 400), "partial");
+        assert_eq!(order_status_after_delivery(1000, 1000), "delivered");
+        assert_eq!(order_status_on_close(400), "closed_short");
+        assert_eq!(order_status_on_close(0), "cancelled");
+    }
+
+    #[test]
+    fn test_fifo_cost_of_goods() {
+        // Старша партія 100 л по 40 грн, новіша 500 л по 45 грн
+        let lots = vec![(100, 4000), (500, 4500)];
+        let (taken, cost) = consume_fifo(&lots, 150);
+        assert_eq!(taken, vec![100, 50]);
+        assert_eq!(cost, 100 * 4000 + 50 * 4500);
+
+        // Нестача партій оцінюється за останньою ціною
+        let (taken, cost) = consume_fifo(&lots, 700);
+        assert_eq!(taken, vec![100, 500]);
+        assert_eq!(cost, 100 * 4000 + 600 * 4500);
+
+        assert_eq!(consume_fifo(&[], 10), (vec![], 0));
+        assert_eq!(margin_percent(10000, 7500), Some(25.0));
+        assert_eq!(margin_percent(0, 0), None);
+    }
+
+    #[test]
+    fn test_time_of_day_pricing() {
+        // Нічний тариф 23:00-07:00 та денна знижка 12:00-14:00
+        let rules = vec![(1380, 420, 650), (720, 840, 750)];
+        assert_eq!(effective_price(800, &rules, 0), 650);
+        assert_eq!(effective_price(800, &rules, 1380), 650);
+        assert_eq!(effective_price(800, &rules, 419), 650);
+        assert_eq!(effective_price(800, &rules, 420), 800);
+        assert_eq!(effective_price(800, &rules, 780), 750);
+        assert_eq!(effective_price(800, &[], 780), 800);
+
+        assert_eq!(parse_time_of_day("22:30"), Some(1350));
+        assert_eq!(parse_time_of_day("24:00"), None);
+        assert_eq!(parse_time_of_day("7"), None);
+        assert_eq!(format_time_of_day(425), "07:05");
+    }
+
+    #[test]
+    fn test_market_linked_price() {
+        // Ринок 55.00, правило −0.50 грн
+        assert_eq!(market_linked_price(5500, -50, Some(4500), 300), (5450, false));
+        // Собівартість 52.00 + 3.00 націнки вище за ринкову ціну
+        assert_eq!(market_linked_price(5500, -50, Some(5200), 300), (5500, true));
+        // Без даних про собівартість обмеження не застосовується
+        assert_eq!(market_linked_price(5500, -50, None, 300), (5450, false));
+
+        assert_eq!(weighted_average_cost(&[(100, 4000), (300, 4400)]), Some(4300));
+        assert_eq!(weighted_average_cost(&[]), None);
+    }
+
+    #[test]
+    fn test_price_rank() {
+        let competitors = [59.99, 59.49, 58.90, 56.50];
+        assert_eq!(price_rank(56.00, &competitors), (1, 5));
+        assert_eq!(price_rank(59.00, &competitors), (3, 5));
+        // Однакова ціна — спільне місце
+        assert_eq!(price_rank(58.90, &competitors), (2, 5));
+        assert_eq!(price_rank(61.00, &competitors), (5, 5));
+        assert_eq!(price_rank(50.00, &[]), (1, 1));
+    }
+
+    #[test]
+    fn test_price_alert_helpers() {
+        assert!(alert_triggered("price_below", Some(5200), 5199, true));
+        assert!(!alert_triggered("price_below", Some(5200), 5200, true));
+        // Дешеве, але відсутнє пальне не привід для сповіщення
+        assert!(!alert_triggered("price_below", Some(5200), 5000, false));
+        assert!(!alert_triggered("price_below", None, 5000, true));
+
+        assert!(alert_triggered("available", None, 1200, true));
+        assert!(!alert_triggered("available", None, 1200, false));
+        assert!(!alert_triggered("unknown", None, 1200, true));
+
+        assert!(is_valid_email("driver@example.com"));
+        assert!(!is_valid_email("driver@example"));
+        assert!(!is_valid_email("@example.com"));
+        assert!(!is_valid_email("a@b@example.com"));
+        assert!(!is_valid_email("driver@example.com>\r\nRCPT TO:<x@y.z"));
+    }
+
+    #[test]
+    fn test_low_stock_helpers() {
+        assert_eq!(low_stock_threshold(10000, None), 2000);
+        assert_eq!(low_stock_threshold(10000, Some(1500)), 1500);
+
+        assert_eq!(low_stock_severity(2001, 2000), None);
+        assert_eq!(low_stock_severity(2000, 2000), Some("warning"));
+        assert_eq!(low_stock_severity(0, 2000), Some("critical"));
+        // Нульовий поріг: попереджень немає, порожній резервуар — критично
+        assert_eq!(low_stock_severity(1, 0), None);
+        assert_eq!(low_stock_severity(0, 0), Some("critical"));
+
+        assert!(severity_at_least("critical", "warning"));
+        assert!(severity_at_least("warning", "warning"));
+        assert!(!severity_at_least("warning", "critical"));
+    }
+
+    #[test]
+    fn test_webhook_helpers() {
+        assert!(webhook_matches("*", "sale.completed"));
+        assert!(webhook_matches("sale.completed, price.changed", "price.changed"));
+        assert!(!webhook_matches("sale.completed", "sale"));
+
+        assert_eq!(webhook_retry_delay(1), Some(30));
+        assert_eq!(webhook_retry_delay(2), Some(60));
+        assert_eq!(webhook_retry_delay(7), Some(1920));
+        assert_eq!(webhook_retry_delay(8), None);
+    }
+
+    #[test]
+    fn test_api_key_helpers() {
+        let raw = "gs_1a2b3c4d_00112233445566778899aabbccddeeff";
+        assert_eq!(split_api_key(raw), Some(("1a2b3c4d", "00112233445566778899aabbccddeeff")));
+        assert_eq!(split_api_key(" gs_1a2b3c4d_00112233445566778899aabbccddeeff\n").map(|p| p.0), Some("1a2b3c4d"));
+        assert_eq!(split_api_key("1a2b3c4d_00112233445566778899aabbccddeeff"), None);
+        assert_eq!(split_api_key("gs_1a2b3c4d_short"), None);
+        assert_eq!(split_api_key("gs_xyz_00112233445566778899aabbccddeeff"), None);
+
+        assert!(has_scope("prices:read, stock:read", "stock:read"));
+        assert!(!has_scope("prices:read", "purchases:create"));
+        assert!(!has_scope("", "prices:read"));
+    }
+
+    #[test]
+    fn test_fleet_helpers() {
+        assert!(fuel_type_allowed(None, "diesel"));
+        assert!(fuel_type_allowed(Some("petrol, diesel"), "diesel"));
+        assert!(!fuel_type_allowed(Some("diesel"), "electricity"));
+
+        assert!(!limit_exceeded(None, 1_000_000, 1));
+        assert!(!limit_exceeded(Some(100_000), 60_000, 40_000));
+        assert!(limit_exceeded(Some(100_000), 60_000, 40_001));
+
+        let (from, to) = month_range("2026-12").unwrap();
+        assert_eq!(from.to_string(), "2026-12-01");
+        assert_eq!(to.to_string(), "2027-01-01");
+        assert_eq!(month_range("2026-13"), None);
+        assert_eq!(month_range("грудень"), None);
+    }
+
+    #[test]
+    fn test_loyalty_points() {
+        // 2 бали за кожні повні 100 грн
+        assert_eq!(loyalty_points(40, 25_000, 0, 2), 4);
+        assert_eq!(loyalty_points(40, 9_999, 0, 2), 0);
+        // За літри і за гривні разом
+        assert_eq!(loyalty_points(40, 25_000, 1, 2), 44);
+        assert_eq!(loyalty_points(10, -100, 0, 5), 0);
+    }
+
+    #[test]
+    fn test_promotion_helpers() {
+        // (пальне, літри, вартість)
+        let lines = [(1, 10, 50_000), (2, 20, 90_000)];
+        assert_eq!(promotion_discounts("percent", 10, None, None, &lines), Some(vec![5_000, 9_000]));
+        assert_eq!(promotion_discounts("per_litre", 200, Some(2), None, &lines), Some(vec![0, 4_000]));
+        // Мінімальний обсяг рахується лише по акційному пальному
+        assert_eq!(promotion_discounts("percent", 10, Some(1), Some(15), &lines), None);
+        assert_eq!(promotion_discounts("percent", 10, Some(3), None, &lines), None);
+        // Знижка не більша за вартість рядка
+        assert_eq!(promotion_discounts("per_litre", 10_000, Some(1), None, &lines), Some(vec![50_000, 0]));
+
+        assert!(in_happy_hour(None, None, 0));
+        assert!(in_happy_hour(Some(600), Some(720), 600));
+        assert!(!in_happy_hour(Some(600), Some(720), 720));
+        // Вікно через північ
+        assert!(in_happy_hour(Some(1380), Some(120), 30));
+        assert!(!in_happy_hour(Some(1380), Some(120), 600));
+    }
+
+    #[test]
+    fn test_vehicle_helpers() {
+        assert_eq!(normalize_plate(" aa 1234-bb "), Some("AA1234BB".to_string()));
+        assert_eq!(normalize_plate("ка 0001 ех"), Some("КА0001ЕХ".to_string()));
+        assert_eq!(normalize_plate("A1"), None);
+        assert_eq!(normalize_plate("AA12;DROP"), None);
+
+        let fill = |items: &[(&str, &str, i32)]| {
+            let items: Vec<(String, String, i32)> = items
+                .iter()
+                .map(|(n, t, a)| (n.to_string(), t.to_string(), *a))
+                .collect();
+            check_vehicle_fill("AA1234BB", "petrol", 50, &items)
+        };
+        assert!(fill(&[("95", "petrol", 30), ("98", "petrol", 20)]).is_ok());
+        assert!(fill(&[("ДП", "diesel", 10)]).unwrap_err().contains("не підходить"));
+        assert!(fill(&[("95", "petrol", 51)]).unwrap_err().contains("перевищує бак"));
+        assert!(check_vehicle_fill("EV", "electricity", 60, &[("Type-2".into(), "electricity".into(), 60)]).is_ok());
+    }
+
+    #[test]
+    fn test_price_chart_helpers() {
+        let points = vec![(0, 5000), (10, 5200)];
+        let steps = step_chart_points(&points, 20);
+        assert_eq!(steps, vec![(0, 5000), (10, 5000), (10, 5200), (20, 5200)]);
+
+        let line = svg_polyline(&steps, (0, 20), (5000, 5200), (100.0, 50.0));
+        assert_eq!(line, "0.0,50.0 50.0,50.0 50.0,0.0 100.0,0.0");
+
+        assert_eq!(market_fuel_name("95"), Some("А-95"));
+        assert_eq!(market_fuel_name("ДП"), Some("Дизель"));
+        assert_eq!(market_fuel_name("Type-2"), None);
+    }
+
+    #[test]
+    fn test_reorder_forecast() {
+        assert_eq!(average_daily_consumption(1400, 14), 100.0);
+        assert_eq!(average_daily_consumption(100, 0), 0.0);
+        assert_eq!(days_until_empty(450, 100.0), Some(4.5));
+        assert_eq!(days_until_empty(450, 0.0), None);
+
+        // До цільових 900 л з 1000 л місткості
+        assert_eq!(suggested_order_qty(200, 1000, 0, 900), 700);
+        // Частина вже замовлена
+        assert_eq!(suggested_order_qty(200, 1000, 500, 900), 200);
+        // Ціль вище місткості обмежується вільним місцем
+        assert_eq!(suggested_order_qty(200, 1000, 0, 5000), 800);
+        assert_eq!(suggested_order_qty(950, 1000, 0, 900), 0);
+    }
+
     #[test]
     fn test_balance_check() {
         let balance1 = 100000;
@@ -69,6 +296,90 @@ mod unit_tests {
         assert!(!has_sufficient_fuel(total, 1500));
     }
 
+    #[test]
+    fn test_reconciliation_variance() {
+        // 1000 л на початок, +500 надійшло, −300 продано
+        let expected = expected_closing_stock(1000.0, 500, 300);
+        assert_eq!(expected, 1200.0);
+
+        // Виміряно 1180 л — нестача 20 л
+        let variance = stock_variance(expected, 1180.0);
+        assert_eq!(variance, -20.0);
+        assert!(!variance_exceeds(variance, 50));
+        assert!(variance_exceeds(variance, 10));
+    }
+
+    #[test]
+    fn test_cumulative_variances_skip_unmeasured_days() {
+        let days = vec![Some(-10.0), None, Some(-15.0), Some(5.0)];
+        assert_eq!(cumulative_variances(&days), vec![-10.0, -10.0, -25.0, -20.0]);
+    }
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} != {} (±{})",
+            actual,
+            expected,
+            tolerance
+        );
+    }
+
+    // Значення VCF з таблиці ASTM D1250 54B
+    #[test]
+    fn test_vcf_gasoline_table_54b() {
+        let petrol = ThermalProperties::for_fuel("petrol", Some(745.0), None).unwrap();
+        assert_close(volume_correction_factor(&petrol, 25.0), 0.9878, 0.0002);
+        assert_close(volume_correction_factor(&petrol, 5.0), 1.0121, 0.0002);
+        assert_close(volume_correction_factor(&petrol, 15.0), 1.0, 1e-12);
+    }
+
+    #[test]
+    fn test_vcf_diesel_table_54b() {
+        let diesel = ThermalProperties::for_fuel("diesel", None, None).unwrap();
+        assert_eq!(diesel.density_15, 835.0);
+        assert_close(volume_correction_factor(&diesel, 25.0), 0.9915, 0.0002);
+        assert_close(volume_correction_factor(&diesel, 0.0), 1.0128, 0.0002);
+    }
+
+    #[test]
+    fn test_volume_at_15c() {
+        let petrol = ThermalProperties::for_fuel("petrol", Some(745.0), None).unwrap();
+        // 10 000 л при 25 °C ≈ 9878 л при 15 °C
+        assert_close(volume_at_15c(10000.0, 25.0, &petrol), 9878.0, 2.0);
+
+        // Явно заданий коефіцієнт має пріоритет
+        let gas = ThermalProperties::for_fuel("gas", None, Some(0.0025)).unwrap();
+        assert_close(volume_at_15c(1000.0, 25.0, &gas), 974.8, 0.5);
+
+        assert!(ThermalProperties::for_fuel("electricity", None, None).is_none());
+    }
+
+    #[test]
+    fn test_parse_strapping_csv() {
+        let csv = "height_mm,volume_l\n0,0\n100,250.5\n200,600\n";
+        let chart = parse_strapping_csv(csv).unwrap();
+        assert_eq!(chart, vec![(0, 0.0), (100, 250.5), (200, 600.0)]);
+
+        let csv = "0;0\n100;250.5\n\n200;600";
+        assert_eq!(parse_strapping_csv(csv).unwrap(), chart);
+
+        assert!(parse_strapping_csv("0,0\n100,50\n90,60").is_err());
+        assert!(parse_strapping_csv("0,0").is_err());
+    }
+
+    #[test]
+    fn test_interpolate_volume() {
+        let chart = vec![(0, 0.0), (100, 250.0), (200, 600.0)];
+        assert_eq!(interpolate_volume(&chart, 0.0), Some(0.0));
+        assert_eq!(interpolate_volume(&chart, 50.0), Some(125.0));
+        assert_eq!(interpolate_volume(&chart, 100.0), Some(250.0));
+        assert_eq!(interpolate_volume(&chart, 150.0), Some(425.0));
+        assert_eq!(interpolate_volume(&chart, 200.0), Some(600.0));
+        assert_eq!(interpolate_volume(&chart, 201.0), None);
+        assert_eq!(interpolate_volume(&chart, -1.0), None);
+    }
+
     // Rate limiting tests
     #[test]
     fn test_rate_limit_allows_within_limit() {
@@ -233,3 +544,1726 @@ mod integration_tests {
         assert!(final_count.is_ok(), "Таблиця customer має існувати");
     }
 }
+
+#[cfg(all(test, feature = "server"))]
+mod forecourt_tests {
+    use crate::forecourt::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let frame = encode_frame(3, b'A', "00001000005895");
+        let (pump, cmd, data) = decode_frame(&frame).unwrap();
+        assert_eq!(pump, 3);
+        assert_eq!(cmd, b'A');
+        assert_eq!(data, "00001000005895");
+    }
+
+    #[test]
+    fn test_frame_bad_checksum() {
+        let mut frame = encode_frame(1, b'P', "");
+        let last = frame.len() - 1;
+        frame[last] = if frame[last] == b'0' { b'1' } else { b'0' };
+        assert!(decode_frame(&frame).is_err());
+    }
+
+    #[test]
+    fn test_frame_multibyte_command_is_error() {
+        // 'Ж' (D0 96) починається там, де чекаємо код команди; контрольна сума правильна
+        let body = [b'0', b'1', 0xD0, 0x96, 0x03];
+        let bcc = body.iter().fold(0u8, |acc, b| acc ^ b);
+        let mut frame = vec![0x02];
+        frame.extend_from_slice(&body);
+        frame.extend_from_slice(format!("{:02X}", bcc).as_bytes());
+        assert!(decode_frame(&frame).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dispense_flow_with_simulator() {
+        let config = SimulatorConfig {
+            pumps: 2,
+            flow_per_tick_cl: 100,
+            tick: Duration::from_millis(5),
+        };
+        let addr = spawn_simulator("127.0.0.1:0", config).await.unwrap();
+        let mut client = DispenserClient::connect(&addr.to_string()).await.unwrap();
+
+        // 10 л по 58.95 грн
+        let mut ticks = Vec::new();
+        let result = dispense(&mut client, 1, 1000, 5895, Duration::from_millis(2), |s| {
+            ticks.push(s.volume_cl)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result.volume_cl, 1000);
+        assert_eq!(result.amount, 58950);
+        assert_eq!(result.totals.volume_cl, 1000);
+        assert_eq!(result.totals.amount, 58950);
+        // Обсяг зростає монотонно під час відпуску
+        assert!(ticks.windows(2).all(|w| w[0] <= w[1]));
+
+        // Тоталізатори накопичуються між відпусками
+        let second = dispense(&mut client, 1, 500, 5895, Duration::from_millis(2), |_| {})
+            .await
+            .unwrap();
+        assert_eq!(second.totals.volume_cl, 1500);
+    }
+
+    #[tokio::test]
+    async fn test_stop_and_unknown_pump() {
+        let config = SimulatorConfig {
+            pumps: 1,
+            flow_per_tick_cl: 10,
+            tick: Duration::from_millis(20),
+        };
+        let addr = spawn_simulator("127.0.0.1:0", config).await.unwrap();
+        let mut client = DispenserClient::connect(&addr.to_string()).await.unwrap();
+
+        assert!(matches!(
+            client.authorize(7, 1000, 5000).await,
+            Err(ForecourtError::Rejected(1))
+        ));
+
+        client.authorize(1, 100_000, 5000).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(70)).await;
+        client.stop(1).await.unwrap();
+
+        let status = client.poll(1).await.unwrap();
+        assert_eq!(status.state, PumpState::Finished);
+        assert!(status.volume_cl > 0 && status.volume_cl < 100_000);
+        let totals = client.read_totals(1).await.unwrap();
+        assert_eq!(totals.volume_cl, status.volume_cl as u64);
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod atg_tests {
+    use crate::atg::*;
+    use chrono::NaiveDate;
+    use std::time::Duration;
+
+    fn sample_tank(number: u8, volume: f64) -> TankInventory {
+        TankInventory {
+            tank_number: number,
+            product_code: '1',
+            status: 0,
+            volume,
+            tc_volume: volume - 12.5,
+            ullage: 5000.0 - volume,
+            height: 1250.5,
+            water_height: 8.0,
+            temperature: 14.5,
+            water_volume: 3.0,
+        }
+    }
+
+    fn sample_report() -> InventoryReport {
+        InventoryReport {
+            timestamp: NaiveDate::from_ymd_opt(2026, 10, 18)
+                .unwrap()
+                .and_hms_opt(9, 30, 0)
+                .unwrap(),
+            tanks: vec![sample_tank(1, 3150.0), sample_tank(2, 812.25)],
+        }
+    }
+
+    #[test]
+    fn test_inventory_report_roundtrip() {
+        let report = sample_report();
+        let frame = encode_inventory_report(&report);
+        let parsed = parse_inventory_report(&frame).unwrap();
+        assert_eq!(parsed, report);
+    }
+
+    #[test]
+    fn test_inventory_report_known_float_encoding() {
+        let frame = encode_inventory_report(&sample_report());
+        let text = String::from_utf8_lossy(&frame);
+        assert!(text.starts_with("\u{1}i201002610180930"));
+        // 3150.0 = 0x4544E000
+        assert!(text.contains("0110000074544E000"));
+    }
+
+    #[test]
+    fn test_inventory_report_rejects_corruption() {
+        let mut frame = encode_inventory_report(&sample_report());
+        frame[20] = b'F';
+        assert!(parse_inventory_report(&frame).is_err());
+    }
+
+    #[test]
+    fn test_inventory_report_rejects_non_ascii() {
+        // Кадр з правильною контрольною сумою навколо довільного тіла
+        let framed = |body: &str| {
+            let mut frame = vec![0x01];
+            frame.extend_from_slice(body.as_bytes());
+            frame.extend_from_slice(b"&&");
+            let sum = frame.iter().fold(0u16, |acc, b| acc.wrapping_add(*b as u16));
+            frame.extend_from_slice(format!("{:04X}", (!sum).wrapping_add(1)).as_bytes());
+            frame.push(0x03);
+            frame
+        };
+        assert!(parse_inventory_report(&framed("i20ЖЖЖЖЖЖЖЖЖЖЖЖЖ")).is_err());
+        assert!(parse_inventory_report(&framed("i2010026101809300Ж000070")).is_err());
+        assert!(parse_inventory_report(&framed("i201002610180930")).unwrap().tanks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_listener_receives_simulated_reports() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let addr = listen("127.0.0.1:0", move |report| {
+            tx.send(report).ok();
+        })
+        .await
+        .unwrap();
+
+        run_simulator(&addr.to_string(), Duration::from_millis(5), 2, || {
+            vec![sample_tank(3, 1000.0)]
+        })
+        .await
+        .unwrap();
+
+        for _ in 0..2 {
+            let report = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(report.tanks.len(), 1);
+            assert_eq!(report.tanks[0].tank_number, 3);
+            assert_eq!(report.tanks[0].volume, 1000.0);
+            assert_eq!(report.tanks[0].temperature, 14.5);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod scheduler_tests {
+    use crate::scheduler::CronSchedule;
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cron_matches() {
+        let every_5 = CronSchedule::parse("*/5 * * * *").unwrap();
+        assert!(every_5.matches(&at(2026, 10, 18, 12, 35)));
+        assert!(!every_5.matches(&at(2026, 10, 18, 12, 36)));
+
+        // Будні о 06:30 (18.10.2026 — неділя)
+        let weekdays = CronSchedule::parse("30 6 * * 1-5").unwrap();
+        assert!(weekdays.matches(&at(2026, 10, 19, 6, 30)));
+        assert!(!weekdays.matches(&at(2026, 10, 18, 6, 30)));
+
+        // 7 — теж неділя
+        let sunday = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert!(sunday.matches(&at(2026, 10, 18, 0, 0)));
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let daily = CronSchedule::parse("@daily").unwrap();
+        assert_eq!(daily.next_after(at(2026, 10, 18, 12, 0)), Some(at(2026, 10, 19, 0, 0)));
+
+        let late = CronSchedule::parse("55 23 * * *").unwrap();
+        assert_eq!(late.next_after(at(2026, 10, 18, 23, 55)), Some(at(2026, 10, 19, 23, 55)));
+
+        let steps = CronSchedule::parse("10-50/20 8,20 * * *").unwrap();
+        assert_eq!(steps.next_after(at(2026, 10, 18, 8, 30)), Some(at(2026, 10, 18, 8, 50)));
+        assert_eq!(steps.next_after(at(2026, 10, 18, 8, 50)), Some(at(2026, 10, 18, 20, 10)));
+
+        // 29 лютого — лише у високосний рік
+        let leap = CronSchedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(leap.next_after(at(2026, 10, 18, 0, 0)), Some(at(2028, 2, 29, 0, 0)));
+    }
+
+    #[test]
+    fn test_cron_invalid() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("5-1 * * * *").is_err());
+        assert!(CronSchedule::parse("a * * * *").is_err());
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod pricing_tests {
+    use crate::pricing;
+    use crate::schema::{fuel, price_history, price_rule, price_schedule};
+    use diesel::prelude::*;
+
+    #[test]
+    #[serial_test::serial]
+    fn test_due_schedule_applied_once() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::delete(price_rule::table).execute(conn)?;
+            let fuel_id: i32 = fuel::table.order(fuel::id).select(fuel::id).first(conn)?;
+            let now = chrono::Utc::now().timestamp();
+            diesel::insert_into(price_schedule::table)
+                .values((
+                    price_schedule::fuel_id.eq(fuel_id),
+                    price_schedule::price.eq(12_345),
+                    price_schedule::effective_at.eq(now - 60),
+                    price_schedule::created_by.eq("admin"),
+                    price_schedule::created_at.eq(now - 120),
+                ))
+                .execute(conn)?;
+
+            assert_eq!(pricing::apply_due_schedules(conn)?, 1);
+            assert_eq!(pricing::apply_due_schedules(conn)?, 0);
+            let price: i64 = fuel::table.find(fuel_id).select(fuel::price).first(conn)?;
+            assert_eq!(price, 12_345);
+            let entries: i64 = price_history::table
+                .filter(price_history::fuel_id.eq(fuel_id))
+                .filter(price_history::source.eq("schedule"))
+                .count()
+                .get_result(conn)?;
+            assert_eq!(entries, 1);
+            Ok(())
+        });
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod market_tests {
+    use crate::market::{parse_minfin_html, parse_quotes_csv, summarize};
+
+    const MINFIN_HTML: &str = include_str!("../fixtures/minfin_fuel_tm.html");
+    const MINFIN_CHANGED_HTML: &str = include_str!("../fixtures/minfin_fuel_tm_changed.html");
+    const MARKET_CSV: &str = include_str!("../fixtures/market_prices.csv");
+
+    #[test]
+    fn test_parse_minfin_fixture() {
+        let quotes = parse_minfin_html(MINFIN_HTML).unwrap();
+        // 5 мереж, прочерки пропущено, підсумковий рядок не враховано
+        assert_eq!(quotes.len(), 21);
+        assert!(quotes.iter().all(|q| q.brand != "Середня ціна"));
+
+        let okko_95 = quotes.iter().find(|q| q.brand == "ОККО" && q.fuel == "А-95").unwrap();
+        assert_eq!(okko_95.price, 59.99);
+        assert!(!quotes.iter().any(|q| q.brand == "WOG" && q.fuel == "А-92"));
+
+        let stats = summarize(&quotes);
+        let names: Vec<&str> = stats.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["А-95 Преміум", "А-95", "А-92", "Дизель", "Газ"]);
+
+        let a92 = &stats[2];
+        assert_eq!(a92.count, 3);
+        assert_eq!(a92.min, 54.50);
+        assert_eq!(a92.max, 55.90);
+        assert!((a92.average - 55.2966).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_parse_minfin_layout_change() {
+        let err = parse_minfin_html(MINFIN_CHANGED_HTML).unwrap_err();
+        assert!(err.contains("Змінилась структура"));
+
+        assert!(parse_minfin_html("<html><body><p>Технічні роботи</p></body></html>").is_err());
+    }
+
+    #[test]
+    fn test_parse_quotes_csv() {
+        let quotes = parse_quotes_csv(MARKET_CSV).unwrap();
+        assert_eq!(quotes.len(), 4);
+        assert_eq!(quotes[3].brand, "Місцева АЗС");
+        assert_eq!(quotes[3].price, 34.50);
+
+        let stats = summarize(&quotes);
+        let a95 = stats.iter().find(|s| s.name == "А-95").unwrap();
+        assert_eq!(a95.count, 2);
+
+        assert!(parse_quotes_csv("ОККО,А-98,60.00").is_err());
+        assert!(parse_quotes_csv("ОККО,А-95").is_err());
+        assert!(parse_quotes_csv("# порожньо\n").is_err());
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod notification_tests {
+    use crate::notify::*;
+
+    fn message(body: &str) -> Message {
+        Message {
+            to: "driver@example.com".to_string(),
+            subject: "GasStation: 95".to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_smtp_delivery_to_sink() {
+        let (addr, sink) = spawn_mail_sink("127.0.0.1:0").await.unwrap();
+        let notifier = SmtpNotifier {
+            addr: addr.to_string(),
+            from: "GasStation <noreply@gasstation.local>".to_string(),
+        };
+
+        notifier
+            .send(&Message {
+                subject: "Ціна знизилась".to_string(),
+                ..message("95 подешевшало до 51.90 грн\n.крапка на початку рядка")
+            })
+            .await
+            .unwrap();
+
+        let received = sink.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].from, "noreply@gasstation.local");
+        assert_eq!(received[0].to, vec!["driver@example.com".to_string()]);
+
+        let data = &received[0].data;
+        assert!(data.contains("To: driver@example.com\r\n"));
+        // Кирилиця в темі — encoded-word
+        assert!(data.contains("Subject: =?UTF-8?B?"));
+        assert!(data.contains("\r\n\r\n95 подешевшало до 51.90 грн\r\n"));
+        // Крапку, подвоєну клієнтом, приймач прибрав
+        assert!(data.ends_with("\r\n.крапка на початку рядка\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_smtp_rejected_recipient() {
+        let (addr, sink) = spawn_mail_sink("127.0.0.1:0").await.unwrap();
+        let notifier = SmtpNotifier {
+            addr: addr.to_string(),
+            from: "noreply@gasstation.local".to_string(),
+        };
+
+        let err = notifier
+            .send(&Message {
+                to: "nobody".to_string(),
+                ..message("текст")
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, NotifyError::Smtp(550, _)));
+        assert!(sink.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_format_message_dot_stuffing() {
+        let data = format_message("noreply@gasstation.local", &message("перший\n.другий\n..третій"));
+        assert!(data.contains("Subject: GasStation: 95\r\n"));
+        assert!(data.ends_with("\r\nперший\r\n..другий\r\n...третій\r\n"));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_price_alert_evaluation() {
+        use crate::models::NewCustomer;
+        use crate::schema::{customer, fuel, notification, price_alert, tank};
+        use diesel::prelude::*;
+
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            // Пальне, що є в наявності
+            let (fuel_id, price): (i32, i64) = fuel::table
+                .inner_join(tank::table)
+                .filter(tank::stored.gt(0))
+                .select((fuel::id, fuel::price))
+                .first(conn)?;
+
+            diesel::insert_into(customer::table)
+                .values(&NewCustomer {
+                    login: "alert_test",
+                    password: "hash",
+                    salt: "bcrypt",
+                    balance: 0,
+                })
+                .execute(conn)?;
+            let customer_id: i32 = customer::table
+                .filter(customer::login.eq("alert_test"))
+                .select(customer::id)
+                .first(conn)?;
+            diesel::update(customer::table.find(customer_id))
+                .set(customer::email.eq(Some("alert_test@example.com")))
+                .execute(conn)?;
+
+            for threshold in [price + 100, price - 100] {
+                diesel::insert_into(price_alert::table)
+                    .values((
+                        price_alert::customer_id.eq(customer_id),
+                        price_alert::fuel_id.eq(fuel_id),
+                        price_alert::kind.eq("price_below"),
+                        price_alert::threshold.eq(Some(threshold)),
+                        price_alert::created_at.eq(0),
+                    ))
+                    .execute(conn)?;
+            }
+
+            let messages = crate::alerts::evaluate(conn)?;
+            let ours: Vec<_> = messages.iter().filter(|m| m.to == "alert_test@example.com").collect();
+            assert_eq!(ours.len(), 1);
+
+            // Спрацювала лише підписка з порогом вище поточної ціни
+            let active: Vec<(Option<i64>, bool)> = price_alert::table
+                .filter(price_alert::customer_id.eq(customer_id))
+                .order(price_alert::id)
+                .select((price_alert::threshold, price_alert::active))
+                .load(conn)?;
+            assert_eq!(active, vec![(Some(price + 100), false), (Some(price - 100), true)]);
+
+            let notes: i64 = notification::table
+                .filter(notification::customer_id.eq(customer_id))
+                .count()
+                .get_result(conn)?;
+            assert_eq!(notes, 1);
+
+            // Повторна перевірка не дублює сповіщення
+            let again = crate::alerts::evaluate(conn)?;
+            assert!(again.iter().all(|m| m.to != "alert_test@example.com"));
+            Ok(())
+        });
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod live_tests {
+    use crate::live;
+    use crate::schema::fuel;
+    use diesel::prelude::*;
+
+    #[test]
+    #[serial_test::serial]
+    fn test_publish_only_changes() {
+        dotenvy::dotenv().ok();
+        let mut conn = crate::db::connection();
+        let mut updates = live::subscribe();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let fuels = live::load_fuels(conn)?;
+            assert!(!fuels.is_empty());
+
+            // Перша розсилка — стан усіх пальних, повторна без змін — нічого
+            live::publish(conn)?;
+            while updates.try_recv().is_ok() {}
+            assert_eq!(live::publish(conn)?, 0);
+
+            let first = &fuels[0];
+            diesel::update(fuel::table.find(first.id))
+                .set(fuel::price.eq(first.price + 10))
+                .execute(conn)?;
+            assert_eq!(live::publish(conn)?, 1);
+
+            let update = updates.try_recv().unwrap();
+            assert_eq!(update.id, first.id);
+            assert_eq!(update.price, first.price + 10);
+            assert_eq!(update.stored, first.stored);
+            assert!(updates.try_recv().is_err());
+            Ok(())
+        });
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod staff_tests {
+    use crate::models::StaffEvent;
+    use crate::schema::{fuel, staff_channel, staff_event, tank};
+    use crate::staff;
+    use diesel::prelude::*;
+    use diesel::SqliteConnection;
+
+    fn open_event(conn: &mut SqliteConnection, key: &str) -> QueryResult<Option<StaffEvent>> {
+        staff_event::table
+            .filter(staff_event::dedup_key.eq(key))
+            .filter(staff_event::resolved_at.is_null())
+            .select(StaffEvent::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_low_stock_dedup_and_resolve() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let (tank_id, capacity): (i32, i32) = tank::table
+                .inner_join(fuel::table)
+                .filter(fuel::fuel_type.ne("electricity").or(fuel::fuel_type.is_null()))
+                .select((tank::id, tank::capacity))
+                .first(conn)?;
+            let key = format!("low_stock:tank:{}", tank_id);
+            diesel::update(tank::table.find(tank_id))
+                .set((tank::stored.eq(capacity / 10), tank::low_stock_threshold.eq(None::<i32>)))
+                .execute(conn)?;
+
+            staff::check_low_stock(conn)?;
+            let event = open_event(conn, &key)?.unwrap();
+            assert_eq!(event.severity, "warning");
+            assert_eq!(event.occurrences, 1);
+
+            // Повтор не створює нової події
+            staff::check_low_stock(conn)?;
+            let again = open_event(conn, &key)?.unwrap();
+            assert_eq!(again.id, event.id);
+            assert_eq!(again.occurrences, 2);
+
+            // Після підтвердження подія лишається активною, поки залишок низький
+            assert_eq!(staff::acknowledge(conn, event.id, "admin")?, 1);
+            assert_eq!(staff::acknowledge(conn, event.id, "admin")?, 0);
+            staff::check_low_stock(conn)?;
+            assert_eq!(open_event(conn, &key)?.unwrap().id, event.id);
+
+            // Порожній резервуар — підвищення рівня і повторне сповіщення
+            diesel::update(tank::table.find(tank_id)).set(tank::stored.eq(0)).execute(conn)?;
+            staff::check_low_stock(conn)?;
+            let critical = open_event(conn, &key)?.unwrap();
+            assert_eq!(critical.id, event.id);
+            assert_eq!(critical.severity, "critical");
+            assert_eq!(critical.acknowledged_at, None);
+            assert_eq!(critical.dispatched_at, None);
+
+            // Поповнення вирішує подію; наступне падіння — нова подія
+            diesel::update(tank::table.find(tank_id)).set(tank::stored.eq(capacity)).execute(conn)?;
+            staff::check_low_stock(conn)?;
+            assert!(open_event(conn, &key)?.is_none());
+
+            diesel::update(tank::table.find(tank_id))
+                .set((tank::stored.eq(capacity / 2), tank::low_stock_threshold.eq(Some(capacity / 2))))
+                .execute(conn)?;
+            staff::check_low_stock(conn)?;
+            assert_ne!(open_event(conn, &key)?.unwrap().id, event.id);
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_claim_pending_routes_by_severity() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            // Чужі нерозіслані події не заважають перевірці
+            diesel::update(staff_event::table.filter(staff_event::dispatched_at.is_null()))
+                .set(staff_event::dispatched_at.eq(Some(0i64)))
+                .execute(conn)?;
+            diesel::update(staff_channel::table).set(staff_channel::enabled.eq(false)).execute(conn)?;
+            diesel::insert_into(staff_channel::table)
+                .values(&vec![
+                    (
+                        staff_channel::kind.eq("email"),
+                        staff_channel::target.eq("staff@example.com"),
+                        staff_channel::min_severity.eq("warning"),
+                    ),
+                    (
+                        staff_channel::kind.eq("webhook"),
+                        staff_channel::target.eq("http://127.0.0.1:9/hook"),
+                        staff_channel::min_severity.eq("critical"),
+                    ),
+                ])
+                .execute(conn)?;
+
+            assert!(staff::raise(conn, staff::KIND_VARIANCE, "warning", "test:w", "w", None, None)?);
+            assert!(staff::raise(conn, staff::KIND_REFILL_FAILED, "critical", "test:c", "c", None, None)?);
+            assert!(!staff::raise(conn, staff::KIND_REFILL_FAILED, "critical", "test:c", "c", None, None)?);
+
+            let claimed = staff::claim_pending(conn)?;
+            let routes: Vec<(String, Vec<String>)> = claimed
+                .into_iter()
+                .map(|(e, channels)| (e.dedup_key, channels.into_iter().map(|c| c.kind).collect()))
+                .collect();
+            assert_eq!(
+                routes,
+                vec![
+                    ("test:w".to_string(), vec!["email".to_string()]),
+                    ("test:c".to_string(), vec!["email".to_string(), "webhook".to_string()]),
+                ]
+            );
+            assert!(staff::claim_pending(conn)?.is_empty());
+            Ok(())
+        });
+    }
+
+    #[tokio::test]
+    async fn test_email_channel_to_sink() {
+        use crate::notify::{spawn_mail_sink, SmtpNotifier};
+        use crate::staff::{EmailChannel, EventChannel};
+
+        let (addr, sink) = spawn_mail_sink("127.0.0.1:0").await.unwrap();
+        let channel = EmailChannel {
+            notifier: SmtpNotifier {
+                addr: addr.to_string(),
+                from: "noreply@gasstation.local".to_string(),
+            },
+            to: "staff@example.com".to_string(),
+        };
+        let event = StaffEvent {
+            id: 7,
+            kind: staff::KIND_CHARGER_OFFLINE.to_string(),
+            severity: "critical".to_string(),
+            dedup_key: "charger_offline:fuel:5".to_string(),
+            message: "Зарядна станція Електро недоступна".to_string(),
+            tank_id: None,
+            fuel_id: Some(5),
+            occurrences: 1,
+            created_at: 0,
+            last_seen_at: 0,
+            dispatched_at: None,
+            resolved_at: None,
+            acknowledged_by: None,
+            acknowledged_at: None,
+        };
+
+        channel.deliver(&event).await.unwrap();
+        let received = sink.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].to, vec!["staff@example.com".to_string()]);
+        assert!(received[0].data.contains("Зарядна станція Електро недоступна\r\n"));
+        assert!(received[0].data.contains("Подія №7"));
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod webhook_tests {
+    use crate::models::{WebhookDelivery, WebhookSubscription};
+    use crate::schema::{webhook_delivery, webhook_subscription};
+    use crate::webhooks::{self, DeliveryError};
+    use diesel::prelude::*;
+
+    #[test]
+    fn test_sign_known_vector() {
+        // Еталон: python3 hmac.new(b"whsec_test", b"1700000000.{...}", sha256)
+        assert_eq!(
+            webhooks::sign("whsec_test", 1700000000, r#"{"event":"sale.completed"}"#),
+            "d413d2bff73060ecff054003cc9ac0642fff30f9f36a2902c8db7d9a25e29ea5"
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_enqueue_claim_and_backoff() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::update(webhook_subscription::table)
+                .set(webhook_subscription::enabled.eq(false))
+                .execute(conn)?;
+            diesel::update(webhook_delivery::table.filter(webhook_delivery::status.eq("pending")))
+                .set(webhook_delivery::status.eq("failed"))
+                .execute(conn)?;
+            for (url, events, enabled) in [
+                ("http://127.0.0.1:9/all", "*", true),
+                ("http://127.0.0.1:9/prices", "price.changed", true),
+                ("http://127.0.0.1:9/off", "*", false),
+            ] {
+                diesel::insert_into(webhook_subscription::table)
+                    .values((
+                        webhook_subscription::url.eq(url),
+                        webhook_subscription::secret.eq("s"),
+                        webhook_subscription::events.eq(events),
+                        webhook_subscription::enabled.eq(enabled),
+                        webhook_subscription::created_at.eq(0),
+                    ))
+                    .execute(conn)?;
+            }
+
+            assert_eq!(webhooks::enqueue(conn, webhooks::SALE_COMPLETED, serde_json::json!({ "sale_id": 1 }))?, 1);
+            assert_eq!(webhooks::enqueue(conn, webhooks::PRICE_CHANGED, serde_json::json!({ "fuel_id": 1 }))?, 2);
+
+            let now = chrono::Utc::now().timestamp() + 1;
+            let claimed: Vec<(WebhookDelivery, WebhookSubscription)> = webhooks::claim_due(conn, now)?;
+            assert_eq!(claimed.len(), 3);
+            let payload: serde_json::Value = serde_json::from_str(&claimed[0].0.payload).unwrap();
+            assert_eq!(payload["event"], "sale.completed");
+            assert_eq!(payload["data"]["sale_id"], 1);
+            // Взяті в роботу доставки не видаються повторно
+            assert!(webhooks::claim_due(conn, now)?.is_empty());
+
+            let (delivery, _) = &claimed[0];
+            webhooks::record_result(conn, delivery, &Err(DeliveryError::Status(500)), now)?;
+            let after: WebhookDelivery = webhook_delivery::table
+                .find(delivery.id)
+                .select(WebhookDelivery::as_select())
+                .first(conn)?;
+            assert_eq!(after.status, "pending");
+            assert_eq!(after.attempts, 1);
+            assert_eq!(after.next_attempt_at, now + 30);
+            assert_eq!(after.last_status_code, Some(500));
+
+            // Остання дозволена спроба — доставку припинено
+            let exhausted = WebhookDelivery { attempts: 7, ..after };
+            webhooks::record_result(conn, &exhausted, &Err(DeliveryError::Http("timeout".to_string())), now)?;
+            let status: String = webhook_delivery::table
+                .find(delivery.id)
+                .select(webhook_delivery::status)
+                .first(conn)?;
+            assert_eq!(status, "failed");
+
+            webhooks::record_result(conn, &claimed[1].0, &Ok(204), now)?;
+            let (status, delivered_at): (String, Option<i64>) = webhook_delivery::table
+                .find(claimed[1].0.id)
+                .select((webhook_delivery::status, webhook_delivery::delivered_at))
+                .first(conn)?;
+            assert_eq!((status.as_str(), delivered_at), ("delivered", Some(now)));
+            Ok(())
+        });
+    }
+
+    #[tokio::test]
+    async fn test_send_signed_request() {
+        use axum::{extract::State, http::HeaderMap, routing::post, Router};
+        use std::sync::{Arc, Mutex};
+
+        type Received = Arc<Mutex<Vec<(HeaderMap, String)>>>;
+        let received: Received = Arc::default();
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(|State(r): State<Received>, headers: HeaderMap, body: String| async move {
+                    r.lock().unwrap().push((headers, body));
+                    axum::http::StatusCode::NO_CONTENT
+                }),
+            )
+            .route("/broken", post(|| async { axum::http::StatusCode::INTERNAL_SERVER_ERROR }))
+            .with_state(received.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let subscription = WebhookSubscription {
+            id: 1,
+            url: format!("http://{}/hook", addr),
+            secret: "whsec_test".to_string(),
+            events: "*".to_string(),
+            enabled: true,
+            created_at: 0,
+        };
+        let delivery = WebhookDelivery {
+            id: 42,
+            subscription_id: 1,
+            event: "price.changed".to_string(),
+            payload: r#"{"event":"price.changed","data":{"fuel_id":1}}"#.to_string(),
+            status: "pending".to_string(),
+            attempts: 0,
+            next_attempt_at: 0,
+            last_status_code: None,
+            last_error: None,
+            created_at: 0,
+            delivered_at: None,
+        };
+
+        assert_eq!(webhooks::send(&subscription, &delivery).await.unwrap(), 204);
+        let (headers, body) = received.lock().unwrap()[0].clone();
+        assert_eq!(body, delivery.payload);
+        assert_eq!(headers["x-gasstation-event"], "price.changed");
+        assert_eq!(headers["x-gasstation-delivery"], "42");
+        let timestamp: i64 = headers["x-gasstation-timestamp"].to_str().unwrap().parse().unwrap();
+        assert_eq!(
+            headers["x-gasstation-signature"].to_str().unwrap(),
+            format!("sha256={}", webhooks::sign("whsec_test", timestamp, &body))
+        );
+
+        let broken = WebhookSubscription {
+            url: format!("http://{}/broken", addr),
+            ..subscription
+        };
+        assert!(matches!(
+            webhooks::send(&broken, &delivery).await,
+            Err(DeliveryError::Status(500))
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod api_key_tests {
+    use super::fixtures::{customer_with_full_tanks, fuel_of_type};
+    use crate::api_keys::{self, KeyError, ROTATION_GRACE_SECS};
+    use crate::models::ApiKey;
+    use crate::schema::api_key;
+    use diesel::prelude::*;
+
+    fn scopes(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_issue_and_authenticate() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let (key, raw) = api_keys::issue(conn, "ERP", &scopes(&["prices:read"]), None, 60, "admin")?;
+            assert!(raw.starts_with(&format!("gs_{}_", key.prefix)));
+            // У БД лише хеш секрету
+            let stored: String = api_key::table.find(key.id).select(api_key::key_hash).first(conn)?;
+            assert!(!raw.contains(&stored));
+
+            let now = 1_700_000_000;
+            let ok = api_keys::authenticate(conn, &raw, "prices:read", now).unwrap();
+            assert_eq!(ok.id, key.id);
+            let used: Option<i64> = api_key::table.find(key.id).select(api_key::last_used_at).first(conn)?;
+            assert_eq!(used, Some(now));
+
+            assert_eq!(
+                api_keys::authenticate(conn, &raw, "stock:read", now),
+                Err(KeyError::MissingScope("stock:read".to_string()))
+            );
+            let forged = format!("gs_{}_{}", key.prefix, "0".repeat(40));
+            assert_eq!(api_keys::authenticate(conn, &forged, "prices:read", now), Err(KeyError::Invalid));
+            assert_eq!(api_keys::authenticate(conn, "session-token", "prices:read", now), Err(KeyError::Invalid));
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_rotation_grace_and_revoke() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let (key, old) = api_keys::issue(conn, "Fleet", &scopes(&["stock:read"]), None, 1000, "admin")?;
+            let now = chrono::Utc::now().timestamp();
+            let new = api_keys::rotate(conn, key.id, now)?.unwrap();
+            assert_ne!(new, old);
+            assert!(new.starts_with(&format!("gs_{}_", key.prefix)));
+
+            // Старий секрет діє до кінця пільгового періоду
+            assert!(api_keys::authenticate(conn, &new, "stock:read", now).is_ok());
+            assert!(api_keys::authenticate(conn, &old, "stock:read", now + 60).is_ok());
+            assert_eq!(
+                api_keys::authenticate(conn, &old, "stock:read", now + ROTATION_GRACE_SECS),
+                Err(KeyError::Invalid)
+            );
+
+            assert_eq!(api_keys::revoke(conn, key.id, now)?, 1);
+            assert_eq!(api_keys::authenticate(conn, &new, "stock:read", now), Err(KeyError::Revoked));
+            assert_eq!(api_keys::authenticate(conn, &old, "stock:read", now), Err(KeyError::Invalid));
+            assert_eq!(api_keys::rotate(conn, key.id, now)?, None);
+
+            let revoked: ApiKey = api_key::table.find(key.id).select(ApiKey::as_select()).first(conn)?;
+            assert!(revoked.revoked_at.is_some());
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_per_key_rate_limit() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let now = chrono::Utc::now().timestamp();
+            let (_, slow) = api_keys::issue(conn, "Slow", &scopes(&["prices:read"]), None, 2, "admin")?;
+            let (_, other) = api_keys::issue(conn, "Other", &scopes(&["prices:read"]), None, 2, "admin")?;
+
+            assert!(api_keys::authenticate(conn, &slow, "prices:read", now).is_ok());
+            assert!(api_keys::authenticate(conn, &slow, "prices:read", now).is_ok());
+            assert_eq!(api_keys::authenticate(conn, &slow, "prices:read", now), Err(KeyError::RateLimited));
+            // Ліміт рахується окремо для кожного ключа
+            assert!(api_keys::authenticate(conn, &other, "prices:read", now).is_ok());
+            Ok(())
+        });
+    }
+
+    // Покупка за ключем автопарку списується з прив'язаного рахунку (як у POST /api/v1/purchases)
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_fleet_key_purchase() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let customer_id = customer_with_full_tanks(conn, "fleet_api_key_test", 10_000_000)?;
+            let (fuel_id, price) = fuel_of_type(conn, "petrol")?;
+            let (_, raw) = api_keys::issue(
+                conn,
+                "Fleet",
+                &scopes(&["purchases:create"]),
+                Some(customer_id),
+                100,
+                "admin",
+            )?;
+
+            let now = chrono::Utc::now().timestamp();
+            let key = api_keys::authenticate(conn, &raw, "purchases:create", now).unwrap();
+            assert_eq!(key.customer_id, Some(customer_id));
+            let balance = crate::complete_purchase(conn, customer_id, vec![(fuel_id, 1)], None, 0, None).unwrap();
+            assert_eq!(balance, 10_000_000 - price);
+            Ok(())
+        });
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod fixtures {
+    use crate::schema::{customer, fuel, tank};
+    use diesel::prelude::*;
+    use diesel::SqliteConnection;
+
+    // Клієнт із заданим балансом; резервуари заповнені, щоб пального вистачало на будь-яку покупку
+    pub fn customer_with_full_tanks(conn: &mut SqliteConnection, login: &str, balance: i64) -> QueryResult<i32> {
+        let customer_id = diesel::insert_into(customer::table)
+            .values((
+                customer::login.eq(login),
+                customer::password.eq("-"),
+                customer::salt.eq("bcrypt"),
+                customer::balance.eq(balance),
+            ))
+            .returning(customer::id)
+            .get_result(conn)?;
+        diesel::update(tank::table)
+            .set(tank::stored.eq(tank::capacity))
+            .execute(conn)?;
+        Ok(customer_id)
+    }
+
+    // Перше пальне заданого типу: (id, ціна)
+    pub fn fuel_of_type(conn: &mut SqliteConnection, fuel_type: &str) -> QueryResult<(i32, i64)> {
+        fuel::table
+            .filter(fuel::fuel_type.eq(fuel_type))
+            .order(fuel::id)
+            .select((fuel::id, fuel::price))
+            .first(conn)
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod fleet_tests {
+    use super::fixtures::{customer_with_full_tanks, fuel_of_type};
+    use crate::fleet;
+    use crate::models::{FleetCompany, FleetDriver};
+    use crate::schema::{bank, fleet_company, fleet_driver};
+    use diesel::prelude::*;
+    use diesel::SqliteConnection;
+
+    // Компанія з балансом і водій-клієнт із нульовим особистим балансом
+    fn setup(
+        conn: &mut SqliteConnection,
+        balance: i64,
+        daily_limit: Option<i64>,
+        fuel_types: Option<&str>,
+    ) -> QueryResult<(FleetCompany, FleetDriver)> {
+        let company: FleetCompany = diesel::insert_into(fleet_company::table)
+            .values((
+                fleet_company::name.eq("ТОВ Тест-Логістик"),
+                fleet_company::created_at.eq(0),
+            ))
+            .returning(FleetCompany::as_returning())
+            .get_result(conn)?;
+        fleet::top_up(conn, company.id, balance, Some("рахунок №1"), chrono::Utc::now().timestamp())?;
+        let customer_id = customer_with_full_tanks(conn, "fleet_driver_test", 0)?;
+        let driver: FleetDriver = diesel::insert_into(fleet_driver::table)
+            .values((
+                fleet_driver::company_id.eq(company.id),
+                fleet_driver::customer_id.eq(customer_id),
+                fleet_driver::daily_limit.eq(daily_limit),
+                fleet_driver::allowed_fuel_types.eq(fuel_types),
+                fleet_driver::created_at.eq(0),
+            ))
+            .returning(FleetDriver::as_returning())
+            .get_result(conn)?;
+        Ok((company, driver))
+    }
+
+    // complete_purchase розсилає оновлення у фоні, тому тести йдуть у runtime tokio
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_driver_purchase_charges_company() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let (company, driver) = setup(conn, 10_000_000, None, Some("petrol,diesel"))?;
+            let (petrol_id, price) = fuel_of_type(conn, "petrol")?;
+            let bank_before: i64 = bank::table.select(bank::total).first(conn).optional()?.unwrap_or(0);
+
+            let balance = crate::complete_purchase(conn, driver.customer_id, vec![(petrol_id, 10)], None, 0, None).unwrap();
+            assert_eq!(balance, 0, "Особистий баланс водія не змінюється");
+
+            let company_balance: i64 = fleet_company::table
+                .find(company.id)
+                .select(fleet_company::balance)
+                .first(conn)?;
+            assert_eq!(company_balance, 10_000_000 - price * 10);
+            let bank_after: i64 = bank::table.select(bank::total).first(conn)?;
+            assert_eq!(bank_after, bank_before + price * 10);
+
+            let (day_from, _) = fleet::period_starts(chrono::Local::now().date_naive());
+            assert_eq!(fleet::spent_since(conn, driver.id, day_from)?, price * 10);
+            Ok(())
+        });
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_driver_restrictions() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let (company, driver) = setup(conn, 10_000_000, None, Some("petrol"))?;
+            let (petrol_id, price) = fuel_of_type(conn, "petrol")?;
+            let (diesel_id, _) = fuel_of_type(conn, "diesel")?;
+
+            // Недозволений тип пального
+            let err = crate::complete_purchase(conn, driver.customer_id, vec![(diesel_id, 5)], None, 0, None).unwrap_err();
+            assert!(err.to_string().contains("не дозволене"), "{}", err);
+
+            // Добовий ліміт: перша покупка проходить, друга вже ні
+            diesel::update(fleet_driver::table.find(driver.id))
+                .set(fleet_driver::daily_limit.eq(Some(price * 15)))
+                .execute(conn)?;
+            crate::complete_purchase(conn, driver.customer_id, vec![(petrol_id, 10)], None, 0, None).unwrap();
+            let err = crate::complete_purchase(conn, driver.customer_id, vec![(petrol_id, 10)], None, 0, None).unwrap_err();
+            assert!(err.to_string().contains("добовий ліміт"), "{}", err);
+
+            // Списання умовне: коштів компанії не вистачає — нічого не змінюється
+            let err = fleet::record_purchase(conn, &driver, 0, 20_000_000, chrono::Utc::now().timestamp()).unwrap_err();
+            assert!(err.contains("Недостатньо коштів"), "{}", err);
+
+            // Заблокована картка
+            diesel::update(fleet_driver::table.find(driver.id))
+                .set(fleet_driver::active.eq(false))
+                .execute(conn)?;
+            let err = crate::complete_purchase(conn, driver.customer_id, vec![(petrol_id, 1)], None, 0, None).unwrap_err();
+            assert!(err.to_string().contains("заблоковано"), "{}", err);
+
+            // Баланс компанії змінився лише на першу покупку
+            let company_balance: i64 = fleet_company::table
+                .find(company.id)
+                .select(fleet_company::balance)
+                .first(conn)?;
+            assert_eq!(company_balance, 10_000_000 - price * 10);
+            Ok(())
+        });
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_monthly_statement() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let (company, driver) = setup(conn, 500_000, None, None)?;
+            let (petrol_id, price) = fuel_of_type(conn, "petrol")?;
+            crate::complete_purchase(conn, driver.customer_id, vec![(petrol_id, 3)], None, 0, None).unwrap();
+
+            let company: FleetCompany = fleet_company::table
+                .find(company.id)
+                .select(FleetCompany::as_select())
+                .first(conn)?;
+            let month = chrono::Local::now().format("%Y-%m").to_string();
+            let (first, next) = crate::utils::month_range(&month).unwrap();
+            let from = crate::reconciliation::day_start(first);
+            let to = crate::reconciliation::day_start(next);
+
+            let st = fleet::statement(conn, company.clone(), &month, from, to)?;
+            assert_eq!(st.opening_balance, 0);
+            assert_eq!(st.top_ups, 500_000);
+            assert_eq!(st.purchases, price * 3);
+            assert_eq!(st.closing_balance, company.balance);
+            assert_eq!(st.lines.len(), 2);
+            assert_eq!(st.lines[1].driver_login.as_deref(), Some("fleet_driver_test"));
+            assert_eq!(st.lines[1].volume, Some(3));
+            assert_eq!(st.by_driver, vec![("fleet_driver_test".to_string(), price * 3)]);
+
+            // Наступний місяць починається із залишку цього
+            let (_, after) = crate::utils::month_range(&next.format("%Y-%m").to_string()).unwrap();
+            let st = fleet::statement(conn, company.clone(), "next", to, crate::reconciliation::day_start(after))?;
+            assert_eq!(st.opening_balance, company.balance);
+            assert!(st.lines.is_empty());
+            Ok(())
+        });
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod vehicle_tests {
+    use super::fixtures::{customer_with_full_tanks, fuel_of_type};
+    use crate::models::Vehicle;
+    use crate::schema::{sale, vehicle};
+    use diesel::prelude::*;
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_purchase_checks_vehicle() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let customer_id = customer_with_full_tanks(conn, "vehicle_test", 10_000_000)?;
+            let car: Vehicle = diesel::insert_into(vehicle::table)
+                .values((
+                    vehicle::customer_id.eq(customer_id),
+                    vehicle::plate.eq("AA1234BB"),
+                    vehicle::fuel_type.eq("petrol"),
+                    vehicle::tank_capacity.eq(40),
+                    vehicle::created_at.eq(0),
+                ))
+                .returning(Vehicle::as_returning())
+                .get_result(conn)?;
+            let (petrol_id, _) = fuel_of_type(conn, "petrol")?;
+            let (diesel_id, _) = fuel_of_type(conn, "diesel")?;
+
+            let err = crate::complete_purchase(conn, customer_id, vec![(diesel_id, 10)], Some(car.id), 0, None).unwrap_err();
+            assert!(err.to_string().contains("не підходить"), "{}", err);
+            let err = crate::complete_purchase(conn, customer_id, vec![(petrol_id, 41)], Some(car.id), 0, None).unwrap_err();
+            assert!(err.to_string().contains("перевищує бак"), "{}", err);
+            // Чуже авто
+            let err = crate::complete_purchase(conn, customer_id + 1, vec![(petrol_id, 1)], Some(car.id), 0, None).unwrap_err();
+            assert!(err.to_string().contains("Авто не знайдено"), "{}", err);
+            // Без вибору авто обмежень немає
+            crate::complete_purchase(conn, customer_id, vec![(diesel_id, 50)], None, 0, None).unwrap();
+
+            crate::complete_purchase(conn, customer_id, vec![(petrol_id, 40)], Some(car.id), 0, None).unwrap();
+            let sales: Vec<(Option<i32>, i32)> = sale::table
+                .filter(sale::customer_id.eq(customer_id))
+                .order(sale::id)
+                .select((sale::vehicle_id, sale::amount))
+                .load(conn)?;
+            assert_eq!(sales, vec![(None, 50), (Some(car.id), 40)]);
+            Ok(())
+        });
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod loyalty_tests {
+    use super::fixtures::{customer_with_full_tanks, fuel_of_type};
+    use crate::loyalty::{self, EXPIRY_SECS};
+    use crate::schema::{bank, customer, loyalty_entry, loyalty_tier, sale};
+    use diesel::prelude::*;
+    use diesel::SqliteConnection;
+
+    // Клієнт з балансом 10 000 грн і рівні: від 0 — 1 бал/100 грн, від 1 000 грн — 5 балів/100 грн
+    fn setup(conn: &mut SqliteConnection) -> QueryResult<(i32, i32, i64)> {
+        diesel::delete(loyalty_tier::table).execute(conn)?;
+        diesel::insert_into(loyalty_tier::table)
+            .values(&vec![
+                (
+                    loyalty_tier::name.eq("Базовий"),
+                    loyalty_tier::min_spent.eq(0),
+                    loyalty_tier::points_per_100_uah.eq(1),
+                ),
+                (
+                    loyalty_tier::name.eq("Золотий"),
+                    loyalty_tier::min_spent.eq(100_000),
+                    loyalty_tier::points_per_100_uah.eq(5),
+                ),
+            ])
+            .execute(conn)?;
+        let customer_id = customer_with_full_tanks(conn, "loyalty_test", 1_000_000)?;
+        let (fuel_id, price) = fuel_of_type(conn, "petrol")?;
+        Ok((customer_id, fuel_id, price))
+    }
+
+    fn balance(conn: &mut SqliteConnection, customer_id: i32) -> QueryResult<i64> {
+        customer::table.find(customer_id).select(customer::balance).first(conn)
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_accrual_tiers_and_redemption() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let (customer_id, fuel_id, price) = setup(conn)?;
+            let now = chrono::Utc::now().timestamp();
+
+            // Перша покупка — базовий рівень
+            let litres = (100_000 / price + 1) as i32;
+            let cost = price * litres as i64;
+            crate::complete_purchase(conn, customer_id, vec![(fuel_id, litres)], None, 0, None).unwrap();
+            let first = cost / 10_000;
+            assert_eq!(loyalty::available(conn, customer_id, now)?, first);
+
+            // Покупки за рік перевищили 1 000 грн — наступна за золотим тарифом, частина оплачена балами
+            let bank_before: i64 = bank::table.select(bank::total).first(conn)?;
+            let balance_before = balance(conn, customer_id)?;
+            crate::complete_purchase(conn, customer_id, vec![(fuel_id, litres)], None, first, None).unwrap();
+            let paid = cost - first * loyalty::POINT_VALUE;
+            assert_eq!(balance(conn, customer_id)?, balance_before - paid);
+            let bank_after: i64 = bank::table.select(bank::total).first(conn)?;
+            assert_eq!(bank_after, bank_before + paid);
+            assert_eq!(loyalty::available(conn, customer_id, now)?, paid / 10_000 * 5);
+            // Оплачене балами записано на продаж і не входить ні у виручку, ні в суму для рівня
+            let points_value: i64 = sale::table
+                .filter(sale::customer_id.eq(customer_id))
+                .order(sale::id.desc())
+                .select(sale::points_value)
+                .first(conn)?;
+            assert_eq!(points_value, first * loyalty::POINT_VALUE);
+            assert_eq!(loyalty::spent_in_window(conn, customer_id, now + 1)?, cost + paid);
+
+            let account = loyalty::account(conn, customer_id, now)?;
+            assert_eq!(account.tier.name, "Золотий");
+            assert_eq!(account.next_tier, None);
+            let kinds: Vec<&str> = account.history.iter().map(|e| e.kind.as_str()).collect();
+            assert_eq!(kinds.iter().filter(|k| **k == "redeem").count(), 1);
+            assert_eq!(kinds.iter().filter(|k| **k == "earn").count(), 2);
+            Ok(())
+        });
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_redemption_limits() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let (customer_id, fuel_id, price) = setup(conn)?;
+            let now = chrono::Utc::now().timestamp();
+            loyalty::earn(conn, customer_id, 1_000, None, now)?;
+
+            let err = crate::complete_purchase(conn, customer_id, vec![(fuel_id, 1)], None, 2_000, None).unwrap_err();
+            assert!(err.to_string().contains("Недостатньо балів"), "{}", err);
+            let too_many = price / loyalty::POINT_VALUE + 1;
+            let err = crate::complete_purchase(conn, customer_id, vec![(fuel_id, 1)], None, too_many, None).unwrap_err();
+            assert!(err.to_string().contains("перевищують суму"), "{}", err);
+            let err = crate::complete_purchase(conn, customer_id, vec![(fuel_id, 1)], None, -1, None).unwrap_err();
+            assert!(err.to_string().contains("від'ємною"), "{}", err);
+            assert_eq!(loyalty::available(conn, customer_id, now)?, 1_000);
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_expiry_and_fifo_redemption() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let (customer_id, _, _) = setup(conn)?;
+            let t0 = 1_700_000_000;
+            loyalty::earn(conn, customer_id, 30, None, t0)?;
+            loyalty::earn(conn, customer_id, 50, None, t0 + 100)?;
+
+            // Списання забирає спершу з партії, що згорає раніше
+            loyalty::redeem(conn, customer_id, 40, None, t0 + 200).unwrap();
+            let remaining: Vec<i64> = loyalty_entry::table
+                .filter(loyalty_entry::customer_id.eq(customer_id))
+                .filter(loyalty_entry::kind.eq("earn"))
+                .order(loyalty_entry::id)
+                .select(loyalty_entry::remaining)
+                .load(conn)?;
+            assert_eq!(remaining, vec![0, 40]);
+
+            // Балів уже не вистачає — Err, і нічого не списано
+            let err = loyalty::redeem(conn, customer_id, 41, None, t0 + 200).unwrap_err();
+            assert!(err.contains("доступно 40"), "{}", err);
+            assert_eq!(loyalty::available(conn, customer_id, t0 + 200)?, 40);
+
+            // Після терміну бали недоступні, згоряння фіксується один раз
+            let expired_at = t0 + 100 + EXPIRY_SECS;
+            assert_eq!(loyalty::available(conn, customer_id, expired_at - 1)?, 40);
+            assert_eq!(loyalty::available(conn, customer_id, expired_at)?, 0);
+            assert_eq!(loyalty::expire_due(conn, Some(customer_id), expired_at)?, 1);
+            assert_eq!(loyalty::expire_due(conn, Some(customer_id), expired_at)?, 0);
+            let total: Vec<i64> = loyalty_entry::table
+                .filter(loyalty_entry::customer_id.eq(customer_id))
+                .select(loyalty_entry::points)
+                .load(conn)?;
+            assert_eq!(total.iter().sum::<i64>(), 0);
+            Ok(())
+        });
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod promotion_tests {
+    use super::fixtures::{customer_with_full_tanks, fuel_of_type};
+    use crate::models::Promotion;
+    use crate::promotions::{self, KIND_PERCENT, KIND_PER_LITRE};
+    use crate::schema::{fuel, promotion, promotion_use, sale};
+    use chrono::Timelike;
+    use diesel::prelude::*;
+    use diesel::SqliteConnection;
+
+    // Клієнт з балансом 10 000 грн і бензин по 50 грн
+    fn setup(conn: &mut SqliteConnection) -> QueryResult<(i32, i32)> {
+        diesel::delete(promotion_use::table).execute(conn)?;
+        diesel::update(sale::table).set(sale::promotion_id.eq(None::<i32>)).execute(conn)?;
+        diesel::delete(promotion::table).execute(conn)?;
+        let customer_id = customer_with_full_tanks(conn, "promo_test", 1_000_000)?;
+        let (fuel_id, _) = fuel_of_type(conn, "petrol")?;
+        diesel::update(fuel::table.find(fuel_id))
+            .set(fuel::price.eq(5_000))
+            .execute(conn)?;
+        Ok((customer_id, fuel_id))
+    }
+
+    fn add_promotion(
+        conn: &mut SqliteConnection,
+        name: &str,
+        code: Option<&str>,
+        kind: &str,
+        value: i32,
+        fuel_id: Option<i32>,
+    ) -> QueryResult<i32> {
+        diesel::insert_into(promotion::table)
+            .values((
+                promotion::name.eq(name),
+                promotion::code.eq(code),
+                promotion::kind.eq(kind),
+                promotion::value.eq(value),
+                promotion::fuel_id.eq(fuel_id),
+                promotion::created_at.eq(chrono::Utc::now().timestamp()),
+            ))
+            .returning(promotion::id)
+            .get_result(conn)
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_best_promotion_and_coupon() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let (customer_id, fuel_id) = setup(conn)?;
+            let now = chrono::Local::now();
+            add_promotion(conn, "Мінус 5%", None, KIND_PERCENT, 5, None)?;
+            let coupon_id = add_promotion(conn, "Купон 5 грн/л", Some("SAVE5"), KIND_PER_LITRE, 500, Some(fuel_id))?;
+            diesel::update(promotion::table.find(coupon_id))
+                .set((promotion::min_quantity.eq(Some(20)), promotion::per_customer_limit.eq(Some(1))))
+                .execute(conn)?;
+
+            // Без купона — автоматична знижка 5%
+            let best = promotions::best_for(conn, customer_id, &[(fuel_id, 10, 50_000)], None, now)
+                .unwrap()
+                .unwrap();
+            assert_eq!(best.promotion.name, "Мінус 5%");
+            assert_eq!(best.total(), 2_500);
+
+            let err = promotions::best_for(conn, customer_id, &[(fuel_id, 10, 50_000)], Some("save5"), now)
+                .err()
+                .unwrap();
+            assert!(err.contains("від 20 л"), "{}", err);
+            let err = promotions::best_for(conn, customer_id, &[(fuel_id, 30, 150_000)], Some("NOPE"), now)
+                .err()
+                .unwrap();
+            assert!(err.contains("не знайдено"), "{}", err);
+
+            // Купон вигідніший за 5%: 30 л × 5 грн = 150 грн проти 75 грн
+            let balance = crate::complete_purchase(conn, customer_id, vec![(fuel_id, 30)], None, 0, Some(" save5 ".into()))
+                .unwrap();
+            assert_eq!(balance, 1_000_000 - (150_000 - 15_000));
+            let (sale_id, discount, promotion_id): (i32, i64, Option<i32>) = sale::table
+                .filter(sale::customer_id.eq(customer_id))
+                .select((sale::id, sale::discount, sale::promotion_id))
+                .first(conn)?;
+            assert_eq!((discount, promotion_id), (15_000, Some(coupon_id)));
+            assert_eq!(promotions::uses(conn, coupon_id, customer_id)?, 1);
+
+            // Покупка, що пройшла перевірку паралельно з першою, ліміт у транзакції не обійде
+            let coupon: Promotion = promotion::table.find(coupon_id).select(Promotion::as_select()).first(conn)?;
+            let err = promotions::record_use(conn, &coupon, customer_id, sale_id, 15_000, 0).unwrap_err();
+            assert!(err.contains("максимальну кількість"), "{}", err);
+            assert_eq!(promotions::uses(conn, coupon_id, customer_id)?, 1);
+
+            // Ліміт на клієнта вичерпано; автоматична акція діє й далі
+            let err = crate::complete_purchase(conn, customer_id, vec![(fuel_id, 30)], None, 0, Some("SAVE5".into()))
+                .unwrap_err();
+            assert!(err.to_string().contains("вже використали"), "{}", err);
+            let balance = crate::complete_purchase(conn, customer_id, vec![(fuel_id, 30)], None, 0, None).unwrap();
+            assert_eq!(balance, 1_000_000 - 135_000 - (150_000 - 7_500));
+            Ok(())
+        });
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_happy_hour_and_validity() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let (customer_id, fuel_id) = setup(conn)?;
+            let now = chrono::Local::now();
+            let minute = (now.hour() * 60 + now.minute()) as i32;
+            let lines = [(fuel_id, 10, 50_000)];
+
+            // Happy hour почнеться через годину — поки не діє
+            let later = add_promotion(conn, "Вечірня", None, KIND_PERCENT, 10, None)?;
+            diesel::update(promotion::table.find(later))
+                .set((
+                    promotion::happy_hour_start.eq(Some((minute + 60) % 1440)),
+                    promotion::happy_hour_end.eq(Some((minute + 120) % 1440)),
+                ))
+                .execute(conn)?;
+            assert!(promotions::best_for(conn, customer_id, &lines, None, now).unwrap().is_none());
+
+            // Вікно навколо поточного часу
+            diesel::update(promotion::table.find(later))
+                .set((
+                    promotion::happy_hour_start.eq(Some((minute + 1410) % 1440)),
+                    promotion::happy_hour_end.eq(Some((minute + 30) % 1440)),
+                ))
+                .execute(conn)?;
+            let best = promotions::best_for(conn, customer_id, &lines, None, now).unwrap().unwrap();
+            assert_eq!(best.total(), 5_000);
+
+            // Термін дії минув, вимкнена акція не діє
+            diesel::update(promotion::table.find(later))
+                .set(promotion::valid_until.eq(Some(now.timestamp() - 1)))
+                .execute(conn)?;
+            assert!(promotions::best_for(conn, customer_id, &lines, None, now).unwrap().is_none());
+            let coupon = add_promotion(conn, "Купон", Some("OLD"), KIND_PERCENT, 10, None)?;
+            diesel::update(promotion::table.find(coupon))
+                .set(promotion::active.eq(false))
+                .execute(conn)?;
+            let err = promotions::best_for(conn, customer_id, &lines, Some("old"), now).err().unwrap();
+            assert!(err.contains("не діє"), "{}", err);
+
+            // Бали списуються із суми після знижки
+            diesel::update(promotion::table.find(coupon))
+                .set(promotion::active.eq(true))
+                .execute(conn)?;
+            crate::loyalty::earn(conn, customer_id, 1_000, None, now.timestamp())?;
+            let err = crate::complete_purchase(conn, customer_id, vec![(fuel_id, 10)], None, 460, Some("OLD".into()))
+                .unwrap_err();
+            assert!(err.to_string().contains("перевищують"), "{}", err);
+            let balance =
+                crate::complete_purchase(conn, customer_id, vec![(fuel_id, 10)], None, 450, Some("OLD".into())).unwrap();
+            assert_eq!(balance, 1_000_000);
+            Ok(())
+        });
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod rest_tests {
+    use crate::models::FuelWithTank;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    async fn call(request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = crate::rest::router().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    fn get(uri: &str) -> Request<Body> {
+        Request::get(uri).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_fuels_endpoints() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+
+        let (status, body) = call(get("/api/v1/fuels")).await;
+        assert_eq!(status, StatusCode::OK);
+        let fuels: Vec<FuelWithTank> = serde_json::from_value(body).unwrap();
+        assert!(!fuels.is_empty());
+
+        let (status, body) = call(get(&format!("/api/v1/fuels/{}", fuels[0].id))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(serde_json::from_value::<FuelWithTank>(body).unwrap(), fuels[0]);
+
+        let (status, body) = call(get("/api/v1/fuels/999999")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].is_string());
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_auth_required() {
+        dotenvy::dotenv().ok();
+
+        let (status, _) = call(get("/api/v1/tanks")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let bad_token = |uri: &str| {
+            Request::get(uri)
+                .header("Authorization", "Bearer not-a-session")
+                .body(Body::empty())
+                .unwrap()
+        };
+        assert_eq!(call(bad_token("/api/v1/bank")).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(call(bad_token("/api/v1/tanks")).await.0, StatusCode::UNAUTHORIZED);
+
+        let purchase = Request::post("/api/v1/purchases")
+            .header("Authorization", "Bearer not-a-session")
+            .header("Content-Type", "application/json")
+            .body(Body::from(r#"{"items":[{"fuel_id":1,"amount":1}]}"#))
+            .unwrap();
+        let (status, body) = call(purchase).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body["error"].as_str().unwrap().contains("Invalid Token"));
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_api_key_access() {
+        use diesel::prelude::*;
+
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        let mut conn = crate::db::connection();
+
+        // Ключі потрібні обробникам через пул, тому вони зберігаються і видаляються в кінці тесту.
+        // Покупку за ключем автопарку перевіряє api_key_tests у test_transaction.
+        let scopes = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let (fleet_key, fleet_raw) = crate::api_keys::issue(
+            &mut conn,
+            "Fleet test",
+            &scopes(&["stock:read", "purchases:create"]),
+            None,
+            100,
+            "test",
+        )
+        .unwrap();
+        let (prices_key, prices_raw) =
+            crate::api_keys::issue(&mut conn, "Prices test", &scopes(&["prices:read"]), None, 100, "test").unwrap();
+
+        let with_key = |method: &str, uri: &str, key: &str, body: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("X-Api-Key", key)
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        assert_eq!(call(with_key("GET", "/api/v1/tanks", &fleet_raw, "")).await.0, StatusCode::OK);
+        let (status, body) = call(with_key("GET", "/api/v1/tanks", &prices_raw, "")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(body["error"].as_str().unwrap().contains("stock:read"));
+
+        let bearer = Request::get("/api/v1/fuels")
+            .header("Authorization", format!("Bearer {}", prices_raw))
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(call(bearer).await.0, StatusCode::OK);
+
+        // Жоден із запитів нижче не доходить до списання
+        let order = r#"{"items":[{"fuel_id":1,"amount":1}]}"#;
+        assert_eq!(
+            call(with_key("POST", "/api/v1/purchases", &prices_raw, order)).await.0,
+            StatusCode::FORBIDDEN
+        );
+        let (status, body) = call(with_key("POST", "/api/v1/purchases", &fleet_raw, order)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(body["error"].as_str().unwrap().contains("не прив'язаний"));
+        let with_points = r#"{"items":[{"fuel_id":1,"amount":1}],"points":10}"#;
+        let (status, body) = call(with_key("POST", "/api/v1/purchases", &fleet_raw, with_points)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("Бали лояльності"));
+
+        crate::api_keys::revoke(&mut conn, fleet_key.id, chrono::Utc::now().timestamp()).unwrap();
+        let (status, body) = call(with_key("GET", "/api/v1/tanks", &fleet_raw, "")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body["error"].as_str().unwrap().contains("відкликано"));
+
+        diesel::delete(
+            crate::schema::api_key::table
+                .filter(crate::schema::api_key::id.eq_any([fleet_key.id, prices_key.id])),
+        )
+        .execute(&mut conn)
+        .unwrap();
+    }
+
+    // Збережена специфікація має збігатися з тією, що генерується з коду.
+    // Оновити файл: UPDATE_OPENAPI=1 cargo test --features server test_openapi_snapshot
+    #[test]
+    fn test_openapi_snapshot() {
+        let generated = crate::rest::router_with_spec().1.to_pretty_json().unwrap();
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/docs/openapi.json");
+        if std::env::var("UPDATE_OPENAPI").is_ok() {
+            std::fs::write(path, format!("{}\n", generated)).unwrap();
+        }
+        let saved = std::fs::read_to_string(path).expect("docs/openapi.json відсутній");
+        assert_eq!(
+            saved.trim_end(),
+            generated,
+            "Специфікація API змінилася — перегенеруйте docs/openapi.json (UPDATE_OPENAPI=1)"
+        );
+
+        let schemas = crate::rest::router_with_spec().1.components.unwrap().schemas;
+        for name in ["FuelWithTank", "Customer", "Bank", "FuelPriceStats", "ErrorBody", "PurchaseRequest"] {
+            assert!(schemas.contains_key(name), "немає схеми {}", name);
+        }
+    }
+
+    // Кожна операція зі специфікації обробляється роутером і повертає лише задокументовані статуси
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_openapi_matches_handlers() {
+        dotenvy::dotenv().ok();
+        crate::db::run_migrations();
+        std::env::set_var(
+            "MARKET_PRICE_SOURCE",
+            concat!("file:", env!("CARGO_MANIFEST_DIR"), "/fixtures/market_prices.csv"),
+        );
+
+        let (status, spec) = call(get(crate::rest::SPEC_PATH)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(spec, serde_json::to_value(crate::rest::router_with_spec().1).unwrap());
+
+        let paths = spec["paths"].as_object().unwrap();
+        assert!(!paths.is_empty());
+        for (path, item) in paths {
+            let uri = path.replace("{id}", "1");
+            for (method, operation) in item.as_object().unwrap() {
+                let mut request = Request::builder().method(method.to_uppercase().as_str()).uri(&uri);
+                let body = if operation.get("requestBody").is_some() {
+                    request = request.header("Content-Type", "application/json");
+                    Body::from("{}")
+                } else {
+                    Body::empty()
+                };
+                let response = crate::rest::router().oneshot(request.body(body).unwrap()).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+                assert_ne!(status, StatusCode::METHOD_NOT_ALLOWED, "{} {} не обробляється", method, path);
+                if status == StatusCode::NOT_FOUND {
+                    assert!(!bytes.is_empty(), "{} {} не обробляється", method, path);
+                }
+                assert!(
+                    operation["responses"].get(status.as_str()).is_some(),
+                    "{} {} повернув незадокументований статус {}",
+                    method,
+                    path,
+                    status
+                );
+            }
+        }
+        std::env::remove_var("MARKET_PRICE_SOURCE");
+    }
+}
@@ -15,9 +15,371 @@ pub fn format_money(cents: i64) -> String {
     format!("{:.2} грн", cents as f64 / 100.0)
 }
 
-pub fn calculate_refill_cost(fuel_price: i64, amount: i32) -> i64 {
-    let cost_per_unit = fuel_price / 2;
-    cost_per_unit * amount as i64
+pub fn calculate_delivery_cost(unit_cost: i64, amount: i32) -> i64 {
+    unit_cost * amount as i64
+}
+
+// Статус замовлення після отримання поставки
+pub fn order_status_after_delivery(ordered: i32, delivered: i32) -> &'static str {
+    if delivered >= ordered {
+        "delivered"
+    } else if delivered > 0 {
+        "partial"
+    } else {
+        "open"
+    }
+}
+
+// Статус при ручному закритті: недопоставка або скасування
+pub fn order_status_on_close(delivered: i32) -> &'static str {
+    if delivered > 0 {
+        "closed_short"
+    } else {
+        "cancelled"
+    }
+}
+
+pub fn order_status_label(status: &str) -> &'static str {
+    match status {
+        "open" => "Очікує",
+        "partial" => "Частково",
+        "delivered" => "Виконано",
+        "closed_short" => "Закрито з недопоставкою",
+        "cancelled" => "Скасовано",
+        _ => "Невідомо",
+    }
+}
+
+// Списання партій за FIFO. `lots` — (залишок, ціна за літр) від найстаршої партії.
+// Повертає кількість, взяту з кожної партії, та собівартість у копійках.
+// Обсяг понад залишок партій оцінюється за ціною останньої партії.
+pub fn consume_fifo(lots: &[(i32, i64)], amount: i32) -> (Vec<i32>, i64) {
+    let mut taken = Vec::with_capacity(lots.len());
+    let mut cost = 0i64;
+    let mut left = amount.max(0);
+
+    for &(remaining, unit_cost) in lots {
+        let take = left.min(remaining.max(0));
+        taken.push(take);
+        cost += unit_cost * take as i64;
+        left -= take;
+    }
+
+    if left > 0 {
+        let last_cost = lots.last().map(|l| l.1).unwrap_or(0);
+        cost += last_cost * left as i64;
+    }
+
+    (taken, cost)
+}
+
+pub fn margin_percent(revenue: i64, cogs: i64) -> Option<f64> {
+    if revenue == 0 {
+        return None;
+    }
+    Some((revenue - cogs) as f64 / revenue as f64 * 100.0)
+}
+
+// Середнє добове споживання за вікно у `days` днів
+pub fn average_daily_consumption(sold: i64, days: u32) -> f64 {
+    if days == 0 {
+        return 0.0;
+    }
+    sold as f64 / days as f64
+}
+
+pub fn days_until_empty(stock: i32, avg_daily: f64) -> Option<f64> {
+    if avg_daily <= 0.0 {
+        return None;
+    }
+    Some(stock.max(0) as f64 / avg_daily)
+}
+
+// Обсяг замовлення до цільового рівня з урахуванням вже замовленого,
+// але не більше вільного місця в резервуарах
+pub fn suggested_order_qty(stored: i32, capacity: i32, on_order: i32, target_level: i32) -> i32 {
+    let target = target_level.min(capacity);
+    let free_space = capacity - stored - on_order;
+    (target - stored - on_order).min(free_space).max(0)
+}
+
+// Чи потрапляє хвилина доби у вікно [start, end); вікно може переходити через північ
+pub fn minute_in_window(minute: i32, start: i32, end: i32) -> bool {
+    if start <= end {
+        minute >= start && minute < end
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+// Діюча ціна: перше активне правило за часом доби, інакше базова ціна.
+// `rules` — (початок, кінець, ціна) у хвилинах від півночі.
+pub fn effective_price(base_price: i64, rules: &[(i32, i32, i64)], minute: i32) -> i64 {
+    rules
+        .iter()
+        .find(|(start, end, _)| minute_in_window(minute, *start, *end))
+        .map(|r| r.2)
+        .unwrap_or(base_price)
+}
+
+// "22:30" -> 1350
+pub fn parse_time_of_day(value: &str) -> Option<i32> {
+    let (h, m) = value.trim().split_once(':')?;
+    let (h, m) = (h.parse::<i32>().ok()?, m.parse::<i32>().ok()?);
+    if !(0..24).contains(&h) || !(0..60).contains(&m) {
+        return None;
+    }
+    Some(h * 60 + m)
+}
+
+pub fn format_time_of_day(minute: i32) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+// Відповідність нашого пального категоріям ринкової статистики Мінфіну.
+// 98 та 100 порівнюємо з преміальним А-95 — окремої категорії на ринку немає.
+pub fn market_fuel_name(fuel_name: &str) -> Option<&'static str> {
+    match fuel_name.trim() {
+        "100" | "98" => Some("А-95 Преміум"),
+        "95" => Some("А-95"),
+        "92" => Some("А-92"),
+        "ДП" => Some("Дизель"),
+        "Пропан-бутан" => Some("Газ"),
+        _ => None,
+    }
+}
+
+// Ціна за ринковим правилом: середня по ринку + зсув, але не нижче собівартості + націнки.
+// Повертає ціну та ознаку, що спрацювало обмеження знизу.
+pub fn market_linked_price(market_average: i64, offset: i64, cost: Option<i64>, margin_floor: i64) -> (i64, bool) {
+    let target = market_average + offset;
+    match cost {
+        Some(c) if target < c + margin_floor => (c + margin_floor, true),
+        _ => (target, false),
+    }
+}
+
+// Середньозважена собівартість залишку: `lots` — (кількість, ціна за літр)
+pub fn weighted_average_cost(lots: &[(i32, i64)]) -> Option<i64> {
+    let quantity: i64 = lots.iter().map(|l| l.0 as i64).sum();
+    if quantity <= 0 {
+        return None;
+    }
+    let total: i64 = lots.iter().map(|l| l.0 as i64 * l.1).sum();
+    Some((total as f64 / quantity as f64).round() as i64)
+}
+
+// Місце нашої ціни серед конкурентів (1 — найдешевша) та кількість учасників разом із нами.
+// За однакової ціни ділимо місце з конкурентом.
+pub fn price_rank(ours: f64, competitors: &[f64]) -> (usize, usize) {
+    let cheaper = competitors.iter().filter(|p| **p < ours - 0.005).count();
+    (cheaper + 1, competitors.len() + 1)
+}
+
+// Чи спрацьовує підписка клієнта за поточною ціною та наявністю пального
+pub fn alert_triggered(kind: &str, threshold: Option<i64>, price: i64, available: bool) -> bool {
+    match kind {
+        "price_below" => available && threshold.is_some_and(|t| price < t),
+        "available" => available,
+        _ => false,
+    }
+}
+
+// Проста перевірка адреси пошти; також відсікає символи, що ламають заголовки та SMTP-команди
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !domain.contains('@')
+        && !email.chars().any(|c| c.is_whitespace() || c.is_control() || "<>,;".contains(c))
+}
+
+// Діючий поріг низького залишку: налаштований або 20% місткості
+pub fn low_stock_threshold(capacity: i32, configured: Option<i32>) -> i32 {
+    configured.unwrap_or(capacity / 5)
+}
+
+// Рівень події низького залишку: порожній резервуар — критично, нижче порогу — попередження
+pub fn low_stock_severity(stored: i32, threshold: i32) -> Option<&'static str> {
+    if stored <= 0 {
+        Some("critical")
+    } else if stored <= threshold {
+        Some("warning")
+    } else {
+        None
+    }
+}
+
+// Чи підписка (список подій через кому або '*') охоплює подію
+pub fn webhook_matches(events: &str, event: &str) -> bool {
+    events.split(',').map(str::trim).any(|e| e == "*" || e == event)
+}
+
+// Пауза перед наступною спробою доставки після `attempts` невдалих:
+// 30 с, 1 хв, 2 хв, ... (подвоєння); після 8 спроб доставку припинено (None)
+pub fn webhook_retry_delay(attempts: i32) -> Option<i64> {
+    const MAX_ATTEMPTS: i32 = 8;
+    if attempts >= MAX_ATTEMPTS {
+        return None;
+    }
+    Some(30i64 << (attempts.max(1) - 1))
+}
+
+// Розбір API-ключа "gs_<prefix>_<secret>" на префікс (пошук) і секрет (перевірка хешу)
+pub fn split_api_key(raw: &str) -> Option<(&str, &str)> {
+    let (prefix, secret) = raw.trim().strip_prefix("gs_")?.split_once('_')?;
+    let hex = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit());
+    if prefix.len() == 8 && secret.len() >= 32 && hex(prefix) && hex(secret) {
+        Some((prefix, secret))
+    } else {
+        None
+    }
+}
+
+// Чи містить список дозволів ключа (через кому) потрібний дозвіл
+pub fn has_scope(scopes: &str, scope: &str) -> bool {
+    scopes.split(',').map(str::trim).any(|s| s == scope)
+}
+
+// Чи дозволено водію пальне цього типу (None — усі типи)
+pub fn fuel_type_allowed(allowed: Option<&str>, fuel_type: &str) -> bool {
+    allowed.is_none_or(|list| list.split(',').map(str::trim).any(|t| t == fuel_type))
+}
+
+// Чи перевищить покупка ліміт водія (None — без ліміту)
+pub fn limit_exceeded(limit: Option<i64>, spent: i64, total: i64) -> bool {
+    limit.is_some_and(|l| spent + total > l)
+}
+
+// Місяць "РРРР-ММ" → перший день місяця і перший день наступного
+pub fn month_range(month: &str) -> Option<(chrono::NaiveDate, chrono::NaiveDate)> {
+    let (year, month) = month.trim().split_once('-')?;
+    let first = chrono::NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, 1)?;
+    Some((first, first.checked_add_months(chrono::Months::new(1))?))
+}
+
+// Бали за покупку: за кожен літр (кВт·год) і за кожні повні сплачені 100 грн
+pub fn loyalty_points(volume: i64, paid: i64, per_litre: i32, per_100_uah: i32) -> i64 {
+    volume * per_litre as i64 + paid.max(0) / 10_000 * per_100_uah as i64
+}
+
+// Чи діє акція о цій хвилині доби; без вікна happy hour — цілодобово
+pub fn in_happy_hour(start: Option<i32>, end: Option<i32>, minute: i32) -> bool {
+    match (start, end) {
+        (Some(start), Some(end)) => minute_in_window(minute, start, end),
+        _ => true,
+    }
+}
+
+// Знижка акції по рядках кошика (пальне, обсяг, вартість). None — акція не застосовна:
+// у кошику немає акційного пального або його менше за min_quantity.
+// kind "percent" — value відсотків від вартості, "per_litre" — value копійок за літр.
+pub fn promotion_discounts(
+    kind: &str,
+    value: i32,
+    fuel_id: Option<i32>,
+    min_quantity: Option<i32>,
+    lines: &[(i32, i32, i64)],
+) -> Option<Vec<i64>> {
+    let eligible = |line_fuel: i32| fuel_id.is_none_or(|f| f == line_fuel);
+    let quantity: i32 = lines.iter().filter(|l| eligible(l.0)).map(|l| l.1).sum();
+    if quantity == 0 || min_quantity.is_some_and(|min| quantity < min) {
+        return None;
+    }
+    let value = value.max(0) as i64;
+    let discounts = lines
+        .iter()
+        .map(|&(line_fuel, amount, cost)| {
+            if !eligible(line_fuel) {
+                return 0;
+            }
+            let discount = match kind {
+                "percent" => cost * value.min(100) / 100,
+                "per_litre" => amount as i64 * value,
+                _ => 0,
+            };
+            discount.min(cost)
+        })
+        .collect();
+    Some(discounts)
+}
+
+// Номер авто без пробілів і дефісів, великими літерами; None — некоректний номер
+pub fn normalize_plate(raw: &str) -> Option<String> {
+    let plate: String = raw
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_uppercase)
+        .collect();
+    let valid = (4..=10).contains(&plate.chars().count()) && plate.chars().all(char::is_alphanumeric);
+    valid.then_some(plate)
+}
+
+// Чи можна залити в авто пальне (назва, тип, обсяг): тип має збігатися з типом авто,
+// а сумарний обсяг — вміщатися в бак (батарею). Err — причина відмови.
+pub fn check_vehicle_fill(
+    plate: &str,
+    vehicle_type: &str,
+    tank_capacity: i32,
+    fuels: &[(String, String, i32)],
+) -> Result<(), String> {
+    if let Some((name, _, _)) = fuels.iter().find(|(_, fuel_type, _)| fuel_type != vehicle_type) {
+        return Err(format!("Пальне {} не підходить для авто {}", name, plate));
+    }
+    let volume: i32 = fuels.iter().map(|(_, _, amount)| amount).sum();
+    if volume > tank_capacity {
+        let unit = if vehicle_type == "electricity" { "кВт·год" } else { "л" };
+        return Err(format!(
+            "Обсяг {} {} перевищує бак авто {} ({} {})",
+            volume, unit, plate, tank_capacity, unit
+        ));
+    }
+    Ok(())
+}
+
+// Чи досягає рівень події мінімального рівня каналу
+pub fn severity_at_least(severity: &str, min: &str) -> bool {
+    let rank = |s: &str| match s {
+        "critical" => 2,
+        "warning" => 1,
+        _ => 0,
+    };
+    rank(severity) >= rank(min)
+}
+
+// Вершини ступінчастого графіка ціни: ціна діє до наступної зміни, остання — до `to`
+pub fn step_chart_points(points: &[(i64, i64)], to: i64) -> Vec<(i64, i64)> {
+    let mut result = Vec::with_capacity(points.len() * 2);
+    for (i, &(at, price)) in points.iter().enumerate() {
+        result.push((at, price));
+        let end = points.get(i + 1).map(|p| p.0).unwrap_or(to);
+        result.push((end, price));
+    }
+    result
+}
+
+// Перетворює точки (час, ціна) на атрибут `points` для SVG polyline у полотні width×height
+pub fn svg_polyline(
+    points: &[(i64, i64)],
+    (from, to): (i64, i64),
+    (min, max): (i64, i64),
+    (width, height): (f64, f64),
+) -> String {
+    let time_span = (to - from).max(1) as f64;
+    let price_span = (max - min).max(1) as f64;
+    points
+        .iter()
+        .map(|&(at, price)| {
+            let x = (at - from) as f64 / time_span * width;
+            let y = height - (price - min) as f64 / price_span * height;
+            format!("{:.1},{:.1}", x, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 pub fn has_sufficient_balance(balance: i64, cost: i64) -> bool {
@@ -31,3 +393,153 @@ pub fn calculate_total_stored(tanks: &[(i32, i32)]) -> i32 {
 pub fn has_sufficient_fuel(total_stored: i32, amount_needed: i32) -> bool {
     total_stored >= amount_needed
 }
+
+pub fn expected_closing_stock(opening: f64, deliveries: i64, sales: i64) -> f64 {
+    opening + deliveries as f64 - sales as f64
+}
+
+// Від'ємна розбіжність — нестача (витік, крадіжка), додатна — надлишок
+pub fn stock_variance(expected: f64, measured: f64) -> f64 {
+    measured - expected
+}
+
+pub fn variance_exceeds(variance: f64, threshold: i32) -> bool {
+    variance.abs() > threshold as f64
+}
+
+pub fn cumulative_variances(variances: &[Option<f64>]) -> Vec<f64> {
+    variances
+        .iter()
+        .scan(0.0, |acc, v| {
+            *acc += v.unwrap_or(0.0);
+            Some(*acc)
+        })
+        .collect()
+}
+
+pub const REFERENCE_TEMPERATURE: f64 = 15.0;
+
+// Теплові властивості пального для приведення об'єму до 15 °C
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThermalProperties {
+    pub density_15: f64,
+    pub expansion_coeff: Option<f64>,
+}
+
+impl ThermalProperties {
+    // Густина за замовчуванням за типом пального; для електроенергії приведення немає
+    pub fn for_fuel(fuel_type: &str, density: Option<f64>, expansion_coeff: Option<f64>) -> Option<Self> {
+        let default_density = match fuel_type {
+            "electricity" => return None,
+            "diesel" => 835.0,
+            "gas" => 540.0,
+            _ => 745.0,
+        };
+        Some(ThermalProperties {
+            density_15: density.unwrap_or(default_density),
+            expansion_coeff,
+        })
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.expansion_coeff
+            .unwrap_or_else(|| thermal_expansion_coeff(self.density_15))
+    }
+}
+
+// Коефіцієнт розширення при 15 °C за ASTM D1250 / ISO 91-1, таблиця 54B
+pub fn thermal_expansion_coeff(density_15: f64) -> f64 {
+    let rho = density_15;
+    if rho < 770.5 {
+        // Бензини
+        346.4228 / (rho * rho) + 0.4388 / rho
+    } else if rho < 787.5 {
+        // Перехідна зона
+        -0.00336312 + 2680.3206 / (rho * rho)
+    } else if rho < 838.5 {
+        // Гас, реактивне та дизельне пальне
+        594.5418 / (rho * rho)
+    } else {
+        // Мазути
+        186.9696 / (rho * rho) + 0.4862 / rho
+    }
+}
+
+// Volume Correction Factor: VCF = exp(-α·ΔT·(1 + 0.8·α·ΔT))
+pub fn volume_correction_factor(props: &ThermalProperties, temperature: f64) -> f64 {
+    let alpha = props.alpha();
+    let delta = temperature - REFERENCE_TEMPERATURE;
+    (-alpha * delta * (1.0 + 0.8 * alpha * delta)).exp()
+}
+
+pub fn volume_at_15c(gross: f64, temperature: f64, props: &ThermalProperties) -> f64 {
+    gross * volume_correction_factor(props, temperature)
+}
+
+// Розбір градуювальної таблиці з CSV: "висота_мм,об'єм_л" (або через ';'), заголовок необов'язковий
+pub fn parse_strapping_csv(text: &str) -> Result<Vec<(i32, f64)>, String> {
+    let mut chart: Vec<(i32, f64)> = Vec::new();
+
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let parts: Vec<&str> = line.split([',', ';', '\t']).map(|p| p.trim()).collect();
+        if parts.len() < 2 {
+            return Err(format!("Рядок {}: очікується висота та об'єм", n + 1));
+        }
+        let height = parts[0].parse::<i32>();
+        let volume = parts[1].replace(' ', "").parse::<f64>();
+        match (height, volume) {
+            (Ok(h), Ok(v)) => {
+                if let Some(&(prev_h, prev_v)) = chart.last() {
+                    if h <= prev_h || v < prev_v {
+                        return Err(format!("Рядок {}: значення мають зростати", n + 1));
+                    }
+                }
+                if h < 0 || v < 0.0 {
+                    return Err(format!("Рядок {}: від'ємне значення", n + 1));
+                }
+                chart.push((h, v));
+            }
+            // Заголовок допускається лише першим рядком
+            _ if chart.is_empty() && n == 0 => continue,
+            _ => return Err(format!("Рядок {}: некоректні числа", n + 1)),
+        }
+    }
+
+    if chart.len() < 2 {
+        return Err("Таблиця має містити щонайменше дві точки".to_string());
+    }
+    Ok(chart)
+}
+
+// Лінійна інтерполяція об'єму за висотою; None — висота поза межами таблиці
+pub fn interpolate_volume(chart: &[(i32, f64)], height_mm: f64) -> Option<f64> {
+    let (first_h, first_v) = *chart.first()?;
+    if height_mm < first_h as f64 {
+        return None;
+    }
+    if height_mm == first_h as f64 {
+        return Some(first_v);
+    }
+
+    chart.windows(2).find_map(|w| {
+        let (h0, v0) = (w[0].0 as f64, w[0].1);
+        let (h1, v1) = (w[1].0 as f64, w[1].1);
+        if height_mm > h0 && height_mm <= h1 {
+            Some(v0 + (v1 - v0) * (height_mm - h0) / (h1 - h0))
+        } else {
+            None
+        }
+    })
+}
+
+pub fn format_timestamp(ts: i64) -> String {
+    use chrono::{Local, TimeZone};
+    match Local.timestamp_opt(ts, 0).single() {
+        Some(dt) => dt.format("%d.%m.%Y %H:%M").to_string(),
+        None => "-".to_string(),
+    }
+}
@@ -0,0 +1,238 @@
+// Вихідні вебхуки для зовнішніх систем (ERP, BI).
+//
+// Подія записується в чергу webhook_delivery в тій самій транзакції, що й зміна даних, —
+// по запису на кожну підписку. Фонова доставка надсилає POST з JSON і підписом
+// HMAC-SHA256, невдалі спроби повторює з подвоєнням паузи.
+//
+// Заголовки запиту:
+//   X-GasStation-Event      — назва події
+//   X-GasStation-Delivery   — id доставки (однаковий для повторних спроб, для ідемпотентності)
+//   X-GasStation-Timestamp  — час надсилання (unix)
+//   X-GasStation-Signature  — "sha256=" + hex(HMAC(secret, "<timestamp>.<тіло>"))
+
+use std::time::Duration;
+
+use diesel::prelude::*;
+use diesel::SqliteConnection;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::models::{WebhookDelivery, WebhookSubscription};
+use crate::schema::{webhook_delivery, webhook_subscription};
+use crate::utils::{webhook_matches, webhook_retry_delay};
+
+pub const SALE_COMPLETED: &str = "sale.completed";
+pub const PRICE_CHANGED: &str = "price.changed";
+pub const TANK_REFILLED: &str = "tank.refilled";
+pub const STOCK_LOW: &str = "stock.low";
+
+/// Усі події, на які можна підписатися
+pub const EVENTS: [&str; 4] = [SALE_COMPLETED, PRICE_CHANGED, TANK_REFILLED, STOCK_LOW];
+
+// Доставку, взяту в роботу, інші не чіпають протягом цього часу
+const LEASE_SECS: i64 = 120;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const BATCH_SIZE: i64 = 50;
+
+#[derive(Debug)]
+pub enum DeliveryError {
+    Http(String),
+    Status(u16),
+}
+
+impl std::fmt::Display for DeliveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeliveryError::Http(e) => write!(f, "Помилка з'єднання: {}", e),
+            DeliveryError::Status(code) => write!(f, "Отримувач відповів {}", code),
+        }
+    }
+}
+
+impl std::error::Error for DeliveryError {}
+
+/// Підпис тіла запиту: hex(HMAC-SHA256(secret, "<timestamp>.<body>"))
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC приймає ключ будь-якої довжини");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub fn generate_secret() -> String {
+    let mut buf = [0u8; 32];
+    getrandom::fill(&mut buf).unwrap_or(());
+    buf.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Ставить подію в чергу для всіх увімкнених підписок на неї.
+/// Повертає кількість створених доставок.
+pub fn enqueue(conn: &mut SqliteConnection, event: &str, data: serde_json::Value) -> QueryResult<usize> {
+    let subscriptions: Vec<(i32, String)> = webhook_subscription::table
+        .filter(webhook_subscription::enabled.eq(true))
+        .select((webhook_subscription::id, webhook_subscription::events))
+        .load(conn)?;
+    let targets: Vec<i32> = subscriptions
+        .into_iter()
+        .filter(|(_, events)| webhook_matches(events, event))
+        .map(|(id, _)| id)
+        .collect();
+    if targets.is_empty() {
+        return Ok(0);
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let payload = serde_json::json!({
+        "event": event,
+        "created_at": now,
+        "data": data,
+    })
+    .to_string();
+    let rows: Vec<_> = targets
+        .iter()
+        .map(|id| {
+            (
+                webhook_delivery::subscription_id.eq(id),
+                webhook_delivery::event.eq(event),
+                webhook_delivery::payload.eq(&payload),
+                webhook_delivery::next_attempt_at.eq(now),
+                webhook_delivery::created_at.eq(now),
+            )
+        })
+        .collect();
+    diesel::insert_into(webhook_delivery::table).values(&rows).execute(conn)
+}
+
+/// Забирає доставки, час яких настав. Кожна отримує оренду на LEASE_SECS,
+/// тож паралельний запуск її не надішле вдруге.
+pub fn claim_due(
+    conn: &mut SqliteConnection,
+    now: i64,
+) -> QueryResult<Vec<(WebhookDelivery, WebhookSubscription)>> {
+    let due: Vec<(WebhookDelivery, WebhookSubscription)> = webhook_delivery::table
+        .inner_join(webhook_subscription::table)
+        .filter(webhook_delivery::status.eq("pending"))
+        .filter(webhook_delivery::next_attempt_at.le(now))
+        .order(webhook_delivery::id)
+        .limit(BATCH_SIZE)
+        .select((WebhookDelivery::as_select(), WebhookSubscription::as_select()))
+        .load(conn)?;
+
+    let mut claimed = Vec::new();
+    for (delivery, subscription) in due {
+        let taken = diesel::update(
+            webhook_delivery::table
+                .find(delivery.id)
+                .filter(webhook_delivery::next_attempt_at.eq(delivery.next_attempt_at)),
+        )
+        .set(webhook_delivery::next_attempt_at.eq(now + LEASE_SECS))
+        .execute(conn)?;
+        if taken == 1 {
+            claimed.push((delivery, subscription));
+        }
+    }
+    Ok(claimed)
+}
+
+/// Записує результат спроби: успіх, наступна спроба з паузою або остаточна помилка
+pub fn record_result(
+    conn: &mut SqliteConnection,
+    delivery: &WebhookDelivery,
+    result: &Result<u16, DeliveryError>,
+    now: i64,
+) -> QueryResult<()> {
+    let attempts = delivery.attempts + 1;
+    let target = webhook_delivery::table.find(delivery.id);
+    match result {
+        Ok(code) => diesel::update(target)
+            .set((
+                webhook_delivery::status.eq("delivered"),
+                webhook_delivery::attempts.eq(attempts),
+                webhook_delivery::last_status_code.eq(Some(*code as i32)),
+                webhook_delivery::last_error.eq(None::<String>),
+                webhook_delivery::delivered_at.eq(Some(now)),
+            ))
+            .execute(conn)?,
+        Err(e) => {
+            let code = match e {
+                DeliveryError::Status(code) => Some(*code as i32),
+                DeliveryError::Http(_) => None,
+            };
+            let (status, next) = match webhook_retry_delay(attempts) {
+                Some(delay) => ("pending", now + delay),
+                None => ("failed", now),
+            };
+            diesel::update(target)
+                .set((
+                    webhook_delivery::status.eq(status),
+                    webhook_delivery::attempts.eq(attempts),
+                    webhook_delivery::next_attempt_at.eq(next),
+                    webhook_delivery::last_status_code.eq(code),
+                    webhook_delivery::last_error.eq(Some(e.to_string())),
+                ))
+                .execute(conn)?
+        }
+    };
+    Ok(())
+}
+
+/// Надсилає одну доставку. Успіх — будь-яка відповідь 2xx.
+pub async fn send(
+    subscription: &WebhookSubscription,
+    delivery: &WebhookDelivery,
+) -> Result<u16, DeliveryError> {
+    let timestamp = chrono::Utc::now().timestamp();
+    let signature = sign(&subscription.secret, timestamp, &delivery.payload);
+
+    let response = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| DeliveryError::Http(e.to_string()))?
+        .post(&subscription.url)
+        .header("Content-Type", "application/json")
+        .header("X-GasStation-Event", &delivery.event)
+        .header("X-GasStation-Delivery", delivery.id.to_string())
+        .header("X-GasStation-Timestamp", timestamp.to_string())
+        .header("X-GasStation-Signature", format!("sha256={}", signature))
+        .body(delivery.payload.clone())
+        .send()
+        .await
+        .map_err(|e| DeliveryError::Http(e.to_string()))?;
+
+    let code = response.status().as_u16();
+    if response.status().is_success() {
+        Ok(code)
+    } else {
+        Err(DeliveryError::Status(code))
+    }
+}
+
+/// Надсилає всі доставки, час яких настав. Повертає (доставлено, невдалих спроб).
+pub async fn deliver_due() -> Result<(usize, usize), String> {
+    let now = chrono::Utc::now().timestamp();
+    let claimed = claim_due(&mut crate::db::connection(), now).map_err(|e| e.to_string())?;
+
+    let (mut delivered, mut failed) = (0, 0);
+    for (delivery, subscription) in &claimed {
+        let result = send(subscription, delivery).await;
+        match &result {
+            Ok(_) => delivered += 1,
+            Err(e) => {
+                println!("LOG: Webhook delivery {} to {} failed: {}", delivery.id, subscription.url, e);
+                failed += 1;
+            }
+        }
+        record_result(
+            &mut crate::db::connection(),
+            delivery,
+            &result,
+            chrono::Utc::now().timestamp(),
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok((delivered, failed))
+}